@@ -9,6 +9,7 @@ export enum Code {
 	Projects = 'errors.projects',
 	ProjectsGitAuth = 'errors.projects.git.auth',
 	ProjectsGitRemote = 'errors.projects.git.remote',
+	ProjectsGitNonFastForward = 'errors.projects.git.non_fast_forward',
 	ProjectHead = 'errors.projects.head',
 	ProjectConflict = 'errors.projects.conflict'
 }
@@ -15,7 +15,11 @@ export type KeyType =
 	| 'local'
 	| 'systemExecutable';
 export type LocalKey = {
-	local: { private_key_path: string };
+	local: {
+		private_key_path: string;
+		// Refers to the passphrase kept in the keychain, never the passphrase itself.
+		passphrase?: string;
+	};
 };
 
 export type Key = Exclude<KeyType, 'local'> | LocalKey;
@@ -83,6 +87,15 @@ export class ProjectService {
 		this.reload();
 	}
 
+	async setKeyPassphrase(projectId: string, passphrase: string | undefined) {
+		const project = plainToInstance(
+			Project,
+			await invoke('set_project_key_passphrase', { id: projectId, passphrase })
+		);
+		this.reload();
+		return project;
+	}
+
 	async add(path: string) {
 		const project = plainToInstance(Project, await invoke('add_project', { path }));
 		await this.reload();
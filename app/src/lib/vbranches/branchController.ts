@@ -52,16 +52,17 @@ export class BranchController {
 		message: string,
 		ownership: string | undefined = undefined,
 		runHooks = false
-	) {
+	): Promise<string> {
 		try {
-			await invoke<void>('commit_virtual_branch', {
+			const commitId = await invoke<string>('commit_virtual_branch', {
 				projectId: this.projectId,
 				branch,
 				message,
 				ownership,
-				runHooks: runHooks
+				options: { runHooks }
 			});
 			posthog.capture('Commit Successful');
+			return commitId;
 		} catch (err: any) {
 			showError('Failed to commit changes', err);
 			posthog.capture('Commit Failed', err);
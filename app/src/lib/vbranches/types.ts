@@ -25,6 +25,12 @@ export class Hunk {
 	changeType!: ChangeType;
 }
 
+export type FileStatus =
+	| { type: 'added' }
+	| { type: 'modified' }
+	| { type: 'deleted' }
+	| { type: 'renamed'; from: string };
+
 export type AnyFile = LocalFile | RemoteFile;
 
 export class LocalFile {
@@ -42,6 +48,7 @@ export class LocalFile {
 	content!: string;
 	binary!: boolean;
 	large!: boolean;
+	status!: FileStatus;
 
 	get filename(): string {
 		const parts = this.path.split('/');
@@ -69,12 +76,12 @@ export class LocalFile {
 	}
 }
 
+export type SkipReason = 'conflicted';
+
+// A file that is left out of the virtual branches, along with the reason why
 export class SkippedFile {
-	oldPath!: string | undefined;
-	newPath!: string | undefined;
-	binary!: boolean;
-	oldSizeBytes!: number;
-	newSizeBytes!: number;
+	path!: string;
+	reason!: SkipReason;
 }
 
 export class VirtualBranches {
@@ -111,6 +118,9 @@ export class Branch {
 	isMergeable!: Promise<boolean>;
 	@Transform((obj) => new Date(obj.value))
 	updatedAt!: Date;
+	// Last time the branch was committed to, had hunks moved or was renamed
+	@Transform((obj) => new Date(obj.value))
+	lastActivityAt!: Date;
 	// Indicates that branch is default target for new changes
 	selectedForChanges!: boolean;
 
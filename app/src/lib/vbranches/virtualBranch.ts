@@ -1,4 +1,4 @@
-import { Branch, VirtualBranches } from './types';
+import { Branch, VirtualBranches, type SkippedFile } from './types';
 import { invoke, listen } from '$lib/backend/ipc';
 import { observableToStore } from '$lib/rxjs/store';
 import * as toasts from '$lib/utils/toasts';
@@ -23,6 +23,7 @@ import { writable, type Readable } from 'svelte/store';
 
 export class VirtualBranchService {
 	branches$: Observable<Branch[] | undefined>;
+	skippedFiles$: Observable<SkippedFile[]>;
 	stashedBranches$: Observable<Branch[] | undefined>;
 	activeBranches$: Observable<Branch[] | undefined>;
 	branchesError = writable<any>();
@@ -31,22 +32,32 @@ export class VirtualBranchService {
 
 	activeBranches: Readable<Branch[] | undefined>;
 	activeBranchesError: Readable<any>;
+	skippedFiles: Readable<SkippedFile[]>;
 
 	constructor(projectId: string, gbBranchActive$: Observable<boolean>) {
-		this.branches$ = this.reload$.pipe(
+		const virtualBranches$ = this.reload$.pipe(
 			switchMap(() => gbBranchActive$),
 			switchMap((gbBranchActive) =>
 				gbBranchActive
 					? concat(
 							from(listVirtualBranches({ projectId })),
-							new Observable<Branch[]>((subscriber) => {
-								return subscribeToVirtualBranches(projectId, (branches) =>
-									subscriber.next(branches)
+							new Observable<VirtualBranches>((subscriber) => {
+								return subscribeToVirtualBranches(projectId, (virtualBranches) =>
+									subscriber.next(virtualBranches)
 								);
 							})
 						)
-					: of([])
+					: of(undefined)
 			),
+			shareReplay(1)
+		);
+
+		this.skippedFiles$ = virtualBranches$.pipe(
+			map((virtualBranches) => virtualBranches?.skippedFiles ?? [])
+		);
+
+		this.branches$ = virtualBranches$.pipe(
+			map((virtualBranches) => virtualBranches?.branches ?? []),
 			tap((branches) => {
 				branches.forEach((branch) => {
 					branch.files.sort((a) => (a.conflicted ? -1 : 0));
@@ -70,6 +81,7 @@ export class VirtualBranchService {
 		);
 
 		[this.activeBranches, this.activeBranchesError] = observableToStore(this.activeBranches$);
+		[this.skippedFiles] = observableToStore(this.skippedFiles$);
 	}
 
 	async reload() {
@@ -100,13 +112,15 @@ export class VirtualBranchService {
 	}
 }
 
-function subscribeToVirtualBranches(projectId: string, callback: (branches: Branch[]) => void) {
+function subscribeToVirtualBranches(
+	projectId: string,
+	callback: (virtualBranches: VirtualBranches) => void
+) {
 	return listen<any>(`project://${projectId}/virtual-branches`, (event) =>
-		callback(plainToInstance(VirtualBranches, event.payload).branches)
+		callback(plainToInstance(VirtualBranches, event.payload))
 	);
 }
 
-export async function listVirtualBranches(params: { projectId: string }): Promise<Branch[]> {
-	return plainToInstance(VirtualBranches, await invoke<any>('list_virtual_branches', params))
-		.branches;
+export async function listVirtualBranches(params: { projectId: string }): Promise<VirtualBranches> {
+	return plainToInstance(VirtualBranches, await invoke<any>('list_virtual_branches', params));
 }
@@ -91,7 +91,7 @@ impl Proxy {
         }
     }
 
-    async fn proxy_author(&self, author: Author) -> Author {
+    pub async fn proxy_author(&self, author: Author) -> Author {
         Author {
                 gravatar_url: self
                     .proxy(&author.gravatar_url)
@@ -132,11 +132,13 @@ pub enum Code {
     Branches,
     ProjectGitAuth,
     ProjectGitRemote,
+    ProjectGitNonFastForward,
     ProjectConflict,
     ProjectHead,
     Menu,
     PreCommitHook,
     CommitMsgHook,
+    PrePushHook,
 }
 
 impl std::fmt::Display for Code {
@@ -149,11 +151,13 @@ impl std::fmt::Display for Code {
             Code::Branches => "errors.branches",
             Code::ProjectGitAuth => "errors.projects.git.auth",
             Code::ProjectGitRemote => "errors.projects.git.remote",
+            Code::ProjectGitNonFastForward => "errors.projects.git.non_fast_forward",
             Code::ProjectHead => "errors.projects.head",
             Code::ProjectConflict => "errors.projects.conflict",
             //TODO: rename js side to be more precise what kind of hook error this is
             Code::PreCommitHook => "errors.hook",
             Code::CommitMsgHook => "errors.hooks.commit.msg",
+            Code::PrePushHook => "errors.hooks.pre.push",
         };
         f.write_str(code)
     }
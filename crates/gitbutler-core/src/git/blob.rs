@@ -6,7 +6,13 @@ impl<'a> From<git2::Blob<'a>> for Blob<'a> {
     }
 }
 
+use super::Oid;
+
 impl Blob<'_> {
+    pub fn id(&self) -> Oid {
+        self.0.id().into()
+    }
+
     pub fn content(&self) -> &[u8] {
         self.0.content()
     }
@@ -1,7 +1,7 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::error::{AnyhowContextExt, Code, Context, ErrorWithContext};
-use crate::{error, keys, project_repository, projects, users};
+use crate::{error, keys, project_repository, projects, secret, tokens, users};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SshCredential {
@@ -10,12 +10,14 @@ pub enum SshCredential {
         passphrase: Option<String>,
     },
     GitButlerKey(Box<keys::PrivateKey>),
+    Agent,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HttpsCredential {
     CredentialHelper { username: String, password: String },
     GitHubToken(String),
+    PersonalAccessToken { username: String, token: String },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -48,7 +50,19 @@ impl From<Credential> for git2::RemoteCallbacks<'_> {
             Credential::Ssh(SshCredential::GitButlerKey(key)) => {
                 remote_callbacks.credentials(move |url, _username_from_url, _allowed_types| {
                     tracing::info!("authenticating with {} using gitbutler's key", url);
-                    git2::Cred::ssh_key_from_memory("git", None, &key.to_string(), None)
+                    git2::Cred::ssh_key_from_memory(
+                        "git",
+                        Some(&key.public_key().to_string()),
+                        &key.to_string(),
+                        None,
+                    )
+                });
+            }
+            Credential::Ssh(SshCredential::Agent) => {
+                remote_callbacks.credentials(move |url, username_from_url, _allowed_types| {
+                    let username = username_from_url.unwrap_or("git");
+                    tracing::info!("authenticating with {url} as '{username}' using ssh agent");
+                    git2::Cred::ssh_key_from_agent(username)
                 });
             }
             Credential::Https(HttpsCredential::CredentialHelper { username, password }) => {
@@ -57,6 +71,14 @@ impl From<Credential> for git2::RemoteCallbacks<'_> {
                     git2::Cred::userpass_plaintext(&username, &password)
                 });
             }
+            Credential::Https(HttpsCredential::PersonalAccessToken { username, token }) => {
+                remote_callbacks.credentials(move |url, _username_from_url, _allowed_types| {
+                    tracing::info!(
+                        "authenticating with {url} as '{username}' using personal access token"
+                    );
+                    git2::Cred::userpass_plaintext(&username, &token)
+                });
+            }
             Credential::Https(HttpsCredential::GitHubToken(token)) => {
                 remote_callbacks.credentials(move |url, _username_from_url, _allowed_types| {
                     tracing::info!("authenticating with {url} using github token");
@@ -68,10 +90,34 @@ impl From<Credential> for git2::RemoteCallbacks<'_> {
     }
 }
 
+/// Tells if `error`, from authenticating with a [`SshCredential::Keyfile`], means that the key
+/// couldn't be decrypted. libssh2 reports every failure in the session, host keys and handshakes
+/// included, as a generic ssh error, so only its message tells a failed decryption apart.
+pub fn is_wrong_passphrase(error: &git2::Error) -> bool {
+    error.class() == git2::ErrorClass::Ssh && error.message().contains("Wrong passphrase")
+}
+
+// once connected, a key that can't be decrypted fails like any other ssh error, so the passphrase
+// of keys in the openssh format is checked up front. other formats are left to libssh2.
+fn check_passphrase(key_path: &Path, passphrase: Option<&str>) -> Result<(), HelpError> {
+    use resolve_path::PathResolveExt;
+    let Ok(key) = ssh_key::PrivateKey::read_openssh_file(&key_path.resolve()) else {
+        return Ok(());
+    };
+    if !key.is_encrypted() {
+        return Ok(());
+    }
+    match passphrase.map(|passphrase| key.decrypt(passphrase)) {
+        None | Some(Err(ssh_key::Error::Crypto)) => Err(HelpError::WrongPassphrase),
+        Some(_) => Ok(()),
+    }
+}
+
 #[derive(Clone)]
 pub struct Helper {
     keys: keys::Controller,
     users: users::Controller,
+    tokens: tokens::Controller,
     home_dir: Option<PathBuf>,
 }
 
@@ -81,6 +127,8 @@ pub enum HelpError {
     NoUrlSet,
     #[error("failed to convert url: {0}")]
     UrlConvertError(#[from] super::ConvertError),
+    #[error("wrong passphrase for private key")]
+    WrongPassphrase,
     #[error(transparent)]
     Git(#[from] super::Error),
     #[error(transparent)]
@@ -94,6 +142,10 @@ impl ErrorWithContext for HelpError {
                 error::Context::new_static(Code::ProjectGitRemote, "no url set for remote")
             }
             HelpError::UrlConvertError(_) => Code::ProjectGitRemote.into(),
+            HelpError::WrongPassphrase => error::Context::new_static(
+                Code::ProjectGitAuth,
+                "Wrong passphrase for the private key",
+            ),
             HelpError::Git(_) => return None,
             HelpError::Other(error) => return error.custom_context(),
         })
@@ -104,20 +156,23 @@ impl Helper {
     pub fn new(
         keys: keys::Controller,
         users: users::Controller,
+        tokens: tokens::Controller,
         home_dir: Option<PathBuf>,
     ) -> Self {
         Self {
             keys,
             users,
+            tokens,
             home_dir,
         }
     }
 
     pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Self {
         let keys = keys::Controller::from_path(&path);
-        let users = users::Controller::from_path(path);
+        let users = users::Controller::from_path(&path);
+        let tokens = tokens::Controller::from_path(path);
         let home_dir = std::env::var_os("HOME").map(PathBuf::from);
-        Self::new(keys, users, home_dir)
+        Self::new(keys, users, tokens, home_dir)
     }
 
     pub fn help<'a>(
@@ -134,7 +189,10 @@ impl Helper {
         }
 
         match &project_repository.project().preferred_key {
-            projects::AuthKey::Local { private_key_path } => {
+            projects::AuthKey::Local {
+                private_key_path,
+                passphrase,
+            } => {
                 let ssh_remote = if remote_url.scheme == super::Scheme::Ssh {
                     Ok(remote)
                 } else {
@@ -142,11 +200,18 @@ impl Helper {
                     project_repository.git_repository.remote_anonymous(&ssh_url)
                 }?;
 
+                let passphrase = passphrase
+                    .as_deref()
+                    .map(secret::retrieve)
+                    .transpose()?
+                    .flatten();
+                check_passphrase(private_key_path, passphrase.as_deref())?;
+
                 Ok(vec![(
                     ssh_remote,
                     vec![Credential::Ssh(SshCredential::Keyfile {
                         key_path: private_key_path.clone(),
-                        passphrase: None,
+                        passphrase,
                     })],
                 )])
             }
@@ -157,7 +222,8 @@ impl Helper {
                     let url = remote_url.as_https()?;
                     project_repository.git_repository.remote_anonymous(&url)
                 }?;
-                let flow = Self::https_flow(project_repository, &remote_url)?
+                let flow = self
+                    .https_flow(project_repository, &remote_url)?
                     .into_iter()
                     .map(Credential::Https)
                     .collect::<Vec<_>>();
@@ -196,11 +262,13 @@ impl Helper {
             project_repository.git_repository.remote_anonymous(&ssh_url)
         }?;
 
+        let mut flow = vec![];
+        if project_repository.project().use_ssh_agent.unwrap_or(false) {
+            flow.push(Credential::Ssh(SshCredential::Agent));
+        }
         let key = self.keys.get_or_create()?;
-        Ok(vec![(
-            ssh_remote,
-            vec![Credential::Ssh(SshCredential::GitButlerKey(Box::new(key)))],
-        )])
+        flow.push(Credential::Ssh(SshCredential::GitButlerKey(Box::new(key))));
+        Ok(vec![(ssh_remote, flow)])
     }
 
     fn default_flow<'a>(
@@ -236,7 +304,8 @@ impl Helper {
             super::Scheme::Https => {
                 let mut flow = vec![];
 
-                let https_flow = Self::https_flow(project_repository, &remote_url)?
+                let https_flow = self
+                    .https_flow(project_repository, &remote_url)?
                     .into_iter()
                     .map(Credential::Https)
                     .collect::<Vec<_>>();
@@ -247,7 +316,7 @@ impl Helper {
 
                 if let Ok(ssh_url) = remote_url.as_ssh() {
                     let ssh_flow = self
-                        .ssh_flow()?
+                        .ssh_flow(project_repository)?
                         .into_iter()
                         .map(Credential::Ssh)
                         .collect::<Vec<_>>();
@@ -267,7 +336,7 @@ impl Helper {
                 let mut flow = vec![];
 
                 let ssh_flow = self
-                    .ssh_flow()?
+                    .ssh_flow(project_repository)?
                     .into_iter()
                     .map(Credential::Ssh)
                     .collect::<Vec<_>>();
@@ -276,7 +345,8 @@ impl Helper {
                 }
 
                 if let Ok(https_url) = remote_url.as_https() {
-                    let https_flow = Self::https_flow(project_repository, &https_url)?
+                    let https_flow = self
+                        .https_flow(project_repository, &https_url)?
                         .into_iter()
                         .map(Credential::Https)
                         .collect::<Vec<_>>();
@@ -296,7 +366,8 @@ impl Helper {
                 let mut flow = vec![];
 
                 if let Ok(https_url) = remote_url.as_https() {
-                    let https_flow = Self::https_flow(project_repository, &https_url)?
+                    let https_flow = self
+                        .https_flow(project_repository, &https_url)?
                         .into_iter()
                         .map(Credential::Https)
                         .collect::<Vec<_>>();
@@ -313,7 +384,7 @@ impl Helper {
 
                 if let Ok(ssh_url) = remote_url.as_ssh() {
                     let ssh_flow = self
-                        .ssh_flow()?
+                        .ssh_flow(project_repository)?
                         .into_iter()
                         .map(Credential::Ssh)
                         .collect::<Vec<_>>();
@@ -333,11 +404,18 @@ impl Helper {
     }
 
     fn https_flow(
+        &self,
         project_repository: &project_repository::Repository,
         remote_url: &super::Url,
     ) -> Result<Vec<HttpsCredential>, HelpError> {
         let mut flow = vec![];
 
+        if let Some(host) = remote_url.host.as_deref() {
+            if let Some(tokens::Token { username, token }) = self.tokens.get_token(host)? {
+                flow.push(HttpsCredential::PersonalAccessToken { username, token });
+            }
+        }
+
         let mut helper = git2::CredentialHelper::new(&remote_url.to_string());
         let config = project_repository.git_repository.config()?;
         helper.config(&git2::Config::from(config));
@@ -348,8 +426,14 @@ impl Helper {
         Ok(flow)
     }
 
-    fn ssh_flow(&self) -> Result<Vec<SshCredential>, HelpError> {
+    fn ssh_flow(
+        &self,
+        project_repository: &project_repository::Repository,
+    ) -> Result<Vec<SshCredential>, HelpError> {
         let mut flow = vec![];
+        if project_repository.project().use_ssh_agent.unwrap_or(false) {
+            flow.push(SshCredential::Agent);
+        }
         if let Some(home_path) = self.home_dir.as_ref() {
             let id_rsa_path = home_path.join(".ssh").join("id_rsa");
             if id_rsa_path.exists() {
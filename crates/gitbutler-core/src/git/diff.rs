@@ -93,7 +93,8 @@ pub fn workdir(
         .context_lines(context_lines);
 
     let mut diff = repository.diff_tree_to_workdir(Some(&tree), Some(&mut diff_opts))?;
-    let (mut diff_opts, skipped_files) = without_large_files(50_000_000, &diff, diff_opts);
+    let (mut diff_opts, skipped_files) =
+        without_large_files(repository, LARGE_FILE_SIZE_BYTES, &diff, diff_opts)?;
     if !skipped_files.is_empty() {
         diff = repository.diff_tree_to_workdir(Some(&tree), Some(&mut diff_opts))?;
     }
@@ -121,17 +122,37 @@ pub fn trees(
         .context_lines(context_lines)
         .show_untracked_content(true);
 
-    let diff =
+    let mut diff =
         repository.diff_tree_to_tree(Some(old_tree), Some(new_tree), Some(&mut diff_opts))?;
-
-    hunks_by_filepath(repository, &diff)
+    let (mut diff_opts, skipped_files) =
+        without_large_files(repository, LARGE_FILE_SIZE_BYTES, &diff, diff_opts)?;
+    if !skipped_files.is_empty() {
+        diff =
+            repository.diff_tree_to_tree(Some(old_tree), Some(new_tree), Some(&mut diff_opts))?;
+    }
+    let diff_files = hunks_by_filepath(repository, &diff);
+    diff_files.map(|mut df| {
+        for (key, value) in skipped_files {
+            df.insert(key, value);
+        }
+        df
+    })
 }
 
+/// Files bigger than this are not diffed, see [`without_large_files`].
+pub const LARGE_FILE_SIZE_BYTES: u64 = 50_000_000;
+
+/// Leaves files larger than `size_limit_bytes` out of the diff options, returning them as skipped.
+///
+/// Instead of being diffed, each of them gets a single hunk that holds the id of the new blob,
+/// like the hunks of binary files, so that they can still be owned and committed. The blob of a
+/// file in the working directory is not written to the odb here.
 pub fn without_large_files(
+    repository: &Repository,
     size_limit_bytes: u64,
     diff: &git2::Diff,
     mut diff_opts: git2::DiffOptions,
-) -> (git2::DiffOptions, HashMap<path::PathBuf, FileDiff>) {
+) -> Result<(git2::DiffOptions, HashMap<path::PathBuf, FileDiff>)> {
     let mut skipped_files: HashMap<path::PathBuf, FileDiff> = HashMap::new();
     for delta in diff.deltas() {
         if delta.new_file().size() > size_limit_bytes {
@@ -141,7 +162,7 @@ pub fn without_large_files(
                     FileDiff {
                         old_path: delta.old_file().path().map(std::path::Path::to_path_buf),
                         new_path: delta.new_file().path().map(std::path::Path::to_path_buf),
-                        hunks: None,
+                        hunks: Some(vec![large_file_hunk(repository, &delta, path)?]),
                         skipped: true,
                         binary: true,
                         old_size_bytes: delta.old_file().size(),
@@ -155,7 +176,39 @@ pub fn without_large_files(
             }
         }
     }
-    (diff_opts, skipped_files)
+    Ok((diff_opts, skipped_files))
+}
+
+fn large_file_hunk(
+    repository: &Repository,
+    delta: &git2::DiffDelta,
+    path: &path::Path,
+) -> Result<GitHunk> {
+    let new_file = delta.new_file();
+    let git2_repository: &git2::Repository = repository.into();
+    let in_odb = new_file.is_valid_id()
+        && git2_repository
+            .odb()
+            .map(|odb| odb.exists(new_file.id()))
+            .unwrap_or(false);
+    let full_path = repository.workdir().map(|workdir| workdir.join(path));
+    let blob_id = match full_path {
+        // files in the working directory are only hashed, their blob is written once committed
+        Some(full_path) if !in_odb && full_path.exists() => {
+            git2::Oid::hash_file(git2::ObjectType::Blob, &full_path)
+                .with_context(|| format!("failed to hash {}", path.display()))?
+        }
+        _ => new_file.id(),
+    };
+    Ok(GitHunk {
+        old_start: 0,
+        old_lines: 0,
+        new_start: 0,
+        new_lines: 0,
+        diff: blob_id.to_string(),
+        binary: true,
+        change_type: delta.status().into(),
+    })
 }
 
 fn hunks_by_filepath(
@@ -409,7 +462,7 @@ pub fn diff_files_to_hunks(
 ) -> HashMap<path::PathBuf, Vec<git::diff::GitHunk>> {
     let mut file_hunks: HashMap<path::PathBuf, Vec<git::diff::GitHunk>> = HashMap::new();
     for (file_path, diff_file) in files {
-        if !diff_file.skipped {
+        if !diff_file.skipped || diff_file.hunks.is_some() {
             file_hunks.insert(
                 file_path.clone(),
                 diff_file.hunks.clone().unwrap_or_default(),
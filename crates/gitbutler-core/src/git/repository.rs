@@ -1,4 +1,8 @@
-use std::{io::Write, path::Path, str};
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process, str,
+};
 
 use git2::Submodule;
 use git2_hooks::HookResult;
@@ -107,6 +111,13 @@ impl Repository {
             .map_err(Into::into)
     }
 
+    /// Returns the number of commits `local` is ahead and behind `upstream`.
+    pub fn graph_ahead_behind(&self, local: Oid, upstream: Oid) -> Result<(usize, usize)> {
+        self.0
+            .graph_ahead_behind(local.into(), upstream.into())
+            .map_err(Into::into)
+    }
+
     pub fn merge_trees(
         &self,
         ancestor_tree: &Tree<'_>,
@@ -224,6 +235,12 @@ impl Repository {
         Ok(self.0.index()?.len())
     }
 
+    /// Returns the size of the blob with the given id, without loading its content.
+    pub fn blob_size(&self, id: Oid) -> Result<usize> {
+        let (size, _) = self.0.odb()?.read_header(id.into())?;
+        Ok(size)
+    }
+
     pub fn blob_path<P: AsRef<Path>>(&self, path: P) -> Result<Oid> {
         self.0
             .blob_path(path.as_ref())
@@ -464,6 +481,31 @@ impl Repository {
             .map_err(Into::into)
     }
 
+    /// Creates an annotated tag if `message` is given, a lightweight one otherwise.
+    pub fn tag(
+        &self,
+        name: &str,
+        target: Oid,
+        tagger: &Signature<'_>,
+        message: Option<&str>,
+        force: bool,
+    ) -> Result<Oid> {
+        let target = self.0.find_object(target.into(), None)?;
+        match message {
+            Some(message) => self.0.tag(name, &target, tagger.into(), message, force),
+            None => self.0.tag_lightweight(name, &target, force),
+        }
+        .map(Into::into)
+        .map_err(Into::into)
+    }
+
+    pub fn tag_names(&self) -> Result<Vec<String>> {
+        self.0
+            .tag_names(None)
+            .map(|names| names.iter().flatten().map(ToString::to_string).collect())
+            .map_err(Into::into)
+    }
+
     pub fn run_hook_pre_commit(&self) -> Result<HookResult> {
         let res = git2_hooks::hooks_pre_commit(&self.0, Some(&["../.husky"]))?;
         Ok(res)
@@ -474,9 +516,76 @@ impl Repository {
         Ok(res)
     }
 
-    pub fn run_hook_post_commit(&self) -> Result<()> {
-        git2_hooks::hooks_post_commit(&self.0, Some(&["../.husky"]))?;
-        Ok(())
+    pub fn run_hook_post_commit(&self) -> Result<HookResult> {
+        let res = git2_hooks::hooks_post_commit(&self.0, Some(&["../.husky"]))?;
+        Ok(res)
+    }
+
+    /// Runs the `pre-push` hook with the remote's name and url as arguments. `updates` is passed
+    /// on stdin and should hold one `<local ref> <local sha> <remote ref> <remote sha>` line per
+    /// pushed ref, as described in githooks(5).
+    pub fn run_hook_pre_push(
+        &self,
+        remote_name: &str,
+        remote_url: &str,
+        updates: &str,
+    ) -> Result<HookResult> {
+        let Some(hook) = self.find_hook("pre-push") else {
+            return Ok(HookResult::NoHookFound);
+        };
+
+        #[cfg(unix)]
+        let mut command = process::Command::new(&hook);
+        #[cfg(not(unix))]
+        let mut command = {
+            let mut command = process::Command::new("sh");
+            command.arg(&hook);
+            command
+        };
+        let mut child = command
+            .args([remote_name, remote_url])
+            .current_dir(self.0.workdir().unwrap_or_else(|| self.0.path()))
+            .stdin(process::Stdio::piped())
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::piped())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // hooks are free to exit without reading their input
+            if let Err(error) = stdin.write_all(updates.as_bytes()) {
+                if error.kind() != io::ErrorKind::BrokenPipe {
+                    return Err(error.into());
+                }
+            }
+        }
+
+        let output = child.wait_with_output()?;
+        if output.status.success() {
+            Ok(HookResult::Ok { hook })
+        } else {
+            Ok(HookResult::RunNotSuccessful {
+                code: output.status.code(),
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                hook,
+            })
+        }
+    }
+
+    // looks for a hook in the same places as the other hooks: `core.hooksPath` or `.git/hooks`,
+    // and `.husky`.
+    fn find_hook(&self, name: &str) -> Option<PathBuf> {
+        let workdir = self.0.workdir()?;
+        let hooks_dir = self
+            .0
+            .config()
+            .and_then(|config| config.get_path("core.hooksPath"))
+            .map(|hooks_path| workdir.join(hooks_path))
+            .unwrap_or_else(|_| self.0.path().join("hooks"));
+        [hooks_dir, workdir.join(".husky")]
+            .into_iter()
+            .map(|dir| dir.join(name))
+            .find(|hook| hook.is_file())
     }
 }
 
@@ -1,6 +1,6 @@
 use anyhow::Context;
 
-use super::{storage::Storage, PrivateKey};
+use super::{storage::Storage, KeyType, PrivateKey};
 
 #[derive(Clone)]
 pub struct Controller {
@@ -17,12 +17,12 @@ impl Controller {
     }
 
     pub fn get_or_create(&self) -> anyhow::Result<PrivateKey> {
-        if let Some(key) = self.storage.get().context("failed to get key")? {
-            Ok(key)
-        } else {
-            let key = PrivateKey::generate();
-            self.storage.create(&key).context("failed to save key")?;
-            Ok(key)
-        }
+        self.get_or_create_with(KeyType::default())
+    }
+
+    pub fn get_or_create_with(&self, key_type: KeyType) -> anyhow::Result<PrivateKey> {
+        self.storage
+            .get_or_create_with(key_type)
+            .context("failed to get or create key")
     }
 }
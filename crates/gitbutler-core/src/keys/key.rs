@@ -7,6 +7,14 @@ use ssh_key::{HashAlg, LineEnding, SshSig};
 #[derive(Debug, Clone, Eq)]
 pub struct PrivateKey(ssh_key::PrivateKey);
 
+/// The algorithm used when generating a [`PrivateKey`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    Rsa,
+    #[default]
+    Ed25519,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum SignError {
     #[error(transparent)]
@@ -18,6 +26,23 @@ impl PrivateKey {
         Self::default()
     }
 
+    pub fn generate_with(key_type: KeyType) -> Result<Self, ssh_key::Error> {
+        match key_type {
+            KeyType::Ed25519 => Ok(Self::default()),
+            KeyType::Rsa => {
+                let rsa_keypair = ssh_key::private::RsaKeypair::random(&mut OsRng, 3072)?;
+                Ok(Self(ssh_key::PrivateKey::from(rsa_keypair)))
+            }
+        }
+    }
+
+    pub fn key_type(&self) -> KeyType {
+        match self.0.algorithm() {
+            ssh_key::Algorithm::Rsa { .. } => KeyType::Rsa,
+            _ => KeyType::Ed25519,
+        }
+    }
+
     pub fn public_key(&self) -> PublicKey {
         PublicKey::from(self)
     }
@@ -1,4 +1,4 @@
-use super::PrivateKey;
+use super::{KeyType, PrivateKey};
 use crate::storage;
 
 #[derive(Clone)]
@@ -24,19 +24,41 @@ impl Storage {
     }
 
     pub fn get(&self) -> Result<Option<PrivateKey>, Error> {
+        self.get_with(KeyType::default())
+    }
+
+    pub fn get_with(&self, key_type: KeyType) -> Result<Option<PrivateKey>, Error> {
         self.storage
-            .read("keys/ed25519")
+            .read(key_path(key_type))
             .map_err(Error::Storage)
             .and_then(|s| s.map(|s| s.parse().map_err(Error::SSHKey)).transpose())
     }
 
     pub fn create(&self, key: &PrivateKey) -> Result<(), Error> {
+        let path = key_path(key.key_type());
         self.storage
-            .write("keys/ed25519", &key.to_string())
+            .write(path, &key.to_string())
             .map_err(Error::Storage)?;
         self.storage
-            .write("keys/ed25519.pub", &key.public_key().to_string())
+            .write(format!("{path}.pub"), &key.public_key().to_string())
             .map_err(Error::Storage)?;
         Ok(())
     }
+
+    pub fn get_or_create_with(&self, key_type: KeyType) -> Result<PrivateKey, Error> {
+        if let Some(key) = self.get_with(key_type)? {
+            Ok(key)
+        } else {
+            let key = PrivateKey::generate_with(key_type)?;
+            self.create(&key)?;
+            Ok(key)
+        }
+    }
+}
+
+fn key_path(key_type: KeyType) -> &'static str {
+    match key_type {
+        KeyType::Ed25519 => "keys/ed25519",
+        KeyType::Rsa => "keys/rsa",
+    }
 }
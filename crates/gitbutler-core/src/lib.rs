@@ -29,9 +29,11 @@ pub mod path;
 pub mod project_repository;
 pub mod projects;
 pub mod reader;
+pub mod secret;
 pub mod sessions;
 pub mod ssh;
 pub mod storage;
+pub mod tokens;
 pub mod types;
 pub mod users;
 pub mod virtual_branches;
@@ -10,8 +10,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use itertools::Itertools;
+use serde::Serialize;
 
 use super::Repository;
 use crate::git;
@@ -95,6 +96,57 @@ pub fn conflicting_files(repository: &Repository) -> Result<Vec<String>> {
     Ok(reader.lines().map_while(Result::ok).collect())
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictedFile {
+    pub path: PathBuf,
+    pub base: Option<git::Oid>,
+    pub ours: Option<git::Oid>,
+    pub theirs: Option<git::Oid>,
+}
+
+// conflicted files along with the blobs of each side, as recorded in the index.
+// sides that are missing from the index (e.g. added on one side only) are None.
+pub fn conflicted_files(repository: &Repository) -> Result<Vec<ConflictedFile>> {
+    let paths = conflicting_files(repository)?;
+    if paths.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let index = repository
+        .git_repository
+        .index()
+        .context("failed to open index")?;
+    let mut index_conflicts = index
+        .conflicts()
+        .context("failed to get index conflicts")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("failed to read index conflicts")?;
+
+    Ok(paths
+        .into_iter()
+        .map(PathBuf::from)
+        .map(|path| {
+            let conflict = index_conflicts
+                .iter()
+                .position(|conflict| {
+                    [&conflict.ancestor, &conflict.our, &conflict.their]
+                        .into_iter()
+                        .flatten()
+                        .any(|entry| entry.path == path.as_os_str().as_encoded_bytes())
+                })
+                .map(|position| index_conflicts.swap_remove(position));
+            let oid = |entry: Option<&git2::IndexEntry>| entry.map(|entry| entry.id.into());
+            ConflictedFile {
+                base: oid(conflict.as_ref().and_then(|c| c.ancestor.as_ref())),
+                ours: oid(conflict.as_ref().and_then(|c| c.our.as_ref())),
+                theirs: oid(conflict.as_ref().and_then(|c| c.their.as_ref())),
+                path,
+            }
+        })
+        .collect())
+}
+
 pub fn is_conflicting<P: AsRef<Path>>(repository: &Repository, path: Option<P>) -> Result<bool> {
     let conflicts_path = repository.git_repository.path().join("conflicts");
     if !conflicts_path.exists() {
@@ -485,7 +485,12 @@ impl Repository {
             .map_err(|e| RemoteError::Other(e.into()));
         }
 
-        let auth_flows = credentials.help(self, branch.remote())?;
+        let auth_flows = credentials
+            .help(self, branch.remote())
+            .map_err(|error| match error {
+                HelpError::WrongPassphrase => RemoteError::WrongPassphrase,
+                error => RemoteError::Help(error),
+            })?;
         for (mut remote, callbacks) in auth_flows {
             if let Some(url) = remote.url().context("failed to get remote url")? {
                 if !self.project.omit_certificate_check.unwrap_or(false) {
@@ -494,6 +499,12 @@ impl Repository {
             }
             let mut update_refs_error: Option<git2::Error> = None;
             for callback in callbacks {
+                let uses_keyfile = matches!(
+                    callback,
+                    git::credentials::Credential::Ssh(
+                        git::credentials::SshCredential::Keyfile { .. }
+                    )
+                );
                 let mut cbs: git2::RemoteCallbacks = callback.into();
                 if self.project.omit_certificate_check.unwrap_or(false) {
                     cbs.certificate_check(|_, _| Ok(git2::CertificateCheckStatus::CertificateOk));
@@ -521,6 +532,12 @@ impl Repository {
                         );
                         return Ok(());
                     }
+                    Err(git::Error::Auth(error))
+                        if uses_keyfile && git::credentials::is_wrong_passphrase(&error) =>
+                    {
+                        tracing::warn!(project_id = %self.project.id, ?error, "git push failed");
+                        return Err(RemoteError::WrongPassphrase);
+                    }
                     Err(git::Error::Auth(error) | git::Error::Http(error)) => {
                         tracing::warn!(project_id = %self.project.id, ?error, "git push failed");
                         continue;
@@ -529,7 +546,25 @@ impl Repository {
                         tracing::warn!(project_id = %self.project.id, ?error, "git push failed");
                         return Err(RemoteError::Network);
                     }
+                    Err(git::Error::Other(error))
+                        if error.code() == git2::ErrorCode::NotFastForward =>
+                    {
+                        tracing::warn!(project_id = %self.project.id, ?error, "git push failed");
+                        return Err(RemoteError::NonFastForward {
+                            branch: branch.branch().to_string(),
+                        });
+                    }
                     Err(error) => {
+                        // the remote rejected the update itself, e.g. because it was pushed to
+                        // in the meantime
+                        if update_refs_error
+                            .as_ref()
+                            .is_some_and(|e| is_non_fast_forward_status(e.message()))
+                        {
+                            return Err(RemoteError::NonFastForward {
+                                branch: branch.branch().to_string(),
+                            });
+                        }
                         if let Some(e) = update_refs_error.as_ref() {
                             return Err(RemoteError::Other(anyhow::anyhow!(e.to_string())));
                         }
@@ -575,7 +610,12 @@ impl Repository {
             .map_err(|e| RemoteError::Other(e.into()));
         }
 
-        let auth_flows = credentials.help(self, remote_name)?;
+        let auth_flows = credentials
+            .help(self, remote_name)
+            .map_err(|error| match error {
+                HelpError::WrongPassphrase => RemoteError::WrongPassphrase,
+                error => RemoteError::Help(error),
+            })?;
         for (mut remote, callbacks) in auth_flows {
             if let Some(url) = remote.url().context("failed to get remote url")? {
                 if !self.project.omit_certificate_check.unwrap_or(false) {
@@ -583,6 +623,12 @@ impl Repository {
                 }
             }
             for callback in callbacks {
+                let uses_keyfile = matches!(
+                    callback,
+                    git::credentials::Credential::Ssh(
+                        git::credentials::SshCredential::Keyfile { .. }
+                    )
+                );
                 let mut fetch_opts = git2::FetchOptions::new();
                 let mut cbs: git2::RemoteCallbacks = callback.into();
                 if self.project.omit_certificate_check.unwrap_or(false) {
@@ -596,6 +642,12 @@ impl Repository {
                         tracing::info!(project_id = %self.project.id, %refspec, "git fetched");
                         return Ok(());
                     }
+                    Err(git::Error::Auth(error))
+                        if uses_keyfile && git::credentials::is_wrong_passphrase(&error) =>
+                    {
+                        tracing::warn!(project_id = %self.project.id, ?error, "fetch failed");
+                        return Err(RemoteError::WrongPassphrase);
+                    }
                     Err(git::Error::Auth(error) | git::Error::Http(error)) => {
                         tracing::warn!(project_id = %self.project.id, ?error, "fetch failed");
                         continue;
@@ -621,10 +673,27 @@ pub enum RemoteError {
     Network,
     #[error("authentication failed")]
     Auth,
+    #[error("wrong passphrase for private key")]
+    WrongPassphrase,
+    #[error("{branch}: remote branch has commits that are not present locally")]
+    NonFastForward { branch: String },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
+// the statuses a remote reports for a ref update that is not a fast-forward
+fn is_non_fast_forward_status(status: &str) -> bool {
+    status.contains("non-fast-forward") || status.contains("fetch first")
+}
+
+impl RemoteError {
+    /// Whether trying again might succeed, as the failure was likely transient. Rejections by the
+    /// remote and authentication failures are not.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, RemoteError::Network)
+    }
+}
+
 impl ErrorWithContext for RemoteError {
     fn context(&self) -> Option<error::Context> {
         Some(match self {
@@ -636,6 +705,17 @@ impl ErrorWithContext for RemoteError {
                 Code::ProjectGitAuth,
                 "Project remote authentication error",
             ),
+            RemoteError::WrongPassphrase => error::Context::new_static(
+                Code::ProjectGitAuth,
+                "Wrong passphrase for the private key",
+            ),
+            RemoteError::NonFastForward { branch } => error::Context::new(
+                Code::ProjectGitNonFastForward,
+                format!(
+                    "The remote branch {} has commits that are not present locally, integrate them first",
+                    branch
+                ),
+            ),
             RemoteError::Other(error) => return error.custom_context(),
         })
     }
@@ -7,7 +7,7 @@ use anyhow::{Context, Result};
 use async_trait::async_trait;
 
 use super::{storage, storage::UpdateRequest, Project, ProjectId};
-use crate::{error, gb_repository, project_repository, users};
+use crate::{error, gb_repository, project_repository, secret, users};
 use crate::{
     error::{AnyhowContextExt, Code, Error, ErrorWithContext},
     projects::AuthKey,
@@ -150,6 +150,12 @@ impl Controller {
         #[cfg(windows)]
         let project = &project_owned;
 
+        let previous_key = self
+            .projects_storage
+            .get(&project.id)
+            .ok()
+            .map(|project| project.preferred_key);
+
         let updated = self
             .projects_storage
             .update(project)
@@ -158,6 +164,23 @@ impl Controller {
                 error => UpdateError::Other(error.into()),
             })?;
 
+        // a passphrase the new key doesn't refer to anymore is of no use to keep around
+        if let Some(AuthKey::Local {
+            passphrase: Some(handle),
+            ..
+        }) = previous_key
+        {
+            let still_referenced = matches!(
+                &updated.preferred_key,
+                AuthKey::Local { passphrase: Some(current), .. } if *current == handle
+            );
+            if !still_referenced {
+                if let Err(error) = secret::delete(&handle) {
+                    tracing::error!(project_id = %project.id, ?error, "failed to delete key passphrase");
+                }
+            }
+        }
+
         if let Some(watchers) = &self.watchers {
             if let Some(api) = &project.api {
                 if api.sync {
@@ -183,6 +206,51 @@ impl Controller {
         Ok(updated)
     }
 
+    /// Stores the passphrase of the project's local private key in the keychain, or removes it if
+    /// `passphrase` is `None`. The project only keeps a reference to it.
+    pub fn set_key_passphrase(
+        &self,
+        id: &ProjectId,
+        passphrase: Option<&str>,
+    ) -> Result<Project, UpdateError> {
+        let project = self.projects_storage.get(id).map_err(|error| match error {
+            super::storage::Error::NotFound => UpdateError::NotFound,
+            error => UpdateError::Other(error.into()),
+        })?;
+        let AuthKey::Local {
+            private_key_path, ..
+        } = project.preferred_key.clone()
+        else {
+            return Err(UpdateError::Validation(UpdateValidationError::KeyNotLocal));
+        };
+
+        let handle = project.key_passphrase_handle();
+        let passphrase = match passphrase {
+            Some(passphrase) => {
+                secret::persist(&handle, passphrase)?;
+                Some(handle)
+            }
+            None => {
+                secret::delete(&handle)?;
+                None
+            }
+        };
+
+        self.projects_storage
+            .update(&UpdateRequest {
+                id: *id,
+                preferred_key: Some(AuthKey::Local {
+                    private_key_path,
+                    passphrase,
+                }),
+                ..Default::default()
+            })
+            .map_err(|error| match error {
+                super::storage::Error::NotFound => UpdateError::NotFound,
+                error => UpdateError::Other(error.into()),
+            })
+    }
+
     pub fn get(&self, id: &ProjectId) -> Result<Project, GetError> {
         let project = self.projects_storage.get(id).map_err(|error| match error {
             super::storage::Error::NotFound => GetError::NotFound,
@@ -223,6 +291,28 @@ impl Controller {
         self.projects_storage.list().map_err(Into::into)
     }
 
+    /// Returns the ids of all projects that are in the middle of resolving conflicts.
+    ///
+    /// Only the conflict markers in each repository are looked at, projects that can't be
+    /// opened are skipped.
+    pub fn projects_in_conflict(&self) -> Result<Vec<ProjectId>, Error> {
+        let projects = self.projects_storage.list().map_err(anyhow::Error::from)?;
+        let mut ids = Vec::new();
+        for project in projects {
+            let project_repository = match project_repository::Repository::open(&project) {
+                Ok(project_repository) => project_repository,
+                Err(error) => {
+                    tracing::warn!(project_id = %project.id, ?error, "failed to open project repository");
+                    continue;
+                }
+            };
+            if project_repository::conflicts::is_resolving(&project_repository) {
+                ids.push(project.id);
+            }
+        }
+        Ok(ids)
+    }
+
     pub async fn delete(&self, id: &ProjectId) -> Result<(), Error> {
         let project = match self.projects_storage.get(id) {
             Ok(project) => Ok(project),
@@ -244,6 +334,10 @@ impl Controller {
             .purge(&project.id)
             .map_err(anyhow::Error::from)?;
 
+        if let Err(error) = secret::delete(&project.key_passphrase_handle()) {
+            tracing::error!(project_id = %id, ?error, "failed to delete key passphrase");
+        }
+
         if let Err(error) = std::fs::remove_dir_all(
             self.local_data_dir
                 .join("projects")
@@ -353,6 +447,12 @@ impl ErrorWithContext for UpdateError {
                     format!("'{}' is not a file", path.display()),
                 )
             }
+            UpdateError::Validation(UpdateValidationError::KeyNotLocal) => {
+                error::Context::new_static(
+                    Code::Projects,
+                    "Only a local private key can have a passphrase",
+                )
+            }
             UpdateError::NotFound => {
                 error::Context::new_static(Code::Projects, "Project not found")
             }
@@ -367,6 +467,8 @@ pub enum UpdateValidationError {
     KeyNotFound(PathBuf),
     #[error("{0} is not a file")]
     KeyNotFile(PathBuf),
+    #[error("the preferred key is not a local key")]
+    KeyNotLocal,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -18,6 +18,11 @@ pub enum AuthKey {
     GitCredentialsHelper,
     Local {
         private_key_path: path::PathBuf,
+        /// the handle of the passphrase that decrypts `private_key_path`, if it is
+        /// passphrase-protected. the passphrase itself is kept in the keychain, see
+        /// [`crate::secret`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        passphrase: Option<String>,
     },
 }
 
@@ -86,6 +91,15 @@ pub struct Project {
     pub use_diff_context: Option<bool>,
     #[serde(default)]
     pub use_toml_vbranches_state: Option<bool>,
+    /// if true, keys loaded into the user's ssh-agent are tried before gitbutler's own key
+    #[serde(default)]
+    pub use_ssh_agent: Option<bool>,
+    /// the remote virtual branches are pushed to, if not the remote of the base branch
+    #[serde(default)]
+    pub push_remote_name: Option<String>,
+    /// how often a push that failed with a network error is retried, if not the default
+    #[serde(default)]
+    pub push_retries: Option<usize>,
 }
 
 impl AsRef<Project> for Project {
@@ -116,4 +130,9 @@ impl Project {
     pub fn use_toml_vbranches_state(&self) -> bool {
         self.use_toml_vbranches_state.unwrap_or(false)
     }
+
+    /// Returns the handle under which the passphrase of a local private key is stored.
+    pub fn key_passphrase_handle(&self) -> String {
+        format!("projects/{}/private-key-passphrase", self.id)
+    }
 }
@@ -25,6 +25,9 @@ pub struct UpdateRequest {
     pub project_data_last_fetched: Option<project::FetchResult>,
     pub omit_certificate_check: Option<bool>,
     pub use_diff_context: Option<bool>,
+    pub use_ssh_agent: Option<bool>,
+    pub push_remote_name: Option<String>,
+    pub push_retries: Option<usize>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -132,6 +135,18 @@ impl Storage {
             project.use_diff_context = Some(use_diff_context);
         }
 
+        if let Some(use_ssh_agent) = update_request.use_ssh_agent {
+            project.use_ssh_agent = Some(use_ssh_agent);
+        }
+
+        if let Some(push_remote_name) = &update_request.push_remote_name {
+            project.push_remote_name = Some(push_remote_name.clone());
+        }
+
+        if let Some(push_retries) = update_request.push_retries {
+            project.push_retries = Some(push_retries);
+        }
+
         self.storage
             .write(PROJECTS_FILE, &serde_json::to_string_pretty(&projects)?)?;
 
@@ -1,14 +1,28 @@
 use std::{
-    fs, io, num,
-    path::{Path, PathBuf},
+    borrow,
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Read},
+    num,
+    ops::{self, ControlFlow},
+    path::{Component, Path, PathBuf},
     str,
     sync::Arc,
+    time::SystemTime,
 };
 
 use anyhow::{Context, Result};
 use serde::{ser::SerializeStruct, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::{git, lock, path::Normalize};
+use crate::{
+    git::{self, diff},
+    lock,
+    path::Normalize,
+};
+
+mod git_config;
+pub use git_config::GitConfigReader;
 
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum Error {
@@ -16,6 +30,14 @@ pub enum Error {
     NotFound,
     #[error("io error: {0}")]
     Io(Arc<io::Error>),
+    /// Reading the file at `path` on disk failed, e.g. for lack of permissions.
+    #[error("{}: {source}", .path.display())]
+    IoAt {
+        path: PathBuf,
+        source: Arc<io::Error>,
+    },
+    #[error("{}: path is outside of the reader's root", .0.display())]
+    OutsideRoot(PathBuf),
     #[error(transparent)]
     From(FromError),
 }
@@ -32,26 +54,79 @@ impl From<FromError> for Error {
     }
 }
 
+impl Error {
+    // files that disappear while they are read are still not found
+    fn io_at(path: &Path, error: io::Error) -> Self {
+        if error.kind() == io::ErrorKind::NotFound {
+            Error::NotFound
+        } else {
+            Error::IoAt {
+                path: path.to_path_buf(),
+                source: Arc::new(error),
+            }
+        }
+    }
+}
+
 pub enum Reader<'reader> {
     Filesystem(FilesystemReader),
     Commit(CommitReader<'reader>),
     Prefixed(PrefixedReader<'reader>),
+    Overlay(OverlayReader<'reader>),
 }
 
 impl<'reader> Reader<'reader> {
+    /// Reads from the directory `root` on disk, usually a working directory. Bare repositories
+    /// have none, so they can only be read from with [`Self::from_commit`] and friends.
     pub fn open<P: AsRef<Path>>(root: P) -> Result<Self, io::Error> {
         FilesystemReader::open(root).map(Reader::Filesystem)
     }
 
+    /// Like [`Self::open`], but also leaves `ignored` out when listing files.
+    /// See [`FilesystemReader::with_ignored`].
+    pub fn open_with_ignored<P: AsRef<Path>>(
+        root: P,
+        ignored: Vec<PathBuf>,
+    ) -> Result<Self, io::Error> {
+        FilesystemReader::open(root)
+            .map(|reader| reader.with_ignored(ignored))
+            .map(Reader::Filesystem)
+    }
+
+    /// Reads relative to `prefix`. Paths that could lead outside of it, such as ones with `..`
+    /// or absolute ones, are refused with [`Error::OutsideRoot`].
     pub fn sub<P: AsRef<Path>>(&'reader self, prefix: P) -> Self {
         Reader::Prefixed(PrefixedReader::new(self, prefix))
     }
 
+    /// Reads from `top` where a file exists there, and from `bottom` otherwise, e.g. to see the
+    /// working directory on top of a commit.
+    ///
+    /// Files deleted in `top` are still read from `bottom`, as deletions are not tracked.
+    pub fn overlay(top: Reader<'reader>, bottom: Reader<'reader>) -> Self {
+        Reader::Overlay(OverlayReader {
+            top: Box::new(top),
+            bottom: Box::new(bottom),
+        })
+    }
+
     pub fn commit_id(&self) -> Option<git::Oid> {
         match self {
             Reader::Filesystem(_) => None,
             Reader::Commit(reader) => Some(reader.get_commit_oid()),
             Reader::Prefixed(reader) => reader.reader.commit_id(),
+            Reader::Overlay(_) => None,
+        }
+    }
+
+    /// Returns a reader over the `n`th parent of the commit this reader reads from. Fails with
+    /// [`Error::NotFound`] if there is no such parent, or if this doesn't read from a commit.
+    pub fn parent(&self, n: usize) -> Result<Reader<'reader>, Error> {
+        match self {
+            Reader::Commit(reader) => reader.parent(n).map(Reader::Commit),
+            Reader::Filesystem(_) | Reader::Prefixed(_) | Reader::Overlay(_) => {
+                Err(Error::NotFound)
+            }
         }
     }
 
@@ -62,11 +137,45 @@ impl<'reader> Reader<'reader> {
         Ok(Reader::Commit(CommitReader::new(repository, commit)?))
     }
 
+    /// Reads from the commit `reference` points to, peeling tags and annotated tags along the
+    /// way. Fails if the reference doesn't lead to a commit, e.g. for a tag of a tree.
+    pub fn from_reference(
+        repository: &'reader git::Repository,
+        reference: &git::Reference<'reader>,
+    ) -> Result<Self> {
+        let commit = reference.peel_to_commit().with_context(|| {
+            format!(
+                "reference {} does not point to a commit",
+                String::from_utf8_lossy(reference.name_bytes())
+            )
+        })?;
+        Self::from_commit(repository, &commit)
+    }
+
+    /// Reads from the tree of the `index`th stash entry, with `0` being the most recent one.
+    ///
+    /// Fails with [`Error::NotFound`] if there is no such stash entry.
+    pub fn from_stash(repository: &'reader git::Repository, index: usize) -> Result<Self> {
+        let stash_oid = {
+            let git2_repository: &git2::Repository = repository.into();
+            let reflog = git2_repository
+                .reflog("refs/stash")
+                .context("failed to read stash reflog")?;
+            let entry = reflog.get(index).ok_or(Error::NotFound)?;
+            entry.id_new()
+        };
+        let commit = repository
+            .find_commit(stash_oid.into())
+            .context(format!("{}: stash commit not found", stash_oid))?;
+        Self::from_commit(repository, &commit)
+    }
+
     pub fn exists<P: AsRef<Path>>(&self, file_path: P) -> Result<bool, io::Error> {
         match self {
             Reader::Filesystem(reader) => reader.exists(file_path),
             Reader::Commit(reader) => Ok(reader.exists(file_path)),
             Reader::Prefixed(reader) => reader.exists(file_path),
+            Reader::Overlay(reader) => reader.exists(file_path),
         }
     }
 
@@ -77,6 +186,59 @@ impl<'reader> Reader<'reader> {
             .expect("batch should return at least one result")
     }
 
+    /// Like [`Self::read`], but when reading from a commit, a file whose path only differs in
+    /// case is read if there is no exact match, like case-insensitive filesystems do.
+    ///
+    /// Paths without an exact match cost a walk of the commit's tree, though only directories
+    /// that could contain the file are entered, so prefer [`Self::read`] where case is known.
+    /// Filesystem readers read `path` as is, leaving case to the filesystem.
+    pub fn read_case_insensitive<P: AsRef<Path>>(&self, path: P) -> Result<Content, Error> {
+        match self {
+            Reader::Filesystem(_) => self.read(path),
+            Reader::Commit(reader) => reader.read_case_insensitive(path.normalize()),
+            Reader::Prefixed(reader) => reader.read_case_insensitive(path),
+            Reader::Overlay(reader) => reader.read_case_insensitive(path),
+        }
+    }
+
+    /// Like [`Self::read`], but also tells where the content came from, following prefixes and
+    /// overlays down to the reader that had the file.
+    pub fn read_located<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(Content, ResolvedSource), Error> {
+        match self {
+            Reader::Filesystem(reader) => reader.read_located(path),
+            Reader::Commit(reader) => {
+                let path = path.normalize();
+                let content = reader.read(&path)?;
+                Ok((
+                    content,
+                    ResolvedSource::Commit(reader.get_commit_oid(), path),
+                ))
+            }
+            Reader::Prefixed(reader) => reader.read_located(path),
+            Reader::Overlay(reader) => reader.read_located(path),
+        }
+    }
+
+    /// Reads the file at `path` and converts it to `T`. Conversion errors are wrapped in
+    /// [`FromError::WithPath`] so that they tell which file was bad.
+    pub fn read_as<T, P: AsRef<Path>>(&self, path: P) -> Result<T, Error>
+    where
+        T: TryFrom<Content, Error = FromError>,
+    {
+        let path = path.as_ref();
+        let content = self.read(path)?;
+        T::try_from(content).map_err(|source| {
+            FromError::WithPath {
+                path: path.to_path_buf(),
+                source: Box::new(source),
+            }
+            .into()
+        })
+    }
+
     pub fn batch<P: AsRef<Path>>(
         &self,
         paths: &[P],
@@ -90,8 +252,7 @@ impl<'reader> Reader<'reader> {
                         if !path.exists() {
                             return Err(Error::NotFound);
                         }
-                        let content = Content::read_from_file(&path)?;
-                        Ok(content)
+                        Content::read_from_file(&path).map_err(|error| Error::io_at(&path, error))
                     })
                     .collect()
             }),
@@ -100,41 +261,303 @@ impl<'reader> Reader<'reader> {
                 .map(|path| reader.read(path.normalize()))
                 .collect()),
             Reader::Prefixed(reader) => reader.batch(paths),
+            Reader::Overlay(reader) => reader.batch(paths),
         }
     }
 
+    /// Reads all of `paths`, returning each of them along with its result.
+    ///
+    /// Commit readers resolve every path in a single walk of the tree instead of looking each
+    /// one up separately.
+    pub fn read_many(&self, paths: &[PathBuf]) -> Vec<(PathBuf, Result<Content, Error>)> {
+        match self {
+            Reader::Filesystem(_) => {
+                let results = match self.batch(paths) {
+                    Ok(results) => results,
+                    Err(error) => {
+                        let error = Error::from(error);
+                        return paths
+                            .iter()
+                            .map(|path| (path.clone(), Err(error.clone())))
+                            .collect();
+                    }
+                };
+                paths.iter().cloned().zip(results).collect()
+            }
+            Reader::Commit(reader) => reader.read_many(paths),
+            Reader::Prefixed(reader) => reader.read_many(paths),
+            Reader::Overlay(reader) => reader.read_many(paths),
+        }
+    }
+
+    /// Returns the git file mode (e.g. `0o100755` for executables) of the file at `path`.
+    ///
+    /// Only readers backed by a commit know about file modes.
+    pub fn file_mode<P: AsRef<Path>>(&self, path: P) -> Result<i32, Error> {
+        match self {
+            Reader::Filesystem(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "file modes are only available when reading from a commit",
+            )
+            .into()),
+            Reader::Commit(reader) => reader.file_mode(path),
+            Reader::Prefixed(reader) => reader.file_mode(path),
+            Reader::Overlay(reader) => reader.file_mode(path),
+        }
+    }
+
+    /// Returns the modification time of the file at `path`.
+    ///
+    /// Files read from a commit never change, so they have no modification time and `None` is
+    /// returned for them.
+    pub fn mtime<P: AsRef<Path>>(&self, path: P) -> Result<Option<SystemTime>, Error> {
+        match self {
+            Reader::Filesystem(reader) => reader.mtime(path).map(Some),
+            Reader::Commit(reader) => {
+                if reader.exists(path) {
+                    Ok(None)
+                } else {
+                    Err(Error::NotFound)
+                }
+            }
+            Reader::Prefixed(reader) => reader.mtime(path),
+            Reader::Overlay(reader) => reader.mtime(path),
+        }
+    }
+
+    /// Reads at most `max_bytes` from the beginning of the file at `path`. Files on disk are read
+    /// only up to `max_bytes`, while a blob of a commit is loaded in full and then truncated. The
+    /// prefix is never considered [`Content::Large`]. The hash of binary content read from a
+    /// commit is the id of the whole blob, elsewhere it only covers the prefix.
+    pub fn read_prefix<P: AsRef<Path>>(&self, path: P, max_bytes: usize) -> Result<Content, Error> {
+        match self {
+            Reader::Filesystem(reader) => reader.read_prefix(path, max_bytes),
+            Reader::Commit(reader) => reader.read_prefix(path.normalize(), max_bytes),
+            Reader::Prefixed(reader) => reader.read_prefix(path, max_bytes),
+            Reader::Overlay(reader) => reader.read_prefix(path, max_bytes),
+        }
+    }
+
+    /// Lists the files under `dir_path`, relative to it, sorted by path.
     pub fn list_files<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<PathBuf>> {
         match self {
             Reader::Filesystem(reader) => reader.list_files(dir_path.as_ref()),
             Reader::Commit(reader) => reader.list_files(dir_path.as_ref()),
             Reader::Prefixed(reader) => reader.list_files(dir_path.as_ref()),
+            Reader::Overlay(reader) => reader.list_files(dir_path.as_ref()),
+        }
+    }
+
+    /// Lists at most `limit` files under `dir_path`, skipping the first `offset` of them. The
+    /// returned flag is `true` if there are more files after the page. Pages follow the order of
+    /// [`Reader::list_files`], so consecutive pages don't overlap.
+    pub fn list_files_page<P: AsRef<Path>>(
+        &self,
+        dir_path: P,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<PathBuf>, bool)> {
+        let files = self.list_files(dir_path)?;
+        Ok(page(files, offset, limit))
+    }
+
+    /// Returns a hash of everything under `dir_path` that changes whenever anything under it
+    /// changes, without reading the files. For a commit this is the id of the subtree, on disk
+    /// it covers the path, modification time and size of every file. Hashes are only comparable
+    /// between readers of the same kind.
+    pub fn tree_hash<P: AsRef<Path>>(&self, dir_path: P) -> Result<String, Error> {
+        match self {
+            Reader::Filesystem(reader) => reader.tree_hash(dir_path.as_ref()),
+            Reader::Commit(reader) => reader.tree_hash(dir_path.normalize()),
+            Reader::Prefixed(reader) => reader.tree_hash(dir_path),
+            Reader::Overlay(reader) => reader.tree_hash(dir_path.as_ref()),
         }
     }
+
+    /// Calls `visit` with every file under `dir_path`, relative to it, without collecting the
+    /// paths first. Returning `ControlFlow::Break` from `visit` ends the walk right away.
+    pub fn walk<P: AsRef<Path>>(
+        &self,
+        dir_path: P,
+        mut visit: impl FnMut(&Path) -> ControlFlow<()>,
+    ) -> Result<()> {
+        self.walk_dyn(dir_path.as_ref(), &mut visit)?;
+        Ok(())
+    }
+
+    fn walk_dyn(
+        &self,
+        dir_path: &Path,
+        visit: &mut dyn FnMut(&Path) -> ControlFlow<()>,
+    ) -> Result<ControlFlow<()>> {
+        match self {
+            Reader::Filesystem(reader) => reader.walk(dir_path, visit),
+            Reader::Commit(reader) => reader.walk(dir_path, visit),
+            Reader::Prefixed(reader) => reader.walk(dir_path, visit),
+            Reader::Overlay(reader) => reader.walk(dir_path, visit),
+        }
+    }
+}
+
+fn page(files: Vec<PathBuf>, offset: usize, limit: usize) -> (Vec<PathBuf>, bool) {
+    let has_more = files.len() > offset.saturating_add(limit);
+    let files = files.into_iter().skip(offset).take(limit).collect();
+    (files, has_more)
+}
+
+/// Where [`Reader::read_located`] found a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedSource {
+    /// The file on disk, joined to the root the reader was opened with.
+    Disk(PathBuf),
+    /// The path of the file in the tree of the commit.
+    Commit(git::Oid, PathBuf),
 }
 
-pub struct FilesystemReader(lock::Dir);
+/// Reads from a directory on disk. Not available for bare repositories, which have no working
+/// directory; use a [`CommitReader`] for those.
+pub struct FilesystemReader {
+    dir: lock::Dir,
+    // directories that are left out when listing files, in addition to .git
+    ignored: Vec<PathBuf>,
+}
 
 impl FilesystemReader {
     fn open<P: AsRef<Path>>(root: P) -> Result<Self, io::Error> {
-        lock::Dir::new(root).map(Self)
+        lock::Dir::new(root).map(|dir| Self {
+            dir,
+            ignored: vec![],
+        })
+    }
+
+    /// Leaves `dirs` out when listing files, in addition to `.git`. Like `.git`, they are
+    /// relative to the listed directory.
+    pub fn with_ignored(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.ignored.extend(dirs);
+        self
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        path.starts_with(".git") || self.ignored.iter().any(|dir| path.starts_with(dir))
     }
 
     fn exists<P: AsRef<Path>>(&self, path: P) -> Result<bool, io::Error> {
-        let exists = self.0.batch(|root| root.join(path.as_ref()).exists())?;
+        let exists = self.dir.batch(|root| root.join(path.as_ref()).exists())?;
         Ok(exists)
     }
 
     fn batch<R>(&self, action: impl FnOnce(&Path) -> R) -> Result<R, io::Error> {
-        self.0.batch(action)
+        self.dir.batch(action)
+    }
+
+    fn read_located<P: AsRef<Path>>(&self, path: P) -> Result<(Content, ResolvedSource), Error> {
+        self.dir.batch(|root| {
+            let path = root.join(path.as_ref());
+            if !path.exists() {
+                return Err(Error::NotFound);
+            }
+            let content =
+                Content::read_from_file(&path).map_err(|error| Error::io_at(&path, error))?;
+            Ok((content, ResolvedSource::Disk(path)))
+        })?
+    }
+
+    fn read_prefix<P: AsRef<Path>>(&self, path: P, max_bytes: usize) -> Result<Content, Error> {
+        self.dir.batch(|root| {
+            let path = root.join(path.as_ref());
+            if !path.exists() {
+                return Err(Error::NotFound);
+            }
+            let mut prefix = Vec::with_capacity(max_bytes);
+            fs::File::open(&path)
+                .and_then(|file| file.take(max_bytes as u64).read_to_end(&mut prefix))
+                .map_err(|error| Error::io_at(&path, error))?;
+            Ok(Content::from_prefix(&prefix))
+        })?
+    }
+
+    fn mtime<P: AsRef<Path>>(&self, path: P) -> Result<SystemTime, Error> {
+        self.dir
+            .batch(|root| match fs::metadata(root.join(path.as_ref())) {
+                Ok(metadata) => Ok(metadata.modified()?),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Err(Error::NotFound),
+                Err(error) => Err(error.into()),
+            })?
     }
 
     fn list_files<P: AsRef<Path>>(&self, path: P) -> Result<Vec<PathBuf>> {
         let path = path.as_ref();
-        self.0
-            .batch(|root| crate::fs::list_files(root.join(path).as_path(), &[Path::new(".git")]))?
+        let ignored = std::iter::once(Path::new(".git"))
+            .chain(self.ignored.iter().map(PathBuf::as_path))
+            .collect::<Vec<_>>();
+        self.dir
+            .batch(|root| crate::fs::list_files(root.join(path).as_path(), &ignored))?
+    }
+
+    fn tree_hash(&self, dir_path: &Path) -> Result<String, Error> {
+        self.dir.batch(|root| {
+            let dir_path = root.join(dir_path);
+            if !dir_path.is_dir() {
+                return Err(Error::NotFound);
+            }
+            let mut hasher = Sha256::new();
+            for entry in walkdir::WalkDir::new(&dir_path).sort_by_file_name() {
+                let entry = entry.map_err(io::Error::from)?;
+                if entry.file_type().is_dir() {
+                    continue;
+                }
+                let path = entry
+                    .path()
+                    .strip_prefix(&dir_path)
+                    .expect("walked paths are under the walked directory");
+                if self.is_ignored(path) {
+                    continue;
+                }
+                let metadata = entry.metadata().map_err(io::Error::from)?;
+                let mtime = metadata
+                    .modified()?
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default();
+                hasher.update(path.to_string_lossy().as_bytes());
+                // separates the path from the next one, which could otherwise be its continuation
+                hasher.update([0]);
+                hasher.update(mtime.as_nanos().to_le_bytes());
+                hasher.update(metadata.len().to_le_bytes());
+            }
+            Ok(format!("{:x}", hasher.finalize()))
+        })?
+    }
+
+    fn walk(
+        &self,
+        dir_path: &Path,
+        visit: &mut dyn FnMut(&Path) -> ControlFlow<()>,
+    ) -> Result<ControlFlow<()>> {
+        self.dir.batch(|root| -> Result<ControlFlow<()>> {
+            let dir_path = root.join(dir_path);
+            if !dir_path.exists() {
+                return Ok(ControlFlow::Continue(()));
+            }
+            for entry in walkdir::WalkDir::new(&dir_path).sort_by_file_name() {
+                let entry = entry?;
+                if entry.file_type().is_dir() {
+                    continue;
+                }
+                let path = entry.path().strip_prefix(&dir_path)?;
+                if self.is_ignored(path) {
+                    continue;
+                }
+                if visit(path).is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+            Ok(ControlFlow::Continue(()))
+        })?
     }
 }
 
+/// Reads from the tree of a commit, going through the object database only. It never touches the
+/// working directory, so it works the same for bare repositories.
 pub struct CommitReader<'reader> {
     repository: &'reader git::Repository,
     commit_oid: git::Oid,
@@ -160,6 +583,28 @@ impl<'reader> CommitReader<'reader> {
         self.commit_oid
     }
 
+    /// Returns a reader over the `n`th parent of the commit, e.g. to read what a file looked like
+    /// before the commit changed it. Fails with [`Error::NotFound`] if there is no such parent.
+    pub fn parent(&self, n: usize) -> Result<CommitReader<'reader>, Error> {
+        let to_error = |error: git::Error| {
+            Error::from(io::Error::new(io::ErrorKind::Other, error.to_string()))
+        };
+        let commit = self
+            .repository
+            .find_commit(self.commit_oid)
+            .map_err(to_error)?;
+        if n >= commit.parent_count() {
+            return Err(Error::NotFound);
+        }
+        let parent = commit.parent(n).map_err(to_error)?;
+        let tree = parent.tree().map_err(to_error)?;
+        Ok(CommitReader {
+            repository: self.repository,
+            commit_oid: parent.id(),
+            tree,
+        })
+    }
+
     fn read<P: AsRef<Path>>(&self, path: P) -> Result<Content, Error> {
         let path = path.as_ref();
         let entry = match self
@@ -177,19 +622,149 @@ impl<'reader> CommitReader<'reader> {
         Ok(Content::from(&blob))
     }
 
+    fn read_case_insensitive(&self, path: PathBuf) -> Result<Content, Error> {
+        match self.read(&path) {
+            Err(Error::NotFound) => {}
+            result => return result,
+        }
+
+        let lowercase = |path: &Path| PathBuf::from(path.to_string_lossy().to_lowercase());
+        let wanted = lowercase(&path);
+        let mut found = None;
+        let walked = self.tree.walk(|root, entry| {
+            let Some(name) = entry.name() else {
+                return git::TreeWalkResult::Continue;
+            };
+            let entry_path = lowercase(&Path::new(root).join(name));
+
+            if entry.kind() == Some(git2::ObjectType::Tree) {
+                return if wanted.starts_with(&entry_path) {
+                    git::TreeWalkResult::Continue
+                } else {
+                    git::TreeWalkResult::Skip
+                };
+            }
+
+            if entry_path == wanted {
+                found = Some(entry.id());
+                git::TreeWalkResult::Stop
+            } else {
+                git::TreeWalkResult::Continue
+            }
+        });
+
+        match found {
+            Some(oid) => match self.repository.find_blob(oid) {
+                Ok(blob) => Ok(Content::from(&blob)),
+                Err(_) => Err(Error::NotFound),
+            },
+            // stopping the walk early is reported as an error, so only a walk that didn't find
+            // anything can have failed
+            None => match walked {
+                Ok(()) => Err(Error::NotFound),
+                Err(error) => Err(io::Error::new(io::ErrorKind::Other, error.to_string()).into()),
+            },
+        }
+    }
+
+    fn read_many(&self, paths: &[PathBuf]) -> Vec<(PathBuf, Result<Content, Error>)> {
+        let mut oids: HashMap<PathBuf, Option<git::Oid>> =
+            paths.iter().map(|path| (path.normalize(), None)).collect();
+        let mut remaining = oids.len();
+        let walked = self.tree.walk(|root, entry| {
+            let Some(name) = entry.name() else {
+                return git::TreeWalkResult::Continue;
+            };
+            let entry_path = Path::new(root).join(name);
+
+            if entry.kind() == Some(git2::ObjectType::Tree) {
+                // don't descend into trees that none of the requested paths live in
+                return if oids.keys().any(|path| path.starts_with(&entry_path)) {
+                    git::TreeWalkResult::Continue
+                } else {
+                    git::TreeWalkResult::Skip
+                };
+            }
+
+            if let Some(oid @ None) = oids.get_mut(&entry_path) {
+                *oid = Some(entry.id());
+                remaining -= 1;
+                if remaining == 0 {
+                    return git::TreeWalkResult::Stop;
+                }
+            }
+
+            git::TreeWalkResult::Continue
+        });
+
+        // stopping the walk early is reported as an error, so only fall back to reading the
+        // paths one by one if the walk failed before finding everything.
+        if walked.is_err() && remaining > 0 {
+            return paths
+                .iter()
+                .map(|path| (path.clone(), self.read(path.normalize())))
+                .collect();
+        }
+
+        paths
+            .iter()
+            .map(|path| {
+                let content = match oids.get(&path.normalize()) {
+                    Some(Some(oid)) => match self.repository.find_blob(*oid) {
+                        Ok(blob) => Ok(Content::from(&blob)),
+                        Err(_) => Err(Error::NotFound),
+                    },
+                    _ => Err(Error::NotFound),
+                };
+                (path.clone(), content)
+            })
+            .collect()
+    }
+
+    pub fn file_mode<P: AsRef<Path>>(&self, path: P) -> Result<i32, Error> {
+        match self.tree.get_path(path) {
+            Ok(entry) => Ok(entry.filemode()),
+            Err(_) => Err(Error::NotFound),
+        }
+    }
+
+    fn read_prefix<P: AsRef<Path>>(&self, path: P, max_bytes: usize) -> Result<Content, Error> {
+        let path = path.as_ref();
+        let entry = match self.tree.get_path(path) {
+            Ok(entry) => entry,
+            Err(_) => return Err(Error::NotFound),
+        };
+        let blob = match self.repository.find_blob(entry.id()) {
+            Ok(blob) => blob,
+            Err(_) => return Err(Error::NotFound),
+        };
+        let content = blob.content();
+        match Content::from_prefix(&content[..content.len().min(max_bytes)]) {
+            Content::Binary { .. } => Ok(Content::Binary {
+                hash: blob.id().to_string(),
+            }),
+            content => Ok(content),
+        }
+    }
+
     pub fn list_files<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<PathBuf>> {
         let dir_path = dir_path.as_ref();
         let mut files = vec![];
         self.tree
             .walk(|root, entry| {
-                if entry.kind() == Some(git2::ObjectType::Tree) {
+                let Some(name) = entry.name() else {
                     return git::TreeWalkResult::Continue;
-                }
+                };
+                let entry_path = Path::new(root).join(name);
 
-                if entry.name().is_none() {
-                    return git::TreeWalkResult::Continue;
+                if entry.kind() == Some(git2::ObjectType::Tree) {
+                    return if entry_path.starts_with(dir_path) || dir_path.starts_with(&entry_path)
+                    {
+                        git::TreeWalkResult::Continue
+                    } else {
+                        git::TreeWalkResult::Skip
+                    };
                 }
-                let entry_path = Path::new(root).join(entry.name().unwrap());
 
                 if !entry_path.starts_with(dir_path) {
                     return git::TreeWalkResult::Continue;
@@ -201,12 +776,99 @@ impl<'reader> CommitReader<'reader> {
             })
             .with_context(|| format!("{}: tree walk failed", dir_path.display()))?;
 
+        // git orders tree entries by name with `/` appended to directories, which differs from
+        // how paths compare. sort to match the other readers.
+        files.sort();
         Ok(files)
     }
 
+    fn walk(
+        &self,
+        dir_path: &Path,
+        visit: &mut dyn FnMut(&Path) -> ControlFlow<()>,
+    ) -> Result<ControlFlow<()>> {
+        let mut flow = ControlFlow::Continue(());
+        let walked = self.tree.walk(|root, entry| {
+            let Some(name) = entry.name() else {
+                return git::TreeWalkResult::Continue;
+            };
+            let entry_path = Path::new(root).join(name);
+
+            if entry.kind() == Some(git2::ObjectType::Tree) {
+                return if entry_path.starts_with(dir_path) || dir_path.starts_with(&entry_path) {
+                    git::TreeWalkResult::Continue
+                } else {
+                    git::TreeWalkResult::Skip
+                };
+            }
+
+            if !entry_path.starts_with(dir_path) {
+                return git::TreeWalkResult::Continue;
+            }
+
+            flow = visit(entry_path.strip_prefix(dir_path).unwrap());
+            if flow.is_break() {
+                git::TreeWalkResult::Stop
+            } else {
+                git::TreeWalkResult::Continue
+            }
+        });
+
+        // stopping the walk early is reported as an error
+        if flow.is_continue() {
+            walked.with_context(|| format!("{}: tree walk failed", dir_path.display()))?;
+        }
+
+        Ok(flow)
+    }
+
     pub fn exists<P: AsRef<Path>>(&self, file_path: P) -> bool {
         self.tree.get_path(file_path.normalize()).is_ok()
     }
+
+    fn tree_hash<P: AsRef<Path>>(&self, dir_path: P) -> Result<String, Error> {
+        let dir_path = dir_path.as_ref();
+        if dir_path.as_os_str().is_empty() {
+            return Ok(self.tree.id().to_string());
+        }
+        match self.tree.get_path(dir_path) {
+            Ok(entry) if entry.kind() == Some(git2::ObjectType::Tree) => Ok(entry.id().to_string()),
+            _ => Err(Error::NotFound),
+        }
+    }
+}
+
+/// Reads from a commit like [`CommitReader`], but owns the repository instead of borrowing it, so
+/// that it can be handed out by whoever opened the repository. Reading goes through a [`Reader`]
+/// borrowed from it with [`OwnedReader::reader`].
+pub struct OwnedReader {
+    repository: git::Repository,
+    commit_oid: git::Oid,
+}
+
+impl OwnedReader {
+    /// Fails if there is no commit `commit_oid` in `repository`.
+    pub fn from_commit(repository: git::Repository, commit_oid: git::Oid) -> Result<Self> {
+        repository
+            .find_commit(commit_oid)
+            .with_context(|| format!("{}: commit not found", commit_oid))?;
+        Ok(Self {
+            repository,
+            commit_oid,
+        })
+    }
+
+    pub fn commit_id(&self) -> git::Oid {
+        self.commit_oid
+    }
+
+    pub fn reader(&self) -> Result<Reader<'_>> {
+        let commit = self
+            .repository
+            .find_commit(self.commit_oid)
+            .with_context(|| format!("{}: commit not found", self.commit_oid))?;
+        Reader::from_commit(&self.repository, &commit)
+    }
 }
 
 pub struct PrefixedReader<'r> {
@@ -222,23 +884,239 @@ impl<'r> PrefixedReader<'r> {
         }
     }
 
+    // joins `path` to the prefix, refusing paths that could point outside of it
+    fn join<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf, Error> {
+        let path = path.as_ref();
+        if path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            Ok(self.prefix.join(path))
+        } else {
+            Err(Error::OutsideRoot(path.to_path_buf()))
+        }
+    }
+
     pub fn batch<P: AsRef<Path>>(
         &self,
         paths: &[P],
     ) -> Result<Vec<Result<Content, Error>>, io::Error> {
-        let paths = paths
+        let joined = paths.iter().map(|path| self.join(path)).collect::<Vec<_>>();
+        let inside = joined
             .iter()
-            .map(|path| self.prefix.join(path))
+            .filter_map(|path| path.as_ref().ok())
             .collect::<Vec<_>>();
-        self.reader.batch(paths.as_slice())
+        let mut contents = self.reader.batch(inside.as_slice())?.into_iter();
+        Ok(joined
+            .into_iter()
+            .map(|path| match path {
+                Ok(_) => contents
+                    .next()
+                    .expect("batch should return a result for every path"),
+                Err(error) => Err(error),
+            })
+            .collect())
     }
 
     fn list_files<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<PathBuf>> {
-        self.reader.list_files(self.prefix.join(dir_path.as_ref()))
+        self.reader.list_files(self.join(dir_path)?)
+    }
+
+    fn walk(
+        &self,
+        dir_path: &Path,
+        visit: &mut dyn FnMut(&Path) -> ControlFlow<()>,
+    ) -> Result<ControlFlow<()>> {
+        self.reader.walk_dyn(&self.join(dir_path)?, visit)
     }
 
     fn exists<P: AsRef<Path>>(&self, file_path: P) -> Result<bool, io::Error> {
-        self.reader.exists(self.prefix.join(file_path.as_ref()))
+        match self.join(file_path) {
+            Ok(path) => self.reader.exists(path),
+            Err(_) => Ok(false),
+        }
+    }
+
+    fn read_many(&self, paths: &[PathBuf]) -> Vec<(PathBuf, Result<Content, Error>)> {
+        let joined = paths.iter().map(|path| self.join(path)).collect::<Vec<_>>();
+        let inside = joined
+            .iter()
+            .filter_map(|path| path.as_ref().ok().cloned())
+            .collect::<Vec<_>>();
+        let mut contents = self.reader.read_many(&inside).into_iter();
+        paths
+            .iter()
+            .cloned()
+            .zip(joined)
+            .map(|(path, joined)| match joined {
+                Ok(_) => {
+                    let (_, content) = contents
+                        .next()
+                        .expect("read_many should return a result for every path");
+                    (path, content)
+                }
+                Err(error) => (path, Err(error)),
+            })
+            .collect()
+    }
+
+    fn file_mode<P: AsRef<Path>>(&self, path: P) -> Result<i32, Error> {
+        self.reader.file_mode(self.join(path)?)
+    }
+
+    fn mtime<P: AsRef<Path>>(&self, path: P) -> Result<Option<SystemTime>, Error> {
+        self.reader.mtime(self.join(path)?)
+    }
+
+    fn read_prefix<P: AsRef<Path>>(&self, path: P, max_bytes: usize) -> Result<Content, Error> {
+        self.reader.read_prefix(self.join(path)?, max_bytes)
+    }
+
+    fn read_case_insensitive<P: AsRef<Path>>(&self, path: P) -> Result<Content, Error> {
+        self.reader.read_case_insensitive(self.join(path)?)
+    }
+
+    fn read_located<P: AsRef<Path>>(&self, path: P) -> Result<(Content, ResolvedSource), Error> {
+        self.reader.read_located(self.join(path)?)
+    }
+
+    fn tree_hash<P: AsRef<Path>>(&self, dir_path: P) -> Result<String, Error> {
+        self.reader.tree_hash(self.join(dir_path)?)
+    }
+}
+
+pub struct OverlayReader<'r> {
+    top: Box<Reader<'r>>,
+    bottom: Box<Reader<'r>>,
+}
+
+impl<'r> OverlayReader<'r> {
+    fn exists<P: AsRef<Path>>(&self, file_path: P) -> Result<bool, io::Error> {
+        let file_path = file_path.as_ref();
+        Ok(self.top.exists(file_path)? || self.bottom.exists(file_path)?)
+    }
+
+    fn batch<P: AsRef<Path>>(&self, paths: &[P]) -> Result<Vec<Result<Content, Error>>, io::Error> {
+        let mut contents = self.top.batch(paths)?;
+        for (path, content) in paths.iter().zip(contents.iter_mut()) {
+            if matches!(content, Err(Error::NotFound)) {
+                *content = self.bottom.read(path);
+            }
+        }
+        Ok(contents)
+    }
+
+    fn read_many(&self, paths: &[PathBuf]) -> Vec<(PathBuf, Result<Content, Error>)> {
+        let mut contents = self.top.read_many(paths);
+        let missing = contents
+            .iter()
+            .filter(|(_, content)| matches!(content, Err(Error::NotFound)))
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        let mut from_bottom = self.bottom.read_many(&missing).into_iter();
+        for (_, content) in &mut contents {
+            if matches!(content, Err(Error::NotFound)) {
+                if let Some((_, bottom_content)) = from_bottom.next() {
+                    *content = bottom_content;
+                }
+            }
+        }
+        contents
+    }
+
+    fn file_mode<P: AsRef<Path>>(&self, path: P) -> Result<i32, Error> {
+        let path = path.as_ref();
+        if self.top.exists(path)? {
+            self.top.file_mode(path)
+        } else {
+            self.bottom.file_mode(path)
+        }
+    }
+
+    fn mtime<P: AsRef<Path>>(&self, path: P) -> Result<Option<SystemTime>, Error> {
+        let path = path.as_ref();
+        match self.top.mtime(path) {
+            Err(Error::NotFound) => self.bottom.mtime(path),
+            result => result,
+        }
+    }
+
+    fn read_prefix<P: AsRef<Path>>(&self, path: P, max_bytes: usize) -> Result<Content, Error> {
+        let path = path.as_ref();
+        match self.top.read_prefix(path, max_bytes) {
+            Err(Error::NotFound) => self.bottom.read_prefix(path, max_bytes),
+            result => result,
+        }
+    }
+
+    fn read_case_insensitive<P: AsRef<Path>>(&self, path: P) -> Result<Content, Error> {
+        let path = path.as_ref();
+        match self.top.read_case_insensitive(path) {
+            Err(Error::NotFound) => self.bottom.read_case_insensitive(path),
+            result => result,
+        }
+    }
+
+    fn read_located<P: AsRef<Path>>(&self, path: P) -> Result<(Content, ResolvedSource), Error> {
+        let path = path.as_ref();
+        match self.top.read_located(path) {
+            Err(Error::NotFound) => self.bottom.read_located(path),
+            result => result,
+        }
+    }
+
+    fn tree_hash(&self, dir_path: &Path) -> Result<String, Error> {
+        // what is read can change with either layer, so the hash covers both
+        let layer_hash = |reader: &Reader| match reader.tree_hash(dir_path) {
+            Ok(hash) => Ok(Some(hash)),
+            Err(Error::NotFound) => Ok(None),
+            Err(error) => Err(error),
+        };
+        match (layer_hash(&self.top)?, layer_hash(&self.bottom)?) {
+            (None, None) => Err(Error::NotFound),
+            (top, bottom) => {
+                let mut hasher = Sha256::new();
+                hasher.update(top.unwrap_or_default());
+                hasher.update([0]);
+                hasher.update(bottom.unwrap_or_default());
+                Ok(format!("{:x}", hasher.finalize()))
+            }
+        }
+    }
+
+    fn list_files<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<PathBuf>> {
+        let dir_path = dir_path.as_ref();
+        let mut files = self.top.list_files(dir_path)?;
+        for file in self.bottom.list_files(dir_path)? {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    fn walk(
+        &self,
+        dir_path: &Path,
+        visit: &mut dyn FnMut(&Path) -> ControlFlow<()>,
+    ) -> Result<ControlFlow<()>> {
+        // files from the top shadow the ones with the same path in the bottom
+        let mut visited = HashSet::new();
+        let flow = self.top.walk_dyn(dir_path, &mut |path| {
+            visited.insert(path.to_path_buf());
+            visit(path)
+        })?;
+        if flow.is_break() {
+            return Ok(flow);
+        }
+        self.bottom.walk_dyn(dir_path, &mut |path| {
+            if visited.contains(path) {
+                ControlFlow::Continue(())
+            } else {
+                visit(path)
+            }
+        })
     }
 }
 
@@ -252,12 +1130,21 @@ pub enum FromError {
     Binary,
     #[error("file too large")]
     Large,
+    #[error("{}: {source}", path.display())]
+    WithPath {
+        path: PathBuf,
+        source: Box<FromError>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Content {
     UTF8(String),
-    Binary,
+    // text with invalid sequences replaced by U+FFFD, only ever created for display purposes by
+    // `Content::from_bytes_lossy`
+    LossyUTF8(String),
+    // binary contents are not kept, only their git blob id to tell them apart
+    Binary { hash: String },
     Large,
 }
 
@@ -273,9 +1160,17 @@ impl Serialize for Content {
                 state.serialize_field("value", text)?;
                 state.end()
             }
-            Content::Binary => {
-                let mut state = serializer.serialize_struct("Content", 1)?;
+            Content::LossyUTF8(text) => {
+                let mut state = serializer.serialize_struct("Content", 3)?;
+                state.serialize_field("type", "utf8")?;
+                state.serialize_field("value", text)?;
+                state.serialize_field("lossy", &true)?;
+                state.end()
+            }
+            Content::Binary { hash } => {
+                let mut state = serializer.serialize_struct("Content", 2)?;
                 state.serialize_field("type", "binary")?;
+                state.serialize_field("hash", hash)?;
                 state.end()
             }
             Content::Large => {
@@ -288,16 +1183,187 @@ impl Serialize for Content {
 }
 
 impl Content {
-    const MAX_SIZE: usize = 1024 * 1024 * 10; // 10 MB
+    pub const MAX_SIZE: usize = 1024 * 1024 * 10; // 10 MB
 
+    /// Reads a file into a buffer sized up front from its metadata, so that text is copied
+    /// once, straight into the resulting string.
     pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
-        let path = path.as_ref();
-        let metadata = fs::metadata(path)?;
-        if metadata.len() > Content::MAX_SIZE as u64 {
+        let file = fs::File::open(path)?;
+        let len = file.metadata()?.len();
+        if len > Content::MAX_SIZE as u64 {
             return Ok(Content::Large);
         }
-        let content = fs::read(path)?;
-        Ok(content.as_slice().into())
+        // the file can grow after its size was checked, so read at most one byte past the limit
+        let mut bytes = Vec::with_capacity(len as usize);
+        file.take(Content::MAX_SIZE as u64 + 1)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() > Content::MAX_SIZE {
+            return Ok(Content::Large);
+        }
+        Ok(match String::from_utf8(bytes) {
+            Ok(text) => Content::UTF8(text),
+            Err(error) => Content::binary(error.as_bytes()),
+        })
+    }
+
+    /// Reads content from a stream with the same classification as [`Content::from`]. At most
+    /// one byte more than the size limit is read, so large streams are not loaded completely.
+    pub fn from_reader(r: impl Read) -> Result<Self, io::Error> {
+        let mut bytes = Vec::new();
+        r.take(Content::MAX_SIZE as u64 + 1)
+            .read_to_end(&mut bytes)?;
+        Ok(bytes.as_slice().into())
+    }
+
+    /// Classifies the first bytes of a file. Unlike [`Content::from`], a multi-byte character
+    /// cut off at the end of `bytes` does not make the content binary.
+    pub(crate) fn from_prefix(bytes: &[u8]) -> Self {
+        match str::from_utf8(bytes) {
+            Ok(text) => Content::UTF8(text.to_string()),
+            Err(error) if error.error_len().is_none() => {
+                Content::UTF8(String::from_utf8_lossy(&bytes[..error.valid_up_to()]).into_owned())
+            }
+            Err(_) => Content::binary(bytes),
+        }
+    }
+
+    /// Like [`Content::from`], but for display purposes: bytes that are not valid UTF-8 are
+    /// replaced with U+FFFD instead of the whole content being considered binary, which yields
+    /// [`Content::LossyUTF8`]. Valid text is still [`Content::UTF8`].
+    pub fn from_bytes_lossy(bytes: &[u8]) -> Self {
+        if bytes.len() > Self::MAX_SIZE {
+            return Content::Large;
+        }
+        match String::from_utf8_lossy(bytes) {
+            borrow::Cow::Borrowed(text) => Content::UTF8(text.to_string()),
+            borrow::Cow::Owned(text) => Content::LossyUTF8(text),
+        }
+    }
+
+    fn binary(bytes: &[u8]) -> Self {
+        let hash = git2::Oid::hash_object(git2::ObjectType::Blob, bytes)
+            .map(|oid| oid.to_string())
+            .unwrap_or_default();
+        Content::Binary { hash }
+    }
+
+    /// Returns true for empty UTF8 content. Binary and large content is never empty, since
+    /// empty files are always read as UTF8.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Content::UTF8(text) if text.is_empty())
+    }
+
+    /// Returns the size of UTF8 content in bytes. The size of binary and large content is not
+    /// kept, so it is unknown, and neither is the original size of lossy content.
+    pub fn byte_len(&self) -> Option<usize> {
+        match self {
+            Content::UTF8(text) => Some(text.len()),
+            Content::LossyUTF8(_) => None,
+            Content::Binary { .. } | Content::Large => None,
+        }
+    }
+
+    /// Borrows the text of UTF8 and lossy UTF8 content. Binary and large content has no text.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Content::UTF8(text) | Content::LossyUTF8(text) => Some(text),
+            Content::Binary { .. } | Content::Large => None,
+        }
+    }
+
+    /// Returns the number of lines of UTF8 content, counting a last line without a trailing
+    /// newline as well. Binary and large content has no lines.
+    pub fn line_count(&self) -> Option<usize> {
+        match self {
+            Content::UTF8(text) | Content::LossyUTF8(text) => {
+                let newlines = text.bytes().filter(|byte| *byte == b'\n').count();
+                if text.is_empty() || text.ends_with('\n') {
+                    Some(newlines)
+                } else {
+                    Some(newlines + 1)
+                }
+            }
+            Content::Binary { .. } | Content::Large => None,
+        }
+    }
+
+    /// Diffs UTF8 content line by line as if `self` was changed into `other`, into the hunks git
+    /// would show with its default context. Binary and large content can't be diffed, so `None`
+    /// is returned if either side is.
+    pub fn diff(&self, other: &Content) -> Option<Vec<diff::GitHunk>> {
+        let (Some(old), Some(new)) = (self.as_str(), other.as_str()) else {
+            return None;
+        };
+        // like git, a range without lines starts at the line before it
+        let start = |range: &ops::Range<usize>| {
+            if range.is_empty() {
+                range.start
+            } else {
+                range.start + 1
+            }
+        };
+        let text_diff = similar::TextDiff::from_lines(old, new);
+        let hunks = text_diff
+            .unified_diff()
+            .context_radius(diff::Options::default().context_lines as usize)
+            .iter_hunks()
+            .filter_map(|hunk| {
+                let (first, last) = (hunk.ops().first()?, hunk.ops().last()?);
+                let old_range = first.old_range().start..last.old_range().end;
+                let new_range = first.new_range().start..last.new_range().end;
+                Some(diff::GitHunk {
+                    old_start: start(&old_range) as u32,
+                    old_lines: old_range.len() as u32,
+                    new_start: start(&new_range) as u32,
+                    new_lines: new_range.len() as u32,
+                    diff: hunk.to_string(),
+                    binary: false,
+                    change_type: diff::ChangeType::Modified,
+                })
+            })
+            .collect();
+        Some(hunks)
+    }
+
+    /// Like `==`, but two UTF8 contents are also equal if their lines only differ in trailing
+    /// spaces or tabs.
+    pub fn eq_ignoring_trailing_ws(&self, other: &Content) -> bool {
+        match (self, other) {
+            (Content::UTF8(left), Content::UTF8(right)) => {
+                let trim = |line: &str| line.trim_end_matches([' ', '\t']);
+                left.split('\n').map(trim).eq(right.split('\n').map(trim))
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Converts CRLF line endings of UTF8 content to LF, so that the same text compares equal
+    /// regardless of the line endings it was stored with.
+    ///
+    /// Binary and large content is returned as is.
+    pub fn normalized(self) -> Self {
+        match self {
+            Content::UTF8(text) if text.contains("\r\n") => {
+                Content::UTF8(text.replace("\r\n", "\n"))
+            }
+            Content::LossyUTF8(text) if text.contains("\r\n") => {
+                Content::LossyUTF8(text.replace("\r\n", "\n"))
+            }
+            content => content,
+        }
+    }
+
+    /// Like [`Content::normalized`], but only normalizes if `path` is considered text by the
+    /// `.gitattributes` of `repository`, i.e. has `text` set or `text=auto`.
+    pub fn normalized_for(self, repository: &git::Repository, path: &Path) -> Result<Self> {
+        let repository: &git2::Repository = repository.into();
+        let text = repository
+            .get_attr(path, "text", git2::AttrCheckFlags::FILE_THEN_INDEX)
+            .context("failed to read text attribute")?;
+        match git2::AttrValue::from_string(text) {
+            git2::AttrValue::True | git2::AttrValue::String("auto") => Ok(self.normalized()),
+            _ => Ok(self),
+        }
     }
 }
 
@@ -316,7 +1382,13 @@ impl From<&git::Blob<'_>> for Content {
         if value.size() > Content::MAX_SIZE {
             Content::Large
         } else {
-            value.content().into()
+            // the blob already knows its id, there is no need to hash the bytes again
+            match str::from_utf8(value.content()) {
+                Ok(text) => Content::UTF8(text.to_string()),
+                Err(_) => Content::Binary {
+                    hash: value.id().to_string(),
+                },
+            }
         }
     }
 }
@@ -327,7 +1399,7 @@ impl From<&[u8]> for Content {
             Content::Large
         } else {
             match String::from_utf8(bytes.to_vec()) {
-                Err(_) => Content::Binary,
+                Err(_) => Content::binary(bytes),
                 Ok(text) => Content::UTF8(text),
             }
         }
@@ -340,7 +1412,8 @@ impl TryFrom<&Content> for usize {
     fn try_from(content: &Content) -> Result<Self, Self::Error> {
         match content {
             Content::UTF8(text) => text.parse().map_err(FromError::ParseInt),
-            Content::Binary => Err(FromError::Binary),
+            // the replaced bytes are lost, lossy text is not what was read
+            Content::Binary { .. } | Content::LossyUTF8(_) => Err(FromError::Binary),
             Content::Large => Err(FromError::Large),
         }
     }
@@ -360,7 +1433,8 @@ impl TryFrom<&Content> for String {
     fn try_from(content: &Content) -> Result<Self, Self::Error> {
         match content {
             Content::UTF8(text) => Ok(text.clone()),
-            Content::Binary => Err(FromError::Binary),
+            // the replaced bytes are lost, lossy text is not what was read
+            Content::Binary { .. } | Content::LossyUTF8(_) => Err(FromError::Binary),
             Content::Large => Err(FromError::Large),
         }
     }
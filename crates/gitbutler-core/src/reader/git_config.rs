@@ -0,0 +1,113 @@
+use std::{collections::HashMap, path::Path};
+
+use super::{Content, Error, FromError, Reader};
+
+/// Gives access to the values of a `.git/config`-style file read through a [`Reader`].
+///
+/// Keys are looked up as `section.key` or `section.subsection.key`, e.g. `core.bare` or
+/// `remote.origin.url`. Section and key names are case-insensitive, subsections are not.
+pub struct GitConfigReader {
+    values: HashMap<String, Content>,
+}
+
+impl GitConfigReader {
+    pub fn open<P: AsRef<Path>>(reader: &Reader, path: P) -> Result<Self, Error> {
+        let text: String = reader.read(path)?.try_into()?;
+        Ok(Self::parse(&text))
+    }
+
+    fn parse(text: &str) -> Self {
+        let mut values = HashMap::new();
+        let mut section = String::new();
+        for line in text.lines() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = parse_section(header);
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), unquote(value.trim())),
+                // a key without a value is a boolean set to true
+                None => (line, "true"),
+            };
+            values.insert(
+                format!("{}.{}", section, key.to_lowercase()),
+                Content::from(value),
+            );
+        }
+        Self { values }
+    }
+
+    pub fn get_str(&self, key: &str) -> Result<Option<String>, Error> {
+        self.get(key)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, Error> {
+        self.get(key)
+    }
+
+    pub fn get_int(&self, key: &str) -> Result<Option<i64>, Error> {
+        self.get(key)
+    }
+
+    fn get<T>(&self, key: &str) -> Result<Option<T>, Error>
+    where
+        for<'c> T: TryFrom<&'c Content, Error = FromError>,
+    {
+        self.values
+            .get(&normalize_key(key))
+            .map(T::try_from)
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+// `[section]`, `[section.subsection]` and `[section "subsection"]` all map to the same prefix.
+fn parse_section(header: &str) -> String {
+    match header.split_once(char::is_whitespace) {
+        Some((name, subsection)) => {
+            format!("{}.{}", name.to_lowercase(), unquote(subsection.trim()))
+        }
+        None => match header.split_once('.') {
+            Some((name, subsection)) => format!("{}.{}", name.to_lowercase(), subsection),
+            None => header.to_lowercase(),
+        },
+    }
+}
+
+// lowercases the section and the key, but keeps the subsection as it is.
+fn normalize_key(key: &str) -> String {
+    match (key.find('.'), key.rfind('.')) {
+        (Some(first), Some(last)) if first != last => format!(
+            "{}{}{}",
+            key[..first].to_lowercase(),
+            &key[first..last],
+            key[last..].to_lowercase()
+        ),
+        _ => key.to_lowercase(),
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '#' | ';' if !in_quotes => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
@@ -0,0 +1,35 @@
+//! Secrets, like passphrases and access tokens, are kept in the keychain of the operating system
+//! rather than in the app's data files, which only refer to them by their handle.
+
+use anyhow::{Context, Result};
+
+const SERVICE: &str = "gitbutler";
+
+/// Stores `secret` under `handle`, replacing the secret stored there before, if any.
+pub fn persist(handle: &str, secret: &str) -> Result<()> {
+    entry(handle)?
+        .set_password(secret)
+        .with_context(|| format!("failed to store secret {handle}"))
+}
+
+/// Returns the secret stored under `handle`, or `None` if there is none.
+pub fn retrieve(handle: &str) -> Result<Option<String>> {
+    match entry(handle)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(error) => Err(error).with_context(|| format!("failed to retrieve secret {handle}")),
+    }
+}
+
+/// Removes the secret stored under `handle`. It's not an error if there is none.
+pub fn delete(handle: &str) -> Result<()> {
+    match entry(handle)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(error) => Err(error).with_context(|| format!("failed to delete secret {handle}")),
+    }
+}
+
+fn entry(handle: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, handle)
+        .with_context(|| format!("failed to open keychain entry of secret {handle}"))
+}
@@ -0,0 +1,6 @@
+pub mod controller;
+pub mod storage;
+mod token;
+
+pub use controller::*;
+pub use token::Token;
@@ -0,0 +1,30 @@
+use anyhow::Context;
+
+use super::{storage::Storage, Token};
+
+#[derive(Clone)]
+pub struct Controller {
+    storage: Storage,
+}
+
+impl Controller {
+    pub fn new(storage: Storage) -> Controller {
+        Controller { storage }
+    }
+
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Controller {
+        Controller::new(Storage::from_path(path))
+    }
+
+    pub fn get_token(&self, host: &str) -> anyhow::Result<Option<Token>> {
+        self.storage.get(host).context("failed to get token")
+    }
+
+    pub fn set_token(&self, host: &str, token: &Token) -> anyhow::Result<()> {
+        self.storage.set(host, token).context("failed to set token")
+    }
+
+    pub fn delete_token(&self, host: &str) -> anyhow::Result<()> {
+        self.storage.delete(host).context("failed to delete token")
+    }
+}
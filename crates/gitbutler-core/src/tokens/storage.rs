@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{secret, storage, tokens::Token};
+
+const TOKENS_FILE: &str = "tokens.json";
+
+#[derive(Debug, Clone)]
+pub struct Storage {
+    storage: storage::Storage,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Storage(#[from] storage::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Secret(#[from] anyhow::Error),
+}
+
+/// What is kept of a token in [`TOKENS_FILE`], the token itself is kept in the keychain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    username: String,
+    /// tokens used to be kept in plain text, these are moved to the keychain when read.
+    #[serde(default, skip_serializing)]
+    token: Option<String>,
+}
+
+impl Storage {
+    pub fn new(storage: storage::Storage) -> Storage {
+        Storage { storage }
+    }
+
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Storage {
+        Storage::new(storage::Storage::new(path))
+    }
+
+    fn list(&self) -> Result<HashMap<String, Entry>, Error> {
+        let mut entries: HashMap<String, Entry> = match self.storage.read(TOKENS_FILE)? {
+            Some(data) => serde_json::from_str(&data)?,
+            None => return Ok(HashMap::new()),
+        };
+
+        let mut migrated = false;
+        for (host, entry) in &mut entries {
+            if let Some(token) = entry.token.take() {
+                secret::persist(&handle(host), &token)?;
+                migrated = true;
+            }
+        }
+        if migrated {
+            self.write(&entries)?;
+        }
+
+        Ok(entries)
+    }
+
+    fn write(&self, entries: &HashMap<String, Entry>) -> Result<(), Error> {
+        self.storage
+            .write(TOKENS_FILE, &serde_json::to_string(entries)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, host: &str) -> Result<Option<Token>, Error> {
+        let Some(entry) = self.list()?.remove(host) else {
+            return Ok(None);
+        };
+        Ok(secret::retrieve(&handle(host))?.map(|token| Token {
+            username: entry.username,
+            token,
+        }))
+    }
+
+    pub fn set(&self, host: &str, token: &Token) -> Result<(), Error> {
+        let mut entries = self.list()?;
+        secret::persist(&handle(host), &token.token)?;
+        entries.insert(
+            host.to_string(),
+            Entry {
+                username: token.username.clone(),
+                token: None,
+            },
+        );
+        self.write(&entries)
+    }
+
+    pub fn delete(&self, host: &str) -> Result<(), Error> {
+        let mut entries = self.list()?;
+        secret::delete(&handle(host))?;
+        if entries.remove(host).is_some() {
+            self.write(&entries)?;
+        }
+        Ok(())
+    }
+}
+
+fn handle(host: &str) -> String {
+    format!("tokens/{host}")
+}
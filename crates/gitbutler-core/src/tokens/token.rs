@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// A personal access token used to authenticate against https remotes of a host.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Token {
+    pub username: String,
+    pub token: String,
+}
@@ -26,5 +26,11 @@ pub use r#virtual::*;
 mod remote;
 pub use remote::*;
 
+mod pull_requests;
+pub use pull_requests::*;
+
+pub mod snapshot;
+pub use snapshot::SnapshotId;
+
 mod state;
 pub use state::VirtualBranchesHandle;
@@ -32,6 +32,19 @@ pub struct BaseBranch {
     pub last_fetched_ms: Option<u128>,
 }
 
+// what happened to a branch when updating the base branch
+#[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum RebaseOutcome {
+    // the branch is now based on the new target, either rebased or with a merge commit
+    Rebased,
+    // the branch conflicts with the new target and was unapplied
+    Conflicted,
+    // the branch is already part of the new target, so there was nothing to rebase. it's deleted
+    // unless it has uncommitted changes.
+    Skipped,
+}
+
 pub fn get_base_branch_data(
     gb_repository: &gb_repository::Repository,
     project_repository: &project_repository::Repository,
@@ -49,6 +62,29 @@ pub fn get_base_branch_data(
     }
 }
 
+/// Counts the upstream commits that are not integrated yet, like [`BaseBranch::behind`], without
+/// collecting the commits themselves. Without a default target nothing is behind.
+pub fn base_branch_behind(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<usize> {
+    let Some(target) = gb_repository
+        .default_target()
+        .context("failed to get default target")?
+    else {
+        return Ok(0);
+    };
+
+    let branch = project_repository
+        .git_repository
+        .find_branch(&target.branch.clone().into())?;
+    let oid = branch.peel_to_commit()?.id();
+    let upstream_commits = project_repository
+        .l(oid, LogUntil::Commit(target.sha))
+        .context("failed to get upstream commits")?;
+    Ok(upstream_commits.len())
+}
+
 fn go_back_to_integration(
     gb_repository: &gb_repository::Repository,
     project_repository: &project_repository::Repository,
@@ -153,19 +189,29 @@ pub fn set_base_branch(
         Err(error) => Err(errors::SetBaseBranchError::Other(error.into())),
     }?;
 
-    let remote = repo
-        .find_remote(target_branch_ref.remote())
-        .context(format!(
-            "failed to find remote for branch {}",
-            target_branch.name().unwrap()
-        ))?;
+    // a remote tracking branch left behind by a remote that is no longer configured can't be
+    // fetched, so it is no use as a target
+    let remote = match repo.find_remote(target_branch_ref.remote()) {
+        Ok(remote) => Ok(remote),
+        Err(git::Error::NotFound(_)) => Err(errors::SetBaseBranchError::InvalidTargetBranch(
+            target_branch_ref.clone(),
+        )),
+        Err(error) => Err(errors::SetBaseBranchError::Other(
+            anyhow::Error::from(error).context(format!(
+                "failed to find remote for branch {}",
+                target_branch.name().unwrap()
+            )),
+        )),
+    }?;
     let remote_url = remote
         .url()
         .context(format!(
             "failed to get remote url for {}",
             target_branch_ref.remote()
         ))?
-        .unwrap();
+        .ok_or_else(|| {
+            errors::SetBaseBranchError::InvalidTargetBranch(target_branch_ref.clone())
+        })?;
 
     let target_branch_head = target_branch.peel_to_commit().context(format!(
         "failed to peel branch {} to commit",
@@ -272,6 +318,7 @@ pub fn set_base_branch(
                 upstream_head,
                 created_timestamp_ms: now_ms,
                 updated_timestamp_ms: now_ms,
+                last_activity_ms: now_ms,
                 head: current_head_commit.id(),
                 tree: super::write_tree_onto_commit(
                     project_repository,
@@ -281,6 +328,8 @@ pub fn set_base_branch(
                 ownership,
                 order: 0,
                 selected_for_changes: None,
+                archived: false,
+                pushed_name: None,
             };
 
             let branch_writer = branch::Writer::new(
@@ -300,6 +349,119 @@ pub fn set_base_branch(
     Ok(base)
 }
 
+// disconnects the project from its base branch: all branches are unapplied, the branch that was
+// checked out before the project was connected is checked out again, or HEAD is detached at the
+// base commit if there is none, and the default target is deleted. refuses if an applied branch
+// has commits that were not pushed, as they would no longer be part of the working directory.
+pub fn unset_base_branch(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<(), errors::UnsetBaseBranchError> {
+    if project_repository.is_resolving() {
+        return Err(errors::UnsetBaseBranchError::Conflict(
+            errors::ProjectConflict {
+                project_id: project_repository.project().id,
+            },
+        ));
+    }
+
+    let Some(default_target) = gb_repository.default_target()? else {
+        return Ok(());
+    };
+
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+    let applied_branches = super::Iterator::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .context("failed to create branch iterator")?
+    .collect::<Result<Vec<branch::Branch>, reader::Error>>()
+    .context("failed to read virtual branches")?
+    .into_iter()
+    .filter(|branch| branch.applied)
+    .collect::<Vec<_>>();
+
+    for branch in &applied_branches {
+        let commits = project_repository.l(branch.head, LogUntil::Commit(default_target.sha))?;
+        let pushed_commits = branch.upstream_head.map_or_else(
+            || Ok(vec![]),
+            |upstream_head| {
+                project_repository.l(upstream_head, LogUntil::Commit(default_target.sha))
+            },
+        )?;
+        if commits.iter().any(|oid| !pushed_commits.contains(oid)) {
+            return Err(errors::UnsetBaseBranchError::UnpushedCommits(
+                branch.name.clone(),
+            ));
+        }
+    }
+
+    let previous_head = head_before_integration(project_repository)?;
+
+    for branch in &applied_branches {
+        super::unapply_branch(gb_repository, project_repository, &branch.id)
+            .with_context(|| format!("failed to unapply branch {}", branch.name))?;
+    }
+
+    let repo = &project_repository.git_repository;
+    match previous_head {
+        Some(refname) => {
+            let refname = git::Refname::from(refname);
+            let tree = repo
+                .find_reference(&refname)
+                .and_then(|reference| reference.peel_to_tree())
+                .with_context(|| format!("failed to find tree of {}", refname))?;
+            repo.checkout_tree(&tree)
+                .checkout()
+                .with_context(|| format!("failed to checkout {}", refname))?;
+            repo.set_head(&refname).context("failed to set head")?;
+        }
+        None => repo
+            .set_head_detached(default_target.sha)
+            .context("failed to detach head")?,
+    }
+
+    target::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create target writer")?
+    .delete_default()?;
+
+    Ok(())
+}
+
+// the local branch that was checked out before the project was connected to its base branch, as
+// recorded in .git/integration when switching to the integration branch. None if it is gone.
+fn head_before_integration(
+    project_repository: &project_repository::Repository,
+) -> Result<Option<git::LocalRefname>> {
+    let repo = &project_repository.git_repository;
+    let path = repo.path().join("integration");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let integration = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let Some(Ok(git::Refname::Local(refname))) = integration
+        .split(':')
+        .next()
+        .map(str::parse::<git::Refname>)
+    else {
+        return Ok(None);
+    };
+    match repo.find_reference(&refname.clone().into()) {
+        Ok(_) => Ok(Some(refname)),
+        Err(git::Error::NotFound(_)) => Ok(None),
+        Err(error) => Err(error).context("failed to find previous head"),
+    }
+}
+
 fn set_exclude_decoration(project_repository: &project_repository::Repository) -> Result<()> {
     let repo = &project_repository.git_repository;
     let mut config = repo.config()?;
@@ -340,6 +502,7 @@ pub fn update_base_branch(
     project_repository: &project_repository::Repository,
     user: Option<&users::User>,
     signing_key: Option<&keys::PrivateKey>,
+    on_branch: &mut dyn FnMut(&str, RebaseOutcome),
 ) -> Result<(), errors::UpdateBaseBranchError> {
     if project_repository.is_resolving() {
         return Err(errors::UpdateBaseBranchError::Conflict(
@@ -401,194 +564,194 @@ pub fn update_base_branch(
     let context_lines = if use_context { 3_u32 } else { 0_u32 };
 
     // try to update every branch
-    let updated_vbranches = super::get_status_by_branch(gb_repository, project_repository)?
-        .0
-        .into_iter()
-        .map(|(branch, _)| branch)
-        .map(
-            |mut branch: branch::Branch| -> Result<Option<branch::Branch>> {
-                let branch_tree = repo.find_tree(branch.tree)?;
-
-                let branch_head_commit = repo.find_commit(branch.head).context(format!(
-                    "failed to find commit {} for branch {}",
-                    branch.head, branch.id
-                ))?;
-                let branch_head_tree = branch_head_commit.tree().context(format!(
-                    "failed to find tree for commit {} for branch {}",
-                    branch.head, branch.id
-                ))?;
-
-                let result_integrated_detected =
-                    |mut branch: branch::Branch| -> Result<Option<branch::Branch>> {
-                        // branch head tree is the same as the new target tree.
-                        // meaning we can safely use the new target commit as the branch head.
-
-                        branch.head = new_target_commit.id();
-
-                        // it also means that the branch is fully integrated into the target.
-                        // disconnect it from the upstream
-                        branch.upstream = None;
-                        branch.upstream_head = None;
-
-                        let non_commited_files = diff::trees(
-                            &project_repository.git_repository,
-                            &branch_head_tree,
-                            &branch_tree,
-                            context_lines,
-                        )?;
-                        if non_commited_files.is_empty() {
-                            // if there are no commited files, then the branch is fully merged
-                            // and we can delete it.
-                            branch_writer.delete(&branch)?;
-                            project_repository.delete_branch_reference(&branch)?;
-                            Ok(None)
-                        } else {
-                            branch_writer.write(&mut branch)?;
-                            Ok(Some(branch))
-                        }
-                    };
-
-                if branch_head_tree.id() == new_target_tree.id() {
-                    return result_integrated_detected(branch);
-                }
-
-                // try to merge branch head with new target
-                let mut branch_tree_merge_index = repo
-                    .merge_trees(&old_target_tree, &branch_tree, &new_target_tree)
-                    .context(format!("failed to merge trees for branch {}", branch.id))?;
-
-                if branch_tree_merge_index.has_conflicts() {
-                    // branch tree conflicts with new target, unapply branch for now. we'll handle it later, when user applies it back.
-                    branch.applied = false;
-                    branch_writer.write(&mut branch)?;
-                    return Ok(Some(branch));
-                }
+    let update_branch =
+        |mut branch: branch::Branch| -> Result<(Option<branch::Branch>, RebaseOutcome)> {
+            let branch_tree = repo.find_tree(branch.tree)?;
 
-                let branch_merge_index_tree_oid = branch_tree_merge_index.write_tree_to(repo)?;
+            let branch_head_commit = repo.find_commit(branch.head).context(format!(
+                "failed to find commit {} for branch {}",
+                branch.head, branch.id
+            ))?;
+            let branch_head_tree = branch_head_commit.tree().context(format!(
+                "failed to find tree for commit {} for branch {}",
+                branch.head, branch.id
+            ))?;
 
-                if branch_merge_index_tree_oid == new_target_tree.id() {
-                    return result_integrated_detected(branch);
-                }
+            let result_integrated_detected =
+                |mut branch: branch::Branch| -> Result<Option<branch::Branch>> {
+                    // branch head tree is the same as the new target tree.
+                    // meaning we can safely use the new target commit as the branch head.
 
-                if branch.head == target.sha {
-                    // there are no commits on the branch, so we can just update the head to the new target and calculate the new tree
                     branch.head = new_target_commit.id();
-                    branch.tree = branch_merge_index_tree_oid;
-                    branch_writer.write(&mut branch)?;
-                    return Ok(Some(branch));
-                }
 
-                let mut branch_head_merge_index = repo
-                    .merge_trees(&old_target_tree, &branch_head_tree, &new_target_tree)
-                    .context(format!(
-                        "failed to merge head tree for branch {}",
-                        branch.id
-                    ))?;
+                    // it also means that the branch is fully integrated into the target.
+                    // disconnect it from the upstream
+                    branch.upstream = None;
+                    branch.upstream_head = None;
+
+                    let non_commited_files = diff::trees(
+                        &project_repository.git_repository,
+                        &branch_head_tree,
+                        &branch_tree,
+                        context_lines,
+                    )?;
+                    if non_commited_files.is_empty() {
+                        // if there are no commited files, then the branch is fully merged
+                        // and we can delete it.
+                        branch_writer.delete(&branch)?;
+                        project_repository.delete_branch_reference(&branch)?;
+                        Ok(None)
+                    } else {
+                        branch_writer.write(&mut branch)?;
+                        Ok(Some(branch))
+                    }
+                };
 
-                if branch_head_merge_index.has_conflicts() {
-                    // branch commits conflict with new target, make sure the branch is
-                    // unapplied. conflicts witll be dealt with when applying it back.
-                    branch.applied = false;
-                    branch_writer.write(&mut branch)?;
-                    return Ok(Some(branch));
-                }
+            if branch_head_tree.id() == new_target_tree.id() {
+                return Ok((result_integrated_detected(branch)?, RebaseOutcome::Skipped));
+            }
+
+            // try to merge branch head with new target
+            let mut branch_tree_merge_index = repo
+                .merge_trees(&old_target_tree, &branch_tree, &new_target_tree)
+                .context(format!("failed to merge trees for branch {}", branch.id))?;
+
+            if branch_tree_merge_index.has_conflicts() {
+                // branch tree conflicts with new target, unapply branch for now. we'll handle it later, when user applies it back.
+                branch.applied = false;
+                branch_writer.write(&mut branch)?;
+                return Ok((Some(branch), RebaseOutcome::Conflicted));
+            }
+
+            let branch_merge_index_tree_oid = branch_tree_merge_index.write_tree_to(repo)?;
+
+            if branch_merge_index_tree_oid == new_target_tree.id() {
+                return Ok((result_integrated_detected(branch)?, RebaseOutcome::Skipped));
+            }
+
+            if branch.head == target.sha {
+                // there are no commits on the branch, so we can just update the head to the new target and calculate the new tree
+                branch.head = new_target_commit.id();
+                branch.tree = branch_merge_index_tree_oid;
+                branch_writer.write(&mut branch)?;
+                return Ok((Some(branch), RebaseOutcome::Rebased));
+            }
+
+            let mut branch_head_merge_index = repo
+                .merge_trees(&old_target_tree, &branch_head_tree, &new_target_tree)
+                .context(format!(
+                    "failed to merge head tree for branch {}",
+                    branch.id
+                ))?;
 
-                // branch commits do not conflict with new target, so lets merge them
-                let branch_head_merge_tree_oid = branch_head_merge_index
+            if branch_head_merge_index.has_conflicts() {
+                // branch commits conflict with new target, make sure the branch is
+                // unapplied. conflicts witll be dealt with when applying it back.
+                branch.applied = false;
+                branch_writer.write(&mut branch)?;
+                return Ok((Some(branch), RebaseOutcome::Conflicted));
+            }
+
+            // branch commits do not conflict with new target, so lets merge them
+            let branch_head_merge_tree_oid =
+                branch_head_merge_index
                     .write_tree_to(repo)
                     .context(format!(
                         "failed to write head merge index for {}",
                         branch.id
                     ))?;
 
-                let ok_with_force_push = project_repository.project().ok_with_force_push;
-
-                let result_merge = |mut branch: branch::Branch| -> Result<Option<branch::Branch>> {
-                    // branch was pushed to upstream, and user doesn't like force pushing.
-                    // create a merge commit to avoid the need of force pushing then.
-                    let branch_head_merge_tree = repo
-                        .find_tree(branch_head_merge_tree_oid)
-                        .context("failed to find tree")?;
-
-                    let new_target_head = project_repository
-                        .commit(
-                            user,
-                            format!(
-                                "Merged {}/{} into {}",
-                                target.branch.remote(),
-                                target.branch.branch(),
-                                branch.name
-                            )
-                            .as_str(),
-                            &branch_head_merge_tree,
-                            &[&branch_head_commit, &new_target_commit],
-                            signing_key,
+            let ok_with_force_push = project_repository.project().ok_with_force_push;
+
+            let result_merge = |mut branch: branch::Branch| -> Result<Option<branch::Branch>> {
+                // branch was pushed to upstream, and user doesn't like force pushing.
+                // create a merge commit to avoid the need of force pushing then.
+                let branch_head_merge_tree = repo
+                    .find_tree(branch_head_merge_tree_oid)
+                    .context("failed to find tree")?;
+
+                let new_target_head = project_repository
+                    .commit(
+                        user,
+                        format!(
+                            "Merged {}/{} into {}",
+                            target.branch.remote(),
+                            target.branch.branch(),
+                            branch.name
                         )
-                        .context("failed to commit merge")?;
-
-                    branch.head = new_target_head;
-                    branch.tree = branch_merge_index_tree_oid;
-                    branch_writer.write(&mut branch)?;
-                    Ok(Some(branch))
-                };
-
-                if branch.upstream.is_some() && !ok_with_force_push {
-                    return result_merge(branch);
-                }
-
-                // branch was not pushed to upstream yet. attempt a rebase,
-                let (_, committer) = project_repository.git_signatures(user)?;
-                let mut rebase_options = git2::RebaseOptions::new();
-                rebase_options.quiet(true);
-                rebase_options.inmemory(true);
-                let mut rebase = repo
-                    .rebase(
-                        Some(branch.head),
-                        Some(new_target_commit.id()),
-                        None,
-                        Some(&mut rebase_options),
+                        .as_str(),
+                        &branch_head_merge_tree,
+                        &[&branch_head_commit, &new_target_commit],
+                        signing_key,
                     )
-                    .context("failed to rebase")?;
-
-                let mut rebase_success = true;
-                // check to see if these commits have already been pushed
-                let mut last_rebase_head = branch.head;
-                while rebase.next().is_some() {
-                    let index = rebase
-                        .inmemory_index()
-                        .context("failed to get inmemory index")?;
-                    if index.has_conflicts() {
-                        rebase_success = false;
-                        break;
-                    }
+                    .context("failed to commit merge")?;
 
-                    if let Ok(commit_id) = rebase.commit(None, &committer.clone().into(), None) {
-                        last_rebase_head = commit_id.into();
-                    } else {
-                        rebase_success = false;
-                        break;
-                    }
-                }
+                branch.head = new_target_head;
+                branch.tree = branch_merge_index_tree_oid;
+                branch_writer.write(&mut branch)?;
+                Ok(Some(branch))
+            };
 
-                if rebase_success {
-                    // rebase worked out, rewrite the branch head
-                    rebase.finish(None).context("failed to finish rebase")?;
-                    branch.head = last_rebase_head;
-                    branch.tree = branch_merge_index_tree_oid;
-                    branch_writer.write(&mut branch)?;
-                    return Ok(Some(branch));
+            if branch.upstream.is_some() && !ok_with_force_push {
+                return Ok((result_merge(branch)?, RebaseOutcome::Rebased));
+            }
+
+            // branch was not pushed to upstream yet. attempt a rebase,
+            let (_, committer) = project_repository.git_signatures(user)?;
+            let mut rebase_options = git2::RebaseOptions::new();
+            rebase_options.quiet(true);
+            rebase_options.inmemory(true);
+            let mut rebase = repo
+                .rebase(
+                    Some(branch.head),
+                    Some(new_target_commit.id()),
+                    None,
+                    Some(&mut rebase_options),
+                )
+                .context("failed to rebase")?;
+
+            let mut rebase_success = true;
+            // check to see if these commits have already been pushed
+            let mut last_rebase_head = branch.head;
+            while rebase.next().is_some() {
+                let index = rebase
+                    .inmemory_index()
+                    .context("failed to get inmemory index")?;
+                if index.has_conflicts() {
+                    rebase_success = false;
+                    break;
                 }
 
-                // rebase failed, do a merge commit
-                rebase.abort().context("failed to abort rebase")?;
-
-                result_merge(branch)
-            },
-        )
-        .collect::<Result<Vec<_>>>()?
-        .into_iter()
-        .flatten()
-        .collect::<Vec<_>>();
+                if let Ok(commit_id) = rebase.commit(None, &committer.clone().into(), None) {
+                    last_rebase_head = commit_id.into();
+                } else {
+                    rebase_success = false;
+                    break;
+                }
+            }
+
+            if rebase_success {
+                // rebase worked out, rewrite the branch head
+                rebase.finish(None).context("failed to finish rebase")?;
+                branch.head = last_rebase_head;
+                branch.tree = branch_merge_index_tree_oid;
+                branch_writer.write(&mut branch)?;
+                return Ok((Some(branch), RebaseOutcome::Rebased));
+            }
+
+            // rebase failed, do a merge commit
+            rebase.abort().context("failed to abort rebase")?;
+
+            Ok((result_merge(branch)?, RebaseOutcome::Rebased))
+        };
+
+    let mut updated_vbranches = vec![];
+    for (branch, _) in super::get_status_by_branch(gb_repository, project_repository)?.0 {
+        let name = branch.name.clone();
+        let (branch, outcome) = update_branch(branch)?;
+        on_branch(&name, outcome);
+        updated_vbranches.extend(branch);
+    }
 
     // ok, now all the problematic branches have been unapplied
     // now we calculate and checkout new tree for the working directory
@@ -39,6 +39,14 @@ pub struct Branch {
         deserialize_with = "deserialize_u128"
     )]
     pub updated_timestamp_ms: u128,
+    // the last time the user changed the branch, by committing, moving hunks or renaming it.
+    // unlike updated_timestamp_ms, this is left alone when the status refresh rewrites the branch
+    #[serde(
+        default,
+        serialize_with = "serialize_u128",
+        deserialize_with = "deserialize_u128"
+    )]
+    pub last_activity_ms: u128,
     /// tree is the last git tree written to a session, or merge base tree if this is new. use this for delta calculation from the session data
     pub tree: git::Oid,
     /// head is id of the last "virtual" commit in this branch
@@ -49,6 +57,12 @@ pub struct Branch {
     // is Some(timestamp), the branch is considered a default destination for new changes.
     // if more than one branch is selected, the branch with the highest timestamp wins.
     pub selected_for_changes: Option<i64>,
+    // archived branches are kept in storage but hidden from the list of virtual branches
+    #[serde(default)]
+    pub archived: bool,
+    // the name the branch had when it was last pushed, to tell if the remote branch needs renaming
+    #[serde(default)]
+    pub pushed_name: Option<String>,
 }
 
 fn serialize_u128<S>(x: &u128, s: S) -> Result<S::Ok, S::Error>
@@ -71,6 +85,13 @@ impl Branch {
     pub fn refname(&self) -> git::VirtualRefname {
         self.into()
     }
+
+    pub fn mark_active(&mut self) {
+        self.last_activity_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -108,6 +129,9 @@ impl Branch {
             "meta/updated_timestamp_ms",
             "meta/ownership",
             "meta/selected_for_changes",
+            "meta/archived",
+            "meta/pushed_name",
+            "meta/last_activity_ms",
         ])?;
 
         let id: String = results[0].clone()?.try_into()?;
@@ -205,6 +229,25 @@ impl Branch {
             Err(e) => Err(e),
         }?;
 
+        let archived = match results[13].clone() {
+            Ok(archived) => archived.try_into(),
+            _ => Ok(false),
+        }
+        .unwrap_or(false);
+
+        let pushed_name = match results[14].clone() {
+            Ok(pushed_name) => Some(pushed_name.try_into()?),
+            Err(crate::reader::Error::NotFound) => None,
+            Err(e) => return Err(e),
+        };
+
+        // branches written before activity was tracked count as last active when created
+        let last_activity_ms = match results[15].clone() {
+            Ok(last_activity_ms) => last_activity_ms.try_into()?,
+            Err(crate::reader::Error::NotFound) => created_timestamp_ms,
+            Err(e) => return Err(e),
+        };
+
         Ok(Self {
             id,
             name,
@@ -226,9 +269,12 @@ impl Branch {
             })?,
             created_timestamp_ms,
             updated_timestamp_ms,
+            last_activity_ms,
             ownership,
             order,
             selected_for_changes,
+            archived,
+            pushed_name,
         })
     }
 }
@@ -129,6 +129,11 @@ impl<'writer> BranchWriter<'writer> {
             branch.updated_timestamp_ms.to_string(),
         ));
 
+        batch.push(writer::BatchTask::Write(
+            format!("branches/{}/meta/last_activity_ms", branch.id),
+            branch.last_activity_ms.to_string(),
+        ));
+
         batch.push(writer::BatchTask::Write(
             format!("branches/{}/meta/ownership", branch.id),
             branch.ownership.to_string(),
@@ -146,6 +151,23 @@ impl<'writer> BranchWriter<'writer> {
             )));
         }
 
+        batch.push(writer::BatchTask::Write(
+            format!("branches/{}/meta/archived", branch.id),
+            branch.archived.to_string(),
+        ));
+
+        if let Some(pushed_name) = &branch.pushed_name {
+            batch.push(writer::BatchTask::Write(
+                format!("branches/{}/meta/pushed_name", branch.id),
+                pushed_name.clone(),
+            ));
+        } else {
+            batch.push(writer::BatchTask::Remove(format!(
+                "branches/{}/meta/pushed_name",
+                branch.id
+            )));
+        }
+
         self.writer.batch(&batch)?;
 
         // Write in the state file as well
@@ -1,22 +1,34 @@
 use crate::error::Error;
-use std::{collections::HashMap, path, sync::Arc};
+use std::{
+    collections::HashMap,
+    path,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use anyhow::Context;
-use tokio::{sync::Semaphore, task::JoinHandle};
+use tokio::{
+    sync::{mpsc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    task::JoinHandle,
+};
+use tracing::instrument;
 
 use super::{
-    branch::{BranchId, BranchOwnershipClaims},
+    branch::{BranchId, BranchOwnershipClaims, OwnershipClaim},
     errors::{self, FetchFromTargetError},
-    target_to_base_branch, BaseBranch, RemoteBranchFile,
+    target_to_base_branch, BaseBranch, PullRequest, RemoteBranchFile, RemoteProvider, SnapshotId,
 };
 use crate::{
     askpass::AskpassBroker,
-    gb_repository, git, keys, project_repository,
+    error, gb_repository, git, keys, project_repository,
     projects::{self, ProjectId},
-    users,
+    reader, users,
     virtual_branches::state::{VirtualBranches, VirtualBranchesHandle},
 };
 
+/// How often a push that failed with a network error is retried, unless the project says otherwise.
+const DEFAULT_PUSH_RETRIES: usize = 3;
+
 #[derive(Clone)]
 pub struct Controller {
     local_data_dir: path::PathBuf,
@@ -24,6 +36,7 @@ pub struct Controller {
     users: users::Controller,
     keys: keys::Controller,
     helper: git::credentials::Helper,
+    remote_provider: Arc<Mutex<Option<Arc<dyn RemoteProvider + Send + Sync>>>>,
 
     by_project_id: Arc<tokio::sync::Mutex<HashMap<ProjectId, ControllerInner>>>,
 }
@@ -38,6 +51,7 @@ impl Controller {
     ) -> Self {
         Self {
             by_project_id: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            remote_provider: Arc::new(Mutex::new(None)),
 
             local_data_dir,
             projects,
@@ -64,20 +78,45 @@ impl Controller {
             .clone()
     }
 
+    /// Sets where [`Self::pull_requests`] gets pull requests from, replacing any previous
+    /// provider. All clones of this controller use it.
+    pub fn set_remote_provider(&self, provider: impl RemoteProvider + Send + Sync + 'static) {
+        *self.remote_provider.lock().unwrap() = Some(Arc::new(provider));
+    }
+
+    /// Lists the pull requests of the project from the provider set with
+    /// [`Self::set_remote_provider`]. There are none as long as no provider is set.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn pull_requests(&self, project_id: &ProjectId) -> Result<Vec<PullRequest>, Error> {
+        let project = self.projects.get(project_id)?;
+        let provider = self.remote_provider.lock().unwrap().clone();
+        let Some(provider) = provider else {
+            return Ok(vec![]);
+        };
+        Ok(provider
+            .list_pull_requests(project.id)
+            .await
+            .context("failed to list pull requests")?)
+    }
+
+    /// Commits the changes of the branch, or the ones in `ownership`. If
+    /// `options.reject_empty` is set, committing fails if there is nothing to commit.
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn create_commit(
         &self,
         project_id: &ProjectId,
         branch_id: &BranchId,
         message: &str,
         ownership: Option<&BranchOwnershipClaims>,
-        run_hooks: bool,
+        options: &super::CommitOptions,
     ) -> Result<git::Oid, Error> {
         self.inner(project_id)
             .await
-            .create_commit(project_id, branch_id, message, ownership, run_hooks)
+            .create_commit(project_id, branch_id, message, ownership, options)
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn can_apply_remote_branch(
         &self,
         project_id: &ProjectId,
@@ -88,6 +127,7 @@ impl Controller {
             .can_apply_remote_branch(project_id, branch_name)
     }
 
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn can_apply_virtual_branch(
         &self,
         project_id: &ProjectId,
@@ -99,6 +139,7 @@ impl Controller {
     }
 
     /// Retrieves the virtual branches state from the gitbutler repository (legacy state) and persists it into a flat TOML file
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn save_vbranches_state(
         &self,
         project_id: &ProjectId,
@@ -123,16 +164,105 @@ impl Controller {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn list_virtual_branches(
         &self,
         project_id: &ProjectId,
-    ) -> Result<(Vec<super::VirtualBranch>, bool, Vec<git::diff::FileDiff>), Error> {
+    ) -> Result<(Vec<super::VirtualBranch>, bool, Vec<super::SkippedFile>), Error> {
+        self.inner(project_id)
+            .await
+            .list_virtual_branches(project_id, false, &super::ListOptions::default())
+            .await
+    }
+
+    /// Like [`Self::list_virtual_branches`], but `options` can turn off the diffing of the
+    /// working directory when only the branch metadata is needed, or order the branches by
+    /// recent activity instead of lane order.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn list_virtual_branches_with_options(
+        &self,
+        project_id: &ProjectId,
+        options: &super::ListOptions,
+    ) -> Result<(Vec<super::VirtualBranch>, bool, Vec<super::SkippedFile>), Error> {
         self.inner(project_id)
             .await
-            .list_virtual_branches(project_id)
+            .list_virtual_branches(project_id, false, options)
             .await
     }
 
+    /// Like [`Self::list_virtual_branches`], but sends every branch to `tx` as soon as it is
+    /// listed, so that they can be shown before all of them are done. Branches are sent in the
+    /// order they are stored in rather than lane order. Fails if `tx` is closed before all of them
+    /// are sent.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn stream_virtual_branches(
+        &self,
+        project_id: &ProjectId,
+        tx: mpsc::Sender<super::VirtualBranch>,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .stream_virtual_branches(project_id, tx)
+            .await
+    }
+
+    /// Lists just the branch with the given id, without the cost of listing all of them.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn get_virtual_branch(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<super::VirtualBranch, Error> {
+        self.inner(project_id)
+            .await
+            .get_virtual_branch(project_id, branch_id)
+            .await
+    }
+
+    /// Returns the base branch, the virtual branches, the conflicts and the head commit at once,
+    /// rather than locking once for each of them.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn workspace_status(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<super::WorkspaceStatus, Error> {
+        self.inner(project_id)
+            .await
+            .workspace_status(project_id)
+            .await
+    }
+
+    /// Lists the `(id, name)` pairs of the applied branches in lane order. Much cheaper than
+    /// [`Self::list_virtual_branches`] as no diffs are calculated.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn list_applied_branch_names(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<(String, String)>, Error> {
+        self.inner(project_id)
+            .await
+            .list_applied_branch_names(project_id)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn list_virtual_branches_including_archived(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<(Vec<super::VirtualBranch>, bool, Vec<super::SkippedFile>), Error> {
+        self.inner(project_id)
+            .await
+            .list_virtual_branches(project_id, true, &super::ListOptions::default())
+            .await
+    }
+
+    /// Checks that `name` can be pushed as a git branch, which [`Self::create_virtual_branch`]
+    /// and [`Self::update_virtual_branch`] also do before changing anything.
+    pub fn validate_branch_name(&self, name: &str) -> Result<(), errors::BranchNameError> {
+        super::validate_branch_name(name)
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn create_virtual_branch(
         &self,
         project_id: &ProjectId,
@@ -144,17 +274,58 @@ impl Controller {
             .await
     }
 
+    /// Moves the given hunks of `from_branch` into a new branch called `name` and returns the
+    /// id of the new branch.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn split_to_new_branch(
+        &self,
+        project_id: &ProjectId,
+        from_branch: &BranchId,
+        ownership: &[OwnershipClaim],
+        name: &str,
+    ) -> Result<BranchId, Error> {
+        self.inner(project_id)
+            .await
+            .split_to_new_branch(project_id, from_branch, ownership, name)
+            .await
+    }
+
+    /// Returns the id of the first applied branch, creating a branch with the default name if
+    /// there is none yet.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn ensure_default_branch(&self, project_id: &ProjectId) -> Result<BranchId, Error> {
+        self.inner(project_id)
+            .await
+            .ensure_default_branch(project_id)
+            .await
+    }
+
+    /// Creates a virtual branch from a local or remote branch, and applies it if `apply` is set
+    /// and it doesn't conflict with the workspace.
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn create_virtual_branch_from_branch(
         &self,
         project_id: &ProjectId,
         branch: &git::Refname,
+        apply: bool,
     ) -> Result<BranchId, Error> {
         self.inner(project_id)
             .await
-            .create_virtual_branch_from_branch(project_id, branch)
+            .create_virtual_branch_from_branch(project_id, branch, apply)
+            .await
+    }
+
+    /// Returns how many upstream commits are not integrated yet, which is much cheaper than
+    /// [`Self::get_base_branch_data`] if that's all that is needed.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn base_branch_behind(&self, project_id: &ProjectId) -> Result<usize, Error> {
+        self.inner(project_id)
+            .await
+            .base_branch_behind(project_id)
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn get_base_branch_data(
         &self,
         project_id: &ProjectId,
@@ -162,8 +333,87 @@ impl Controller {
         self.inner(project_id)
             .await
             .get_base_branch_data(project_id)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn base_branch_exists(&self, project_id: &ProjectId) -> Result<bool, Error> {
+        self.inner(project_id).await.base_branch_exists(project_id)
+    }
+
+    /// Returns how many files, lines added and lines removed all uncommitted changes amount to,
+    /// without diffing them per branch.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn uncommitted_stats(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<super::DiffStats, Error> {
+        self.inner(project_id).await.uncommitted_stats(project_id)
+    }
+
+    /// Lists the files in the working directory that git ignores, and so never show up in any
+    /// branch no matter how they change.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn ignored_changes(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<path::PathBuf>, Error> {
+        self.inner(project_id).await.ignored_changes(project_id)
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn project_head(&self, project_id: &ProjectId) -> Result<git::Oid, Error> {
+        self.inner(project_id).await.project_head(project_id)
+    }
+
+    /// Returns the remote branch the branch was pushed to, if any, and how it compares to it.
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn branch_upstream(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<Option<super::UpstreamInfo>, Error> {
+        self.inner(project_id)
+            .await
+            .branch_upstream(project_id, branch_id)
+            .await
     }
 
+    /// Returns the commits of all applied branches and the base commit, along with how they are
+    /// connected, with commits shared between branches only once.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn commit_graph(&self, project_id: &ProjectId) -> Result<super::CommitGraph, Error> {
+        self.inner(project_id).await.commit_graph(project_id).await
+    }
+
+    /// Returns how the file at `path` changed across the commits of the branch, newest first,
+    /// skipping commits that didn't change it.
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn file_history(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        path: &path::Path,
+    ) -> Result<Vec<super::FileRevision>, Error> {
+        self.inner(project_id)
+            .await
+            .file_history(project_id, branch_id, path)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn ahead_behind(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<(usize, usize), Error> {
+        self.inner(project_id)
+            .await
+            .ahead_behind(project_id, branch_id)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn list_remote_commit_files(
         &self,
         project_id: &ProjectId,
@@ -174,6 +424,7 @@ impl Controller {
             .list_remote_commit_files(project_id, commit_oid)
     }
 
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn set_base_branch(
         &self,
         project_id: &ProjectId,
@@ -184,6 +435,17 @@ impl Controller {
             .set_base_branch(project_id, target_branch)
     }
 
+    /// Disconnects the project from its base branch, unapplying all branches and detaching HEAD
+    /// at the base commit. Refuses if an applied branch has unpushed commits.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn unset_base_branch(&self, project_id: &ProjectId) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .unset_base_branch(project_id)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn merge_virtual_branch_upstream(
         &self,
         project_id: &ProjectId,
@@ -195,13 +457,39 @@ impl Controller {
             .await
     }
 
+    /// Undoes [`Self::merge_virtual_branch_upstream`] if it left the project conflicted, putting
+    /// the branches and the working directory back to how they were before.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn abort_integration(&self, project_id: &ProjectId) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .abort_integration(project_id)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn update_base_branch(&self, project_id: &ProjectId) -> Result<(), Error> {
         self.inner(project_id)
             .await
-            .update_base_branch(project_id)
+            .update_base_branch(project_id, &mut |_, _| {})
             .await
     }
 
+    /// Like [`Self::update_base_branch`], but calls `on_branch` with the name of each branch
+    /// as soon as it is updated, along with how that went.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn update_base_branch_with_progress(
+        &self,
+        project_id: &ProjectId,
+        mut on_branch: impl FnMut(&str, super::RebaseOutcome) + Send,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .update_base_branch(project_id, &mut on_branch)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn update_virtual_branch(
         &self,
         project_id: &ProjectId,
@@ -212,6 +500,20 @@ impl Controller {
             .update_virtual_branch(project_id, branch_update)
             .await
     }
+
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn apply_updates(
+        &self,
+        project_id: &ProjectId,
+        updates: Vec<super::branch::BranchUpdateRequest>,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .apply_updates(project_id, updates)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn delete_virtual_branch(
         &self,
         project_id: &ProjectId,
@@ -223,6 +525,43 @@ impl Controller {
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn archive_branch(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .set_branch_archived(project_id, branch_id, true)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn unarchive_branch(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .set_branch_archived(project_id, branch_id, false)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn reorder_virtual_branches(
+        &self,
+        project_id: &ProjectId,
+        ordered_ids: &[BranchId],
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .reorder_virtual_branches(project_id, ordered_ids)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn apply_virtual_branch(
         &self,
         project_id: &ProjectId,
@@ -234,6 +573,19 @@ impl Controller {
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn integrate_upstream(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .integrate_upstream(project_id, branch_id)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn unapply_ownership(
         &self,
         project_id: &ProjectId,
@@ -245,6 +597,7 @@ impl Controller {
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn reset_files(
         &self,
         project_id: &ProjectId,
@@ -256,6 +609,20 @@ impl Controller {
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn discard_branch_changes(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        paths: Option<&[path::PathBuf]>,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .discard_branch_changes(project_id, branch_id, paths)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn amend(
         &self,
         project_id: &ProjectId,
@@ -268,6 +635,7 @@ impl Controller {
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn reset_virtual_branch(
         &self,
         project_id: &ProjectId,
@@ -280,6 +648,7 @@ impl Controller {
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn unapply_virtual_branch(
         &self,
         project_id: &ProjectId,
@@ -291,6 +660,73 @@ impl Controller {
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn unapply_all(&self, project_id: &ProjectId) -> Result<Vec<BranchId>, Error> {
+        self.inner(project_id).await.unapply_all(project_id).await
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn reapply_branches(
+        &self,
+        project_id: &ProjectId,
+        branch_ids: &[BranchId],
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .reapply_branches(project_id, branch_ids)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn create_snapshot(&self, project_id: &ProjectId) -> Result<SnapshotId, Error> {
+        self.inner(project_id)
+            .await
+            .create_snapshot(project_id)
+            .await
+    }
+
+    /// Deletes all but the `keep` most recent snapshots of the project, returning how many were
+    /// deleted. Taking a snapshot prunes to [`super::snapshot::MAX_SNAPSHOTS`] on its own.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn prune_snapshots(
+        &self,
+        project_id: &ProjectId,
+        keep: usize,
+    ) -> Result<usize, Error> {
+        self.inner(project_id)
+            .await
+            .prune_snapshots(project_id, keep)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %snapshot_id))]
+    pub async fn restore_snapshot(
+        &self,
+        project_id: &ProjectId,
+        snapshot_id: &SnapshotId,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .restore_snapshot(project_id, snapshot_id)
+            .await
+    }
+
+    /// Makes the branch push to `upstream`, e.g. `feature/ABC-123`, instead of a name derived from
+    /// the branch name. Fails if `upstream` is not a valid branch name.
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn set_branch_remote_name(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        upstream: &str,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .set_branch_remote_name(project_id, branch_id, upstream)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn push_virtual_branch(
         &self,
         project_id: &ProjectId,
@@ -304,6 +740,37 @@ impl Controller {
             .await
     }
 
+    /// Pushes every applied branch, returning the outcome for each branch id. A branch that
+    /// fails to push doesn't stop the others from being pushed. Pushes are retried like those of
+    /// [`Self::push_virtual_branch`].
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn push_all(
+        &self,
+        project_id: &ProjectId,
+        with_force: bool,
+        askpass: Option<AskpassBroker>,
+    ) -> Result<Vec<(BranchId, Result<(), Error>)>, Error> {
+        self.inner(project_id)
+            .await
+            .push_all(project_id, with_force, askpass)
+            .await
+    }
+
+    /// Makes virtual branches push to `remote_name` instead of the remote of the base branch,
+    /// which keeps fetching from the target. Useful when working on a fork.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn set_push_remote(
+        &self,
+        project_id: &ProjectId,
+        remote_name: &str,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .set_push_remote(project_id, remote_name)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn cherry_pick(
         &self,
         project_id: &ProjectId,
@@ -316,6 +783,7 @@ impl Controller {
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn list_remote_branches(
         &self,
         project_id: &ProjectId,
@@ -325,6 +793,7 @@ impl Controller {
             .list_remote_branches(project_id)
     }
 
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn get_remote_branch_data(
         &self,
         project_id: &ProjectId,
@@ -335,6 +804,77 @@ impl Controller {
             .get_remote_branch_data(project_id, refname)
     }
 
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn list_conflicts(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<project_repository::conflicts::ConflictedFile>, Error> {
+        self.inner(project_id).await.list_conflicts(project_id)
+    }
+
+    /// Returns the base, ours and theirs versions of a conflicted file as recorded in the index,
+    /// along with the file in the working directory. Fails if the file is not conflicted.
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn conflict_versions(
+        &self,
+        project_id: &ProjectId,
+        path: &path::Path,
+    ) -> Result<super::ConflictVersions, Error> {
+        self.inner(project_id)
+            .await
+            .conflict_versions(project_id, path)
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn list_commit_files(
+        &self,
+        project_id: &ProjectId,
+        commit_oid: git::Oid,
+    ) -> Result<Vec<super::CommitFile>, Error> {
+        self.inner(project_id)
+            .await
+            .list_commit_files(project_id, commit_oid)
+    }
+
+    /// Returns a reader over the tree of the given commit, e.g. to browse the files as they were
+    /// at that commit.
+    #[instrument(skip_all, fields(%project_id, %commit_oid))]
+    pub async fn reader_at_commit(
+        &self,
+        project_id: &ProjectId,
+        commit_oid: git::Oid,
+    ) -> Result<reader::OwnedReader, Error> {
+        self.inner(project_id)
+            .await
+            .reader_at_commit(project_id, commit_oid)
+    }
+
+    /// Lists the files that differ between any two commits of the project, with `to` being
+    /// compared against `from`.
+    #[instrument(skip_all, fields(%project_id, %from, %to))]
+    pub async fn diff_commits(
+        &self,
+        project_id: &ProjectId,
+        from: git::Oid,
+        to: git::Oid,
+    ) -> Result<Vec<super::CommitFile>, Error> {
+        self.inner(project_id)
+            .await
+            .diff_commits(project_id, from, to)
+    }
+
+    #[instrument(skip_all, fields(%project_id))]
+    pub async fn commit_author(
+        &self,
+        project_id: &ProjectId,
+        commit_oid: git::Oid,
+    ) -> Result<super::Author, Error> {
+        self.inner(project_id)
+            .await
+            .commit_author(project_id, commit_oid)
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn squash(
         &self,
         project_id: &ProjectId,
@@ -347,19 +887,35 @@ impl Controller {
             .await
     }
 
+    /// Squashes the commit into its parent on the branch, keeping the parent's message.
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn fixup_commit(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        commit_oid: git::Oid,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .fixup_commit(project_id, branch_id, commit_oid)
+            .await
+    }
+
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
     pub async fn update_commit_message(
         &self,
         project_id: &ProjectId,
         branch_id: &BranchId,
         commit_oid: git::Oid,
         message: &str,
-    ) -> Result<(), Error> {
+    ) -> Result<git::Oid, Error> {
         self.inner(project_id)
             .await
             .update_commit_message(project_id, branch_id, commit_oid, message)
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id))]
     pub async fn fetch_from_target(
         &self,
         project_id: &ProjectId,
@@ -371,6 +927,7 @@ impl Controller {
             .await
     }
 
+    #[instrument(skip_all, fields(%project_id, %target_branch_id))]
     pub async fn move_commit(
         &self,
         project_id: &ProjectId,
@@ -382,12 +939,44 @@ impl Controller {
             .move_commit(project_id, target_branch_id, commit_oid)
             .await
     }
+
+    /// Replays the commits of the branch on top of `onto`, which has to be based on the base
+    /// commit. Fails without changing anything if the branch conflicts with `onto`.
+    #[instrument(skip_all, fields(%project_id, %branch_id, %onto))]
+    pub async fn rebase_branch(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        onto: git::Oid,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .rebase_branch(project_id, branch_id, onto)
+            .await
+    }
+
+    /// Fast-forwards the base to the head of the branch as if it was merged upstream, rebases the
+    /// other applied branches onto it and deletes the branch. Fails without changing anything if
+    /// the branch has uncommitted changes or any of the branches would conflict.
+    #[instrument(skip_all, fields(%project_id, %branch_id))]
+    pub async fn land_branch(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<(), Error> {
+        self.inner(project_id)
+            .await
+            .land_branch(project_id, branch_id)
+            .await
+    }
 }
 
 #[derive(Clone)]
 struct ControllerInner {
     local_data_dir: path::PathBuf,
-    semaphore: Arc<Semaphore>,
+    // reads of the virtual branches state take a shared lock, anything that changes it an exclusive
+    // one.
+    lock: Arc<RwLock<()>>,
 
     projects: projects::Controller,
     users: users::Controller,
@@ -405,7 +994,7 @@ impl ControllerInner {
     ) -> Self {
         Self {
             local_data_dir: data_dir.to_path_buf(),
-            semaphore: Arc::new(Semaphore::new(1)),
+            lock: Arc::new(RwLock::new(())),
             projects: projects.clone(),
             users: users.clone(),
             keys: keys.clone(),
@@ -419,9 +1008,9 @@ impl ControllerInner {
         branch_id: &BranchId,
         message: &str,
         ownership: Option<&BranchOwnershipClaims>,
-        run_hooks: bool,
+        options: &super::CommitOptions,
     ) -> Result<git::Oid, Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, user| {
             let signing_key = project_repository
@@ -443,7 +1032,7 @@ impl ControllerInner {
                 ownership,
                 signing_key.as_ref(),
                 user,
-                run_hooks,
+                options,
             )
             .map_err(Into::into)
         })
@@ -544,14 +1133,148 @@ impl ControllerInner {
         })
     }
 
+    // listings only read, with the shared lock. if the ownership they recalculate for the applied
+    // branches has to be written, they are repeated with the exclusive lock, writing it.
     pub async fn list_virtual_branches(
         &self,
         project_id: &ProjectId,
-    ) -> Result<(Vec<super::VirtualBranch>, bool, Vec<git::diff::FileDiff>), Error> {
-        let _permit = self.semaphore.acquire().await;
+        include_archived: bool,
+        options: &super::ListOptions,
+    ) -> Result<(Vec<super::VirtualBranch>, bool, Vec<super::SkippedFile>), Error> {
+        let list = |read_only| {
+            self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+                match super::list_virtual_branches_with_options(
+                    gb_repository,
+                    project_repository,
+                    include_archived,
+                    &super::ListOptions {
+                        read_only,
+                        ..*options
+                    },
+                ) {
+                    Err(errors::ListVirtualBranchesError::StatusOutdated) => Ok(None),
+                    result => result.map(Some).map_err(Into::into),
+                }
+            })
+        };
+
+        {
+            let _permit = self.read_lock().await;
+            if let Some(listed) = list(true)? {
+                return Ok(listed);
+            }
+        }
+
+        let _permit = self.write_lock().await;
+        Ok(list(false)?.context("virtual branches status is outdated after writing it")?)
+    }
+
+    pub async fn stream_virtual_branches(
+        &self,
+        project_id: &ProjectId,
+        tx: mpsc::Sender<super::VirtualBranch>,
+    ) -> Result<(), Error> {
+        let stream = |read_only| {
+            let tx = tx.clone();
+            // sending waits for the receiver to keep up, so the listing can't block the runtime
+            self.with_verify_branch_async(
+                project_id,
+                move |gb_repository, project_repository, _| {
+                    match super::for_each_virtual_branch(
+                        gb_repository,
+                        project_repository,
+                        false,
+                        &super::ListOptions {
+                            read_only,
+                            ..super::ListOptions::default()
+                        },
+                        |branch| {
+                            tx.blocking_send(branch)
+                                .context("virtual branches receiver was closed")
+                        },
+                    ) {
+                        // the status is read before any branch is sent
+                        Err(errors::ListVirtualBranchesError::StatusOutdated) => Ok(false),
+                        result => result.map(|_| true).map_err(Into::into),
+                    }
+                },
+            )
+        };
+
+        {
+            let _permit = self.read_lock().await;
+            if stream(true)?.await.map_err(Error::from_err)?? {
+                return Ok(());
+            }
+        }
+
+        let _permit = self.write_lock().await;
+        stream(false)?.await.map_err(Error::from_err)??;
+        Ok(())
+    }
+
+    pub async fn get_virtual_branch(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<super::VirtualBranch, Error> {
+        let get = |read_only| {
+            self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+                match super::get_virtual_branch(
+                    gb_repository,
+                    project_repository,
+                    branch_id,
+                    read_only,
+                ) {
+                    Err(errors::GetVirtualBranchError::StatusOutdated) => Ok(None),
+                    result => result.map(Some).map_err(Into::into),
+                }
+            })
+        };
+
+        {
+            let _permit = self.read_lock().await;
+            if let Some(branch) = get(true)? {
+                return Ok(branch);
+            }
+        }
+
+        let _permit = self.write_lock().await;
+        Ok(get(false)?.context("virtual branches status is outdated after writing it")?)
+    }
+
+    pub async fn workspace_status(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<super::WorkspaceStatus, Error> {
+        let status = |read_only| {
+            self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+                match super::workspace_status(gb_repository, project_repository, read_only) {
+                    Err(errors::ListVirtualBranchesError::StatusOutdated) => Ok(None),
+                    result => result.map(Some).map_err(Into::into),
+                }
+            })
+        };
+
+        {
+            let _permit = self.read_lock().await;
+            if let Some(status) = status(true)? {
+                return Ok(status);
+            }
+        }
+
+        let _permit = self.write_lock().await;
+        Ok(status(false)?.context("virtual branches status is outdated after writing it")?)
+    }
+
+    pub async fn list_applied_branch_names(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let _permit = self.read_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
-            super::list_virtual_branches(gb_repository, project_repository).map_err(Into::into)
+            super::applied_branch_names(gb_repository, project_repository).map_err(Into::into)
         })
     }
 
@@ -560,7 +1283,7 @@ impl ControllerInner {
         project_id: &ProjectId,
         create: &super::branch::BranchCreateRequest,
     ) -> Result<BranchId, Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             let branch_id =
@@ -569,12 +1292,52 @@ impl ControllerInner {
         })
     }
 
+    pub async fn split_to_new_branch(
+        &self,
+        project_id: &ProjectId,
+        from_branch: &BranchId,
+        ownership: &[OwnershipClaim],
+        name: &str,
+    ) -> Result<BranchId, Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            let branch = super::split_to_new_branch(
+                gb_repository,
+                project_repository,
+                from_branch,
+                ownership,
+                name,
+            )?;
+            Ok(branch.id)
+        })
+    }
+
+    pub async fn ensure_default_branch(&self, project_id: &ProjectId) -> Result<BranchId, Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            if let Some(branch_id) =
+                super::applied_branch_ids(gb_repository, project_repository)?.first()
+            {
+                return Ok(*branch_id);
+            }
+            let branch = super::create_virtual_branch(
+                gb_repository,
+                project_repository,
+                &super::branch::BranchCreateRequest::default(),
+            )?;
+            Ok(branch.id)
+        })
+    }
+
     pub async fn create_virtual_branch_from_branch(
         &self,
         project_id: &ProjectId,
         branch: &git::Refname,
+        apply: bool,
     ) -> Result<BranchId, Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, user| {
             let signing_key = project_repository
@@ -592,16 +1355,168 @@ impl ControllerInner {
                 gb_repository,
                 project_repository,
                 branch,
+                apply,
                 signing_key.as_ref(),
                 user,
             )?)
         })
     }
 
-    pub fn get_base_branch_data(
+    pub async fn get_base_branch_data(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Option<BaseBranch>, Error> {
+        let _permit = self.read_lock().await;
+
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        let user = self.users.get_user()?;
+        let gb_repository = gb_repository::Repository::open(
+            &self.local_data_dir,
+            &project_repository,
+            user.as_ref(),
+        )
+        .context("failed to open gitbutler repository")?;
+        Ok(super::get_base_branch_data(
+            &gb_repository,
+            &project_repository,
+        )?)
+    }
+
+    pub async fn base_branch_behind(&self, project_id: &ProjectId) -> Result<usize, Error> {
+        let _permit = self.read_lock().await;
+
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        let user = self.users.get_user()?;
+        let gb_repository = gb_repository::Repository::open(
+            &self.local_data_dir,
+            &project_repository,
+            user.as_ref(),
+        )
+        .context("failed to open gitbutler repository")?;
+        Ok(super::base_branch_behind(
+            &gb_repository,
+            &project_repository,
+        )?)
+    }
+
+    /// Checks whether a base branch is configured for the project.
+    ///
+    /// Unlike the other accessors this does not wait for the project lock, so it returns even
+    /// while a long running operation is in progress. The answer is a best-effort snapshot and
+    /// may be outdated by the time it is used.
+    pub fn base_branch_exists(&self, project_id: &ProjectId) -> Result<bool, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        let user = self.users.get_user()?;
+        let gb_repository = gb_repository::Repository::open(
+            &self.local_data_dir,
+            &project_repository,
+            user.as_ref(),
+        )
+        .context("failed to open gitbutler repository")?;
+        let target = gb_repository
+            .default_target()
+            .context("failed to get default target")?;
+        Ok(target.is_some())
+    }
+
+    pub fn uncommitted_stats(&self, project_id: &ProjectId) -> Result<super::DiffStats, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        super::uncommitted_stats(&project_repository).map_err(Into::into)
+    }
+
+    pub fn ignored_changes(&self, project_id: &ProjectId) -> Result<Vec<path::PathBuf>, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        super::ignored_changes(&project_repository).map_err(Into::into)
+    }
+
+    /// Returns the commit the project's HEAD currently points to. Comparing it against the
+    /// expected head tells whether HEAD was moved outside of GitButler.
+    pub fn project_head(&self, project_id: &ProjectId) -> Result<git::Oid, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        let head_commit = project_repository
+            .get_head()
+            .context("failed to get head")?
+            .peel_to_commit()
+            .context("failed to peel head to commit")?;
+        Ok(head_commit.id())
+    }
+
+    pub async fn branch_upstream(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<Option<super::UpstreamInfo>, Error> {
+        let _permit = self.read_lock().await;
+
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        let user = self.users.get_user()?;
+        let gb_repository = gb_repository::Repository::open(
+            &self.local_data_dir,
+            &project_repository,
+            user.as_ref(),
+        )
+        .context("failed to open gitbutler repository")?;
+        Ok(super::branch_upstream(
+            &gb_repository,
+            &project_repository,
+            branch_id,
+        )?)
+    }
+
+    pub async fn commit_graph(&self, project_id: &ProjectId) -> Result<super::CommitGraph, Error> {
+        let _permit = self.read_lock().await;
+
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        let user = self.users.get_user()?;
+        let gb_repository = gb_repository::Repository::open(
+            &self.local_data_dir,
+            &project_repository,
+            user.as_ref(),
+        )
+        .context("failed to open gitbutler repository")?;
+        Ok(super::commit_graph(&gb_repository, &project_repository)?)
+    }
+
+    pub async fn file_history(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        path: &path::Path,
+    ) -> Result<Vec<super::FileRevision>, Error> {
+        let _permit = self.read_lock().await;
+
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        let user = self.users.get_user()?;
+        let gb_repository = gb_repository::Repository::open(
+            &self.local_data_dir,
+            &project_repository,
+            user.as_ref(),
+        )
+        .context("failed to open gitbutler repository")?;
+        Ok(super::file_history(
+            &gb_repository,
+            &project_repository,
+            branch_id,
+            path,
+        )?)
+    }
+
+    pub async fn ahead_behind(
         &self,
         project_id: &ProjectId,
-    ) -> Result<Option<BaseBranch>, Error> {
+        branch_id: &BranchId,
+    ) -> Result<(usize, usize), Error> {
+        let _permit = self.read_lock().await;
+
         let project = self.projects.get(project_id)?;
         let project_repository = project_repository::Repository::open(&project)?;
         let user = self.users.get_user()?;
@@ -611,9 +1526,10 @@ impl ControllerInner {
             user.as_ref(),
         )
         .context("failed to open gitbutler repository")?;
-        Ok(super::get_base_branch_data(
+        Ok(super::ahead_behind(
             &gb_repository,
             &project_repository,
+            branch_id,
         )?)
     }
 
@@ -659,12 +1575,20 @@ impl ControllerInner {
         )?)
     }
 
+    pub async fn unset_base_branch(&self, project_id: &ProjectId) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            Ok(super::unset_base_branch(gb_repository, project_repository)?)
+        })
+    }
+
     pub async fn merge_virtual_branch_upstream(
         &self,
         project_id: &ProjectId,
         branch_id: &BranchId,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, user| {
             let signing_key = project_repository
@@ -678,19 +1602,42 @@ impl ControllerInner {
                 })
                 .transpose()?;
 
-            super::merge_virtual_branch_upstream(
+            let snapshot_id = super::snapshot::create(gb_repository, project_repository)?;
+            let wd_tree = project_repository.get_wd_tree()?.id();
+
+            let result = super::merge_virtual_branch_upstream(
                 gb_repository,
                 project_repository,
                 branch_id,
                 signing_key.as_ref(),
                 user,
-            )
-            .map_err(Into::into)
+            );
+
+            // the merge already happened, failing to record it only means it can't be aborted
+            if let Err(error) =
+                super::snapshot::record_integration(project_repository, &snapshot_id, wd_tree)
+            {
+                tracing::warn!(%project_id, ?error, "failed to record upstream integration");
+            }
+            result.map_err(Into::into)
         })
     }
 
-    pub async fn update_base_branch(&self, project_id: &ProjectId) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+    pub async fn abort_integration(&self, project_id: &ProjectId) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::snapshot::abort_integration(gb_repository, project_repository)
+                .map_err(Into::into)
+        })
+    }
+
+    pub async fn update_base_branch(
+        &self,
+        project_id: &ProjectId,
+        on_branch: &mut (dyn FnMut(&str, super::RebaseOutcome) + Send),
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, user| {
             let signing_key = project_repository
@@ -709,6 +1656,7 @@ impl ControllerInner {
                 project_repository,
                 user,
                 signing_key.as_ref(),
+                on_branch,
             )
             .map_err(Into::into)
         })
@@ -719,7 +1667,7 @@ impl ControllerInner {
         project_id: &ProjectId,
         branch_update: super::branch::BranchUpdateRequest,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             super::update_branch(gb_repository, project_repository, branch_update)?;
@@ -727,12 +1675,39 @@ impl ControllerInner {
         })
     }
 
+    pub async fn set_branch_remote_name(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        upstream: &str,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::set_branch_remote_name(gb_repository, project_repository, branch_id, upstream)
+                .map_err(Into::into)
+        })
+    }
+
+    pub async fn apply_updates(
+        &self,
+        project_id: &ProjectId,
+        updates: Vec<super::branch::BranchUpdateRequest>,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::update_branches(gb_repository, project_repository, updates)?;
+            Ok(())
+        })
+    }
+
     pub async fn delete_virtual_branch(
         &self,
         project_id: &ProjectId,
         branch_id: &BranchId,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             super::delete_branch(gb_repository, project_repository, branch_id)?;
@@ -740,12 +1715,37 @@ impl ControllerInner {
         })
     }
 
+    pub async fn set_branch_archived(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        archived: bool,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::set_branch_archived(gb_repository, project_repository, branch_id, archived)
+        })
+    }
+
+    pub async fn reorder_virtual_branches(
+        &self,
+        project_id: &ProjectId,
+        ordered_ids: &[BranchId],
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::reorder_branches(gb_repository, project_repository, ordered_ids)
+        })
+    }
+
     pub async fn apply_virtual_branch(
         &self,
         project_id: &ProjectId,
         branch_id: &BranchId,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, user| {
             let signing_key = project_repository
@@ -770,12 +1770,42 @@ impl ControllerInner {
         })
     }
 
+    pub async fn integrate_upstream(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, user| {
+            let signing_key = project_repository
+                .config()
+                .sign_commits()
+                .context("failed to get sign commits option")?
+                .then(|| {
+                    self.keys
+                        .get_or_create()
+                        .context("failed to get private key")
+                })
+                .transpose()?;
+
+            super::integrate_upstream(
+                gb_repository,
+                project_repository,
+                branch_id,
+                signing_key.as_ref(),
+                user,
+            )
+            .map_err(Into::into)
+        })
+    }
+
     pub async fn unapply_ownership(
         &self,
         project_id: &ProjectId,
         ownership: &BranchOwnershipClaims,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             super::unapply_ownership(gb_repository, project_repository, ownership)
@@ -788,20 +1818,34 @@ impl ControllerInner {
         project_id: &ProjectId,
         ownership: &Vec<String>,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |_, project_repository, _| {
             super::reset_files(project_repository, ownership).map_err(Into::into)
         })
     }
 
+    pub async fn discard_branch_changes(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        paths: Option<&[path::PathBuf]>,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::discard_branch_changes(gb_repository, project_repository, branch_id, paths)
+                .map_err(Into::into)
+        })
+    }
+
     pub async fn amend(
         &self,
         project_id: &ProjectId,
         branch_id: &BranchId,
         ownership: &BranchOwnershipClaims,
     ) -> Result<git::Oid, Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             super::amend(gb_repository, project_repository, branch_id, ownership)
@@ -815,7 +1859,7 @@ impl ControllerInner {
         branch_id: &BranchId,
         target_commit_oid: git::Oid,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             super::reset_branch(
@@ -833,7 +1877,7 @@ impl ControllerInner {
         project_id: &ProjectId,
         branch_id: &BranchId,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             super::unapply_branch(gb_repository, project_repository, branch_id)
@@ -842,6 +1886,127 @@ impl ControllerInner {
         })
     }
 
+    pub async fn unapply_all(&self, project_id: &ProjectId) -> Result<Vec<BranchId>, Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            let mut unapplied = vec![];
+            for branch_id in super::applied_branch_ids(gb_repository, project_repository)? {
+                match super::unapply_branch(gb_repository, project_repository, &branch_id) {
+                    Ok(Some(_)) => unapplied.push(branch_id),
+                    // branches without any changes are deleted instead, so there is nothing to
+                    // re-apply later
+                    Ok(None) => {}
+                    Err(error) => {
+                        return Err(Error::from(error).context(format!(
+                            "failed to unapply branch {}, already unapplied: [{}]",
+                            branch_id,
+                            unapplied
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )))
+                    }
+                }
+            }
+            Ok(unapplied)
+        })
+    }
+
+    /// Applies `branch_ids` in order, stopping at the first one that would conflict.
+    ///
+    /// Every branch is checked before it is applied, so a conflicting branch is never left
+    /// checked out half-way. As with [`Self::can_apply_virtual_branch`], branches that are
+    /// behind the base branch count as conflicting.
+    pub async fn reapply_branches(
+        &self,
+        project_id: &ProjectId,
+        branch_ids: &[BranchId],
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, user| {
+            let signing_key = project_repository
+                .config()
+                .sign_commits()
+                .context("failed to get sign commits option")?
+                .then(|| {
+                    self.keys
+                        .get_or_create()
+                        .context("failed to get private key")
+                })
+                .transpose()?;
+
+            for (reapplied, branch_id) in branch_ids.iter().enumerate() {
+                let progress = || {
+                    format!(
+                        "failed to reapply branch {}, already reapplied {} of {} branches",
+                        branch_id,
+                        reapplied,
+                        branch_ids.len()
+                    )
+                };
+
+                let mergeable = super::is_virtual_branch_mergeable(
+                    gb_repository,
+                    project_repository,
+                    branch_id,
+                )
+                .map_err(|error| Error::from(error).context(progress()))?;
+                if !mergeable {
+                    return Err(
+                        Error::from(errors::ApplyBranchError::BranchConflicts(*branch_id))
+                            .context(progress()),
+                    );
+                }
+
+                super::apply_branch(
+                    gb_repository,
+                    project_repository,
+                    branch_id,
+                    signing_key.as_ref(),
+                    user,
+                )
+                .map_err(|error| Error::from(error).context(progress()))?;
+            }
+            Ok(())
+        })
+    }
+
+    pub async fn create_snapshot(&self, project_id: &ProjectId) -> Result<SnapshotId, Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::snapshot::create(gb_repository, project_repository).map_err(Into::into)
+        })
+    }
+
+    pub async fn prune_snapshots(
+        &self,
+        project_id: &ProjectId,
+        keep: usize,
+    ) -> Result<usize, Error> {
+        let _permit = self.write_lock().await;
+
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        super::snapshot::prune(&project_repository, keep).map_err(Into::into)
+    }
+
+    pub async fn restore_snapshot(
+        &self,
+        project_id: &ProjectId,
+        snapshot_id: &SnapshotId,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::snapshot::restore(gb_repository, project_repository, snapshot_id)
+                .map_err(Into::into)
+        })
+    }
+
     pub async fn push_virtual_branch(
         &self,
         project_id: &ProjectId,
@@ -849,11 +2014,15 @@ impl ControllerInner {
         with_force: bool,
         askpass: Option<(AskpassBroker, Option<BranchId>)>,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
         let helper = self.helper.clone();
         let project_id = *project_id;
         let branch_id = *branch_id;
         self.with_verify_branch_async(&project_id, move |gb_repository, project_repository, _| {
+            let retries = project_repository
+                .project()
+                .push_retries
+                .unwrap_or(DEFAULT_PUSH_RETRIES);
             Ok(super::push(
                 project_repository,
                 gb_repository,
@@ -861,19 +2030,85 @@ impl ControllerInner {
                 with_force,
                 &helper,
                 askpass,
+                retries,
             )?)
         })?
         .await
         .map_err(Error::from_err)?
     }
 
+    pub async fn push_all(
+        &self,
+        project_id: &ProjectId,
+        with_force: bool,
+        askpass: Option<AskpassBroker>,
+    ) -> Result<Vec<(BranchId, Result<(), Error>)>, Error> {
+        let _permit = self.write_lock().await;
+        let helper = self.helper.clone();
+        self.with_verify_branch_async(project_id, move |gb_repository, project_repository, _| {
+            let retries = project_repository
+                .project()
+                .push_retries
+                .unwrap_or(DEFAULT_PUSH_RETRIES);
+            Ok(
+                super::applied_branch_ids(gb_repository, project_repository)?
+                    .into_iter()
+                    .map(|branch_id| {
+                        let result = super::push(
+                            project_repository,
+                            gb_repository,
+                            &branch_id,
+                            with_force,
+                            &helper,
+                            askpass.clone().map(|broker| (broker, Some(branch_id))),
+                            retries,
+                        )
+                        .map_err(Into::into);
+                        (branch_id, result)
+                    })
+                    .collect(),
+            )
+        })?
+        .await
+        .map_err(Error::from_err)?
+    }
+
+    pub async fn set_push_remote(
+        &self,
+        project_id: &ProjectId,
+        remote_name: &str,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        project_repository
+            .git_repository
+            .find_remote(remote_name)
+            .context(error::Context::new(
+                error::Code::Validation,
+                format!("remote '{}' not found", remote_name),
+            ))?;
+
+        self.projects
+            .update(&projects::UpdateRequest {
+                id: *project_id,
+                push_remote_name: Some(remote_name.to_string()),
+                ..Default::default()
+            })
+            .await
+            .context("failed to update project")?;
+
+        Ok(())
+    }
+
     pub async fn cherry_pick(
         &self,
         project_id: &ProjectId,
         branch_id: &BranchId,
         commit_oid: git::Oid,
     ) -> Result<Option<git::Oid>, Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             super::cherry_pick(gb_repository, project_repository, branch_id, commit_oid)
@@ -921,13 +2156,74 @@ impl ControllerInner {
         )?)
     }
 
+    pub fn list_conflicts(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<project_repository::conflicts::ConflictedFile>, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        project_repository::conflicts::conflicted_files(&project_repository).map_err(Into::into)
+    }
+
+    pub fn conflict_versions(
+        &self,
+        project_id: &ProjectId,
+        path: &path::Path,
+    ) -> Result<super::ConflictVersions, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        super::conflict_versions(&project_repository, path).map_err(Into::into)
+    }
+
+    pub fn list_commit_files(
+        &self,
+        project_id: &ProjectId,
+        commit_oid: git::Oid,
+    ) -> Result<Vec<super::CommitFile>, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        super::list_commit_files(&project_repository, commit_oid).map_err(Into::into)
+    }
+
+    pub fn reader_at_commit(
+        &self,
+        project_id: &ProjectId,
+        commit_oid: git::Oid,
+    ) -> Result<reader::OwnedReader, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        reader::OwnedReader::from_commit(project_repository.git_repository, commit_oid)
+            .map_err(Into::into)
+    }
+
+    pub fn diff_commits(
+        &self,
+        project_id: &ProjectId,
+        from: git::Oid,
+        to: git::Oid,
+    ) -> Result<Vec<super::CommitFile>, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        super::diff_commits(&project_repository, from, to).map_err(Into::into)
+    }
+
+    pub fn commit_author(
+        &self,
+        project_id: &ProjectId,
+        commit_oid: git::Oid,
+    ) -> Result<super::Author, Error> {
+        let project = self.projects.get(project_id)?;
+        let project_repository = project_repository::Repository::open(&project)?;
+        super::commit_author(&project_repository, commit_oid).map_err(Into::into)
+    }
+
     pub async fn squash(
         &self,
         project_id: &ProjectId,
         branch_id: &BranchId,
         commit_oid: git::Oid,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             super::squash(gb_repository, project_repository, branch_id, commit_oid)
@@ -935,14 +2231,28 @@ impl ControllerInner {
         })
     }
 
+    pub async fn fixup_commit(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        commit_oid: git::Oid,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::fixup(gb_repository, project_repository, branch_id, commit_oid)
+                .map_err(Into::into)
+        })
+    }
+
     pub async fn update_commit_message(
         &self,
         project_id: &ProjectId,
         branch_id: &BranchId,
         commit_oid: git::Oid,
         message: &str,
-    ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+    ) -> Result<git::Oid, Error> {
+        let _permit = self.write_lock().await;
         self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
             super::update_commit_message(
                 gb_repository,
@@ -1016,7 +2326,7 @@ impl ControllerInner {
         target_branch_id: &BranchId,
         commit_oid: git::Oid,
     ) -> Result<(), Error> {
-        let _permit = self.semaphore.acquire().await;
+        let _permit = self.write_lock().await;
 
         self.with_verify_branch(project_id, |gb_repository, project_repository, user| {
             let signing_key = project_repository
@@ -1040,9 +2350,57 @@ impl ControllerInner {
             .map_err(Into::into)
         })
     }
+
+    pub async fn rebase_branch(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+        onto: git::Oid,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::rebase_branch(gb_repository, project_repository, branch_id, onto)
+                .map_err(Into::into)
+        })
+    }
+
+    pub async fn land_branch(
+        &self,
+        project_id: &ProjectId,
+        branch_id: &BranchId,
+    ) -> Result<(), Error> {
+        let _permit = self.write_lock().await;
+
+        self.with_verify_branch(project_id, |gb_repository, project_repository, _| {
+            super::land_branch(gb_repository, project_repository, branch_id).map_err(Into::into)
+        })
+    }
 }
 
 impl ControllerInner {
+    // time spent waiting for the lock is traced on its own, so that contention can be told apart
+    // from slow operations within the span of the calling method.
+    async fn read_lock(&self) -> RwLockReadGuard<'_, ()> {
+        let started = Instant::now();
+        let guard = self.lock.read().await;
+        tracing::debug!(
+            lock_wait_ms = started.elapsed().as_millis() as u64,
+            "acquired read lock"
+        );
+        guard
+    }
+
+    async fn write_lock(&self) -> RwLockWriteGuard<'_, ()> {
+        let started = Instant::now();
+        let guard = self.lock.write().await;
+        tracing::debug!(
+            lock_wait_ms = started.elapsed().as_millis() as u64,
+            "acquired write lock"
+        );
+        guard
+    }
+
     fn with_verify_branch<T>(
         &self,
         project_id: &ProjectId,
@@ -1,4 +1,6 @@
-use super::{branch::BranchOwnershipClaims, BranchId, GITBUTLER_INTEGRATION_REFERENCE};
+use std::path::PathBuf;
+
+use super::{branch::BranchOwnershipClaims, BranchId, SnapshotId, GITBUTLER_INTEGRATION_REFERENCE};
 use crate::error::{AnyhowContextExt, Code, Context, ErrorWithContext};
 use crate::{
     error, git,
@@ -99,6 +101,35 @@ impl ErrorWithContext for ApplyBranchError {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum IntegrateUpstreamError {
+    #[error("project is in conflict state")]
+    Conflict(ProjectConflict),
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error("branch conflicts with the base branch")]
+    BranchConflicts(BranchId),
+    #[error("default target not set")]
+    DefaultTargetNotSet(DefaultTargetNotSet),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for IntegrateUpstreamError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            IntegrateUpstreamError::Conflict(ctx) => ctx.to_context(),
+            IntegrateUpstreamError::BranchNotFound(ctx) => ctx.to_context(),
+            IntegrateUpstreamError::BranchConflicts(id) => error::Context::new(
+                Code::Branches,
+                format!("Branch {} conflicts with the base branch", id),
+            ),
+            IntegrateUpstreamError::DefaultTargetNotSet(ctx) => ctx.to_context(),
+            IntegrateUpstreamError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum UnapplyOwnershipError {
     #[error("default target not set")]
@@ -119,6 +150,48 @@ impl ErrorWithContext for UnapplyOwnershipError {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum DiscardBranchChangesError {
+    #[error("default target not set")]
+    DefaultTargetNotSet(DefaultTargetNotSet),
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error("project is in conflict state")]
+    Conflict(ProjectConflict),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for DiscardBranchChangesError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            DiscardBranchChangesError::DefaultTargetNotSet(ctx) => ctx.to_context(),
+            DiscardBranchChangesError::BranchNotFound(ctx) => ctx.to_context(),
+            DiscardBranchChangesError::Conflict(ctx) => ctx.to_context(),
+            DiscardBranchChangesError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommitAuthorError {
+    #[error("commit {0} not found")]
+    CommitNotFound(git::Oid),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for CommitAuthorError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            CommitAuthorError::CommitNotFound(oid) => {
+                error::Context::new(Code::Branches, format!("commit {} not found", oid))
+            }
+            CommitAuthorError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum UnapplyBranchError {
     #[error("default target not set")]
@@ -143,6 +216,9 @@ impl ErrorWithContext for UnapplyBranchError {
 pub enum ListVirtualBranchesError {
     #[error("project")]
     DefaultTargetNotSet(DefaultTargetNotSet),
+    /// Listing without writing found the written ownership of the applied branches outdated.
+    #[error("virtual branches status is outdated")]
+    StatusOutdated,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -151,16 +227,53 @@ impl ErrorWithContext for ListVirtualBranchesError {
     fn context(&self) -> Option<Context> {
         match self {
             ListVirtualBranchesError::DefaultTargetNotSet(ctx) => ctx.to_context().into(),
+            ListVirtualBranchesError::StatusOutdated => None,
             ListVirtualBranchesError::Other(error) => error.custom_context(),
         }
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum GetVirtualBranchError {
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error("project")]
+    DefaultTargetNotSet(DefaultTargetNotSet),
+    /// See [`ListVirtualBranchesError::StatusOutdated`].
+    #[error("virtual branches status is outdated")]
+    StatusOutdated,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for GetVirtualBranchError {
+    fn context(&self) -> Option<Context> {
+        match self {
+            GetVirtualBranchError::BranchNotFound(ctx) => ctx.to_context().into(),
+            GetVirtualBranchError::DefaultTargetNotSet(ctx) => ctx.to_context().into(),
+            GetVirtualBranchError::StatusOutdated => None,
+            GetVirtualBranchError::Other(error) => error.custom_context(),
+        }
+    }
+}
+
+impl From<ListVirtualBranchesError> for GetVirtualBranchError {
+    fn from(value: ListVirtualBranchesError) -> Self {
+        match value {
+            ListVirtualBranchesError::DefaultTargetNotSet(ctx) => Self::DefaultTargetNotSet(ctx),
+            ListVirtualBranchesError::StatusOutdated => Self::StatusOutdated,
+            ListVirtualBranchesError::Other(error) => Self::Other(error),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum CreateVirtualBranchError {
     #[error("project")]
     DefaultTargetNotSet(DefaultTargetNotSet),
     #[error(transparent)]
+    InvalidName(#[from] BranchNameError),
+    #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
@@ -168,11 +281,45 @@ impl ErrorWithContext for CreateVirtualBranchError {
     fn context(&self) -> Option<Context> {
         match self {
             CreateVirtualBranchError::DefaultTargetNotSet(ctx) => ctx.to_context().into(),
+            CreateVirtualBranchError::InvalidName(error) => error.context(),
             CreateVirtualBranchError::Other(error) => error.custom_context(),
         }
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum SplitBranchError {
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error("project")]
+    DefaultTargetNotSet(DefaultTargetNotSet),
+    #[error(transparent)]
+    InvalidName(#[from] BranchNameError),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for SplitBranchError {
+    fn context(&self) -> Option<Context> {
+        match self {
+            SplitBranchError::BranchNotFound(ctx) => ctx.to_context().into(),
+            SplitBranchError::DefaultTargetNotSet(ctx) => ctx.to_context().into(),
+            SplitBranchError::InvalidName(error) => error.context(),
+            SplitBranchError::Other(error) => error.custom_context(),
+        }
+    }
+}
+
+impl From<CreateVirtualBranchError> for SplitBranchError {
+    fn from(value: CreateVirtualBranchError) -> Self {
+        match value {
+            CreateVirtualBranchError::DefaultTargetNotSet(ctx) => Self::DefaultTargetNotSet(ctx),
+            CreateVirtualBranchError::InvalidName(error) => Self::InvalidName(error),
+            CreateVirtualBranchError::Other(error) => Self::Other(error),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum MergeVirtualBranchUpstreamError {
     #[error("project")]
@@ -205,6 +352,8 @@ pub enum CommitError {
     CommitHookRejected(String),
     #[error("commit msg hook rejected")]
     CommitMsgHookRejected(String),
+    #[error("nothing to commit")]
+    NothingToCommit(BranchId),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -221,6 +370,10 @@ impl ErrorWithContext for CommitError {
             CommitError::CommitMsgHookRejected(error) => {
                 error::Context::new(Code::CommitMsgHook, error)
             }
+            CommitError::NothingToCommit(branch_id) => error::Context::new(
+                Code::Validation,
+                format!("branch {} has no changes to commit", branch_id),
+            ),
             CommitError::Other(error) => return error.custom_context(),
         })
     }
@@ -232,6 +385,8 @@ pub enum PushError {
     DefaultTargetNotSet(DefaultTargetNotSet),
     #[error("branch not found")]
     BranchNotFound(BranchNotFound),
+    #[error("pre-push hook rejected")]
+    PrePushHookRejected(String),
     #[error(transparent)]
     Remote(#[from] project_repository::RemoteError),
     #[error(transparent)]
@@ -243,6 +398,7 @@ impl ErrorWithContext for PushError {
         Some(match self {
             PushError::DefaultTargetNotSet(ctx) => ctx.to_context(),
             PushError::BranchNotFound(ctx) => ctx.to_context(),
+            PushError::PrePushHookRejected(error) => error::Context::new(Code::PrePushHook, error),
             PushError::Remote(error) => return error.context(),
             PushError::Other(error) => return error.custom_context(),
         })
@@ -468,6 +624,8 @@ pub enum SetBaseBranchError {
     DirtyWorkingDirectory,
     #[error("branch {0} not found")]
     BranchNotFound(git::RemoteRefname),
+    #[error("branch {0} does not belong to a remote with a url")]
+    InvalidTargetBranch(git::RemoteRefname),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -482,11 +640,44 @@ impl ErrorWithContext for SetBaseBranchError {
                 Code::Branches,
                 format!("remote branch '{}' not found", name),
             ),
+            SetBaseBranchError::InvalidTargetBranch(name) => error::Context::new(
+                Code::Branches,
+                format!(
+                    "'{}' is not a branch of a configured remote with a url",
+                    name
+                ),
+            ),
             SetBaseBranchError::Other(error) => return error.custom_context(),
         })
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum UnsetBaseBranchError {
+    #[error("project is in conflicting state")]
+    Conflict(ProjectConflict),
+    #[error("branch {0} has unpushed commits")]
+    UnpushedCommits(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for UnsetBaseBranchError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            UnsetBaseBranchError::Conflict(ctx) => ctx.to_context(),
+            UnsetBaseBranchError::UnpushedCommits(name) => error::Context::new(
+                Code::Branches,
+                format!(
+                    "branch '{}' has unpushed commits, push them before unsetting the base branch",
+                    name
+                ),
+            ),
+            UnsetBaseBranchError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum UpdateBaseBranchError {
     #[error("project is in conflicting state")]
@@ -541,6 +732,96 @@ impl ErrorWithContext for MoveCommitError {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum RebaseBranchError {
+    #[error("project is in conflicted state")]
+    Conflict(ProjectConflict),
+    #[error("default target not set")]
+    DefaultTargetNotSet(DefaultTargetNotSet),
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error("commit {0} not found")]
+    CommitNotFound(git::Oid),
+    #[error("commit {0} is not based on the base branch")]
+    NotOnBase(git::Oid),
+    #[error("branch conflicts with the commit it is rebased onto")]
+    BranchConflicts(BranchId),
+    #[error("force push not allowed")]
+    ForcePushNotAllowed(ForcePushNotAllowed),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for RebaseBranchError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            RebaseBranchError::Conflict(ctx) => ctx.to_context(),
+            RebaseBranchError::DefaultTargetNotSet(ctx) => ctx.to_context(),
+            RebaseBranchError::BranchNotFound(ctx) => ctx.to_context(),
+            RebaseBranchError::CommitNotFound(oid) => {
+                error::Context::new(Code::Branches, format!("Commit {} not found", oid))
+            }
+            RebaseBranchError::NotOnBase(oid) => error::Context::new(
+                Code::Branches,
+                format!("Commit {} is not based on the base branch", oid),
+            ),
+            RebaseBranchError::BranchConflicts(id) => error::Context::new(
+                Code::Branches,
+                format!("Branch {} conflicts with the commit it is rebased onto", id),
+            ),
+            RebaseBranchError::ForcePushNotAllowed(ctx) => ctx.to_context(),
+            RebaseBranchError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LandBranchError {
+    #[error("project is in conflicted state")]
+    Conflict(ProjectConflict),
+    #[error("default target not set")]
+    DefaultTargetNotSet(DefaultTargetNotSet),
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error("branch {0} is not based on the current base")]
+    NotOnBase(BranchId),
+    #[error("branch {0} has uncommitted changes")]
+    UncommittedChanges(BranchId),
+    #[error("branch {0} conflicts with the landed commits")]
+    BranchConflicts(BranchId),
+    #[error("force push not allowed")]
+    ForcePushNotAllowed(ForcePushNotAllowed),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for LandBranchError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            LandBranchError::Conflict(ctx) => ctx.to_context(),
+            LandBranchError::DefaultTargetNotSet(ctx) => ctx.to_context(),
+            LandBranchError::BranchNotFound(ctx) => ctx.to_context(),
+            LandBranchError::NotOnBase(id) => error::Context::new(
+                Code::Branches,
+                format!(
+                    "Branch {} is not based on the current base, update it first",
+                    id
+                ),
+            ),
+            LandBranchError::UncommittedChanges(id) => error::Context::new(
+                Code::Branches,
+                format!("Branch {} has uncommitted changes", id),
+            ),
+            LandBranchError::BranchConflicts(id) => error::Context::new(
+                Code::Branches,
+                format!("Branch {} conflicts with the landed commits", id),
+            ),
+            LandBranchError::ForcePushNotAllowed(ctx) => ctx.to_context(),
+            LandBranchError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum CreateVirtualBranchFromBranchError {
     #[error("failed to apply")]
@@ -620,6 +901,70 @@ impl BranchNotFound {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum RestoreSnapshotError {
+    #[error("snapshot not found")]
+    SnapshotNotFound(SnapshotId),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for RestoreSnapshotError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            RestoreSnapshotError::SnapshotNotFound(id) => {
+                error::Context::new(Code::Branches, format!("snapshot {} not found", id))
+            }
+            RestoreSnapshotError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AbortIntegrationError {
+    #[error("no conflicted integration")]
+    NotIntegrating(ProjectId),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for AbortIntegrationError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            AbortIntegrationError::NotIntegrating(project_id) => error::Context::new(
+                Code::Validation,
+                format!(
+                    "project {} has no conflicted integration to abort",
+                    project_id
+                ),
+            ),
+            AbortIntegrationError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum BranchNameError {
+    #[error("branch name is empty")]
+    Empty,
+    #[error("branch name {0:?} can't contain \"..\"")]
+    ConsecutiveDots(String),
+    #[error("branch name {0:?} can't start or end with \"/\", or contain \"//\"")]
+    InvalidSlash(String),
+    #[error("branch name {0:?} can't have a part that ends with \".lock\"")]
+    LockSuffix(String),
+    #[error("branch name {0:?} can't have a part that starts or ends with \".\"")]
+    InvalidDot(String),
+    #[error("branch name {0:?} is not a valid git reference name")]
+    InvalidRefname(String),
+}
+
+impl ErrorWithContext for BranchNameError {
+    fn context(&self) -> Option<Context> {
+        error::Context::new(Code::Validation, self.to_string()).into()
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum UpdateBranchError {
     #[error("default target not set")]
@@ -627,6 +972,8 @@ pub enum UpdateBranchError {
     #[error("branch not found")]
     BranchNotFound(BranchNotFound),
     #[error(transparent)]
+    InvalidName(#[from] BranchNameError),
+    #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
@@ -635,6 +982,7 @@ impl ErrorWithContext for UpdateBranchError {
         Some(match self {
             UpdateBranchError::DefaultTargetNotSet(ctx) => ctx.to_context(),
             UpdateBranchError::BranchNotFound(ctx) => ctx.to_context(),
+            UpdateBranchError::InvalidName(error) => return error.context(),
             UpdateBranchError::Other(error) => return error.custom_context(),
         })
     }
@@ -692,3 +1040,117 @@ impl ErrorWithContext for GetRemoteBranchDataError {
         }
     }
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum AheadBehindError {
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for AheadBehindError {
+    fn context(&self) -> Option<Context> {
+        match self {
+            AheadBehindError::BranchNotFound(ctx) => ctx.to_context().into(),
+            AheadBehindError::Other(error) => error.custom_context(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BranchUpstreamError {
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for BranchUpstreamError {
+    fn context(&self) -> Option<Context> {
+        match self {
+            BranchUpstreamError::BranchNotFound(ctx) => ctx.to_context().into(),
+            BranchUpstreamError::Other(error) => error.custom_context(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommitGraphError {
+    #[error("default target not set")]
+    DefaultTargetNotSet(DefaultTargetNotSet),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for CommitGraphError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            CommitGraphError::DefaultTargetNotSet(ctx) => ctx.to_context(),
+            CommitGraphError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FileHistoryError {
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error("default target not set")]
+    DefaultTargetNotSet(DefaultTargetNotSet),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for FileHistoryError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            FileHistoryError::BranchNotFound(ctx) => ctx.to_context(),
+            FileHistoryError::DefaultTargetNotSet(ctx) => ctx.to_context(),
+            FileHistoryError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConflictVersionsError {
+    #[error("{} is not conflicted", .0.display())]
+    NotConflicted(PathBuf),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for ConflictVersionsError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            ConflictVersionsError::NotConflicted(path) => error::Context::new(
+                Code::Branches,
+                format!("{} is not conflicted", path.display()),
+            ),
+            ConflictVersionsError::Other(error) => return error.custom_context(),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SetBranchRemoteNameError {
+    #[error("default target not set")]
+    DefaultTargetNotSet(DefaultTargetNotSet),
+    #[error("branch not found")]
+    BranchNotFound(BranchNotFound),
+    #[error(transparent)]
+    InvalidName(#[from] BranchNameError),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ErrorWithContext for SetBranchRemoteNameError {
+    fn context(&self) -> Option<Context> {
+        Some(match self {
+            SetBranchRemoteNameError::DefaultTargetNotSet(ctx) => ctx.to_context(),
+            SetBranchRemoteNameError::BranchNotFound(ctx) => ctx.to_context(),
+            SetBranchRemoteNameError::InvalidName(error) => return error.context(),
+            SetBranchRemoteNameError::Other(error) => return error.custom_context(),
+        })
+    }
+}
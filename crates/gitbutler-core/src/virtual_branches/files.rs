@@ -17,11 +17,11 @@ pub struct RemoteBranchFile {
     pub binary: bool,
 }
 
-pub fn list_remote_commit_files(
+// the tree of the commit and that of its first parent, if it has one.
+pub(crate) fn commit_trees(
     repository: &git::Repository,
     commit_oid: git::Oid,
-    context_lines: u32,
-) -> Result<Vec<RemoteBranchFile>, errors::ListRemoteCommitFilesError> {
+) -> Result<(Option<git::Tree<'_>>, git::Tree<'_>), errors::ListRemoteCommitFilesError> {
     let commit = match repository.find_commit(commit_oid) {
         Ok(commit) => Ok(commit),
         Err(git::Error::NotFound(_)) => Err(errors::ListRemoteCommitFilesError::CommitNotFound(
@@ -30,13 +30,30 @@ pub fn list_remote_commit_files(
         Err(error) => Err(errors::ListRemoteCommitFilesError::Other(error.into())),
     }?;
 
-    if commit.parent_count() == 0 {
+    let commit_tree = commit.tree().context("failed to get commit tree")?;
+    let parent_tree = if commit.parent_count() == 0 {
+        None
+    } else {
+        Some(
+            commit
+                .parent(0)
+                .context("failed to get parent commit")?
+                .tree()
+                .context("failed to get parent tree")?,
+        )
+    };
+    Ok((parent_tree, commit_tree))
+}
+
+pub fn list_remote_commit_files(
+    repository: &git::Repository,
+    commit_oid: git::Oid,
+    context_lines: u32,
+) -> Result<Vec<RemoteBranchFile>, errors::ListRemoteCommitFilesError> {
+    let (Some(parent_tree), commit_tree) = commit_trees(repository, commit_oid)? else {
         return Ok(vec![]);
-    }
+    };
 
-    let parent = commit.parent(0).context("failed to get parent commit")?;
-    let commit_tree = commit.tree().context("failed to get commit tree")?;
-    let parent_tree = parent.tree().context("failed to get parent tree")?;
     let diff = diff::trees(repository, &parent_tree, &commit_tree, context_lines)?;
     let diff = diff::diff_files_to_hunks(&diff);
 
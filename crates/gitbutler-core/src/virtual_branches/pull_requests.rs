@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::projects::ProjectId;
+
+// a pull request, or merge request, as the forge of the project's remote knows it
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    // the name of the remote branch that is to be merged, to tell which branch it belongs to
+    pub source_branch: String,
+    pub draft: bool,
+}
+
+/// Queries the forge the project is pushed to, e.g. GitHub or GitLab, for its pull requests.
+#[async_trait]
+pub trait RemoteProvider {
+    async fn list_pull_requests(&self, project_id: ProjectId) -> anyhow::Result<Vec<PullRequest>>;
+}
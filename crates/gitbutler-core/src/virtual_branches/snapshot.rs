@@ -0,0 +1,366 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    branch, errors, integration, target, BranchId, Iterator, VirtualBranches, VirtualBranchesHandle,
+};
+use crate::{
+    gb_repository, git,
+    id::Id,
+    project_repository::{self, conflicts},
+    reader, sessions, writer,
+};
+
+pub struct Snapshot;
+
+pub type SnapshotId = Id<Snapshot>;
+
+/// The number of snapshots kept per project, older ones are deleted when a new one is taken.
+pub const MAX_SNAPSHOTS: usize = 50;
+
+fn snapshots_dir(project_repository: &project_repository::Repository) -> PathBuf {
+    project_repository.project().gb_dir().join("snapshots")
+}
+
+// snapshots are stored as `<sequence>-<id>.toml`, where the sequence is one more than that of the
+// newest snapshot at the time it is taken. it orders snapshots when pruning, which modification
+// times can't do for snapshots taken in quick succession.
+struct SnapshotFile {
+    sequence: u64,
+    id: SnapshotId,
+    path: PathBuf,
+}
+
+fn list_snapshots(
+    project_repository: &project_repository::Repository,
+) -> Result<Vec<SnapshotFile>> {
+    let dir = snapshots_dir(project_repository);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry.context("failed to read snapshots dir entry")?.path();
+        if path
+            .extension()
+            .map_or(true, |extension| extension != "toml")
+        {
+            continue;
+        }
+        let Some((sequence, id)) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.split_once('-'))
+            .and_then(|(sequence, id)| Some((sequence.parse().ok()?, id.parse().ok()?)))
+        else {
+            continue;
+        };
+        snapshots.push(SnapshotFile { sequence, id, path });
+    }
+    Ok(snapshots)
+}
+
+fn find_snapshot(
+    project_repository: &project_repository::Repository,
+    id: &SnapshotId,
+) -> Result<Option<PathBuf>> {
+    Ok(list_snapshots(project_repository)?
+        .into_iter()
+        .find(|snapshot| snapshot.id == *id)
+        .map(|snapshot| snapshot.path))
+}
+
+fn delete_snapshot(
+    project_repository: &project_repository::Repository,
+    id: &SnapshotId,
+) -> Result<()> {
+    let path = find_snapshot(project_repository, id)?
+        .with_context(|| format!("snapshot {} not found", id))?;
+    fs::remove_file(&path).with_context(|| format!("failed to delete snapshot {}", path.display()))
+}
+
+// what is needed to abort an integration of upstream changes that left the project conflicted.
+#[derive(Serialize, Deserialize)]
+struct Integration {
+    snapshot_id: SnapshotId,
+    // the working directory before the integration, including uncommitted changes
+    wd_tree: git::Oid,
+    // the merge parent of the conflicts, to tell them apart from conflicts of later operations
+    merge_parent: git::Oid,
+}
+
+fn integration_path(project_repository: &project_repository::Repository) -> PathBuf {
+    snapshots_dir(project_repository).join("integration.toml")
+}
+
+// stores the branches and targets of the virtual branches state, so that they can be restored
+// later. the working directory is not part of a snapshot.
+pub fn create(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<SnapshotId> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+    let target_reader = target::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    );
+
+    let default_target = match target_reader.read_default() {
+        Ok(target) => Some(target),
+        Err(reader::Error::NotFound) => None,
+        Err(error) => return Err(error).context("failed to read default target"),
+    };
+
+    let mut branches = HashMap::new();
+    let mut branch_targets = HashMap::new();
+    for branch in Iterator::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .context("failed to create branch iterator")?
+    {
+        let branch = branch.context("failed to read virtual branch")?;
+        match target_reader.read(&branch.id) {
+            // branches without a target of their own use the default one
+            Ok(target) if Some(&target) == default_target.as_ref() => {}
+            Ok(target) => {
+                branch_targets.insert(branch.id, target);
+            }
+            Err(reader::Error::NotFound) => {}
+            Err(error) => return Err(error).context("failed to read branch target"),
+        }
+        branches.insert(branch.id, branch);
+    }
+
+    let snapshot = VirtualBranches {
+        default_target,
+        branch_targets,
+        branches,
+    };
+
+    let id = SnapshotId::generate();
+    let sequence = list_snapshots(project_repository)?
+        .iter()
+        .map(|snapshot| snapshot.sequence + 1)
+        .max()
+        .unwrap_or_default();
+    let dir = snapshots_dir(project_repository);
+    fs::create_dir_all(&dir).context("failed to create snapshots dir")?;
+    let path = dir.join(format!("{}-{}.toml", sequence, id));
+    fs::write(
+        &path,
+        toml::to_string(&snapshot).context("failed to serialize snapshot")?,
+    )
+    .with_context(|| format!("failed to write snapshot {}", path.display()))?;
+
+    prune(project_repository, MAX_SNAPSHOTS).context("failed to prune snapshots")?;
+
+    Ok(id)
+}
+
+// deletes all but the `keep` most recent snapshots, returning how many were deleted. the snapshot
+// needed to abort a conflicted integration is always kept.
+pub fn prune(project_repository: &project_repository::Repository, keep: usize) -> Result<usize> {
+    let integration_path = integration_path(project_repository);
+    let integration_snapshot = if integration_path.exists() {
+        let integration: Integration = toml::from_str(
+            &fs::read_to_string(&integration_path)
+                .with_context(|| format!("failed to read {}", integration_path.display()))?,
+        )
+        .context("failed to parse integration")?;
+        Some(integration.snapshot_id)
+    } else {
+        None
+    };
+
+    let mut snapshots = list_snapshots(project_repository)?;
+    snapshots.retain(|snapshot| Some(snapshot.id) != integration_snapshot);
+    snapshots.sort_by(|a, b| b.sequence.cmp(&a.sequence));
+
+    let mut deleted = 0;
+    for SnapshotFile { path, .. } in snapshots.into_iter().skip(keep) {
+        fs::remove_file(&path)
+            .with_context(|| format!("failed to delete snapshot {}", path.display()))?;
+        deleted += 1;
+    }
+    Ok(deleted)
+}
+
+// puts the branches and targets back to the state they were in when the snapshot was taken.
+// branches and targets created since are deleted. the working directory is left as it is.
+pub fn restore(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    id: &SnapshotId,
+) -> Result<(), errors::RestoreSnapshotError> {
+    let path = find_snapshot(project_repository, id)
+        .context("failed to find snapshot")?
+        .ok_or(errors::RestoreSnapshotError::SnapshotNotFound(*id))?;
+    let snapshot: VirtualBranches = toml::from_str(
+        &fs::read_to_string(&path)
+            .with_context(|| format!("failed to read snapshot {}", path.display()))?,
+    )
+    .context("failed to parse snapshot")?;
+
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+    let branch_writer = branch::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create branch writer")?;
+    let target_writer = target::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create target writer")?;
+
+    let current_branches = Iterator::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .context("failed to create branch iterator")?
+    .collect::<Result<Vec<branch::Branch>, reader::Error>>()
+    .context("failed to read virtual branches")?;
+    for branch in current_branches {
+        if !snapshot.branches.contains_key(&branch.id) {
+            delete_branch_target(gb_repository, project_repository, &branch.id)?;
+            branch_writer
+                .delete(&branch)
+                .context("failed to delete branch")?;
+        }
+    }
+
+    match &snapshot.default_target {
+        Some(default_target) => target_writer
+            .write_default(default_target)
+            .context("failed to write default target")?,
+        None => target_writer
+            .delete_default()
+            .context("failed to delete default target")?,
+    }
+
+    for (_, mut branch) in snapshot.branches {
+        branch_writer
+            .write(&mut branch)
+            .context("failed to write branch")?;
+        match snapshot.branch_targets.get(&branch.id) {
+            Some(target) => target_writer
+                .write(&branch.id, target)
+                .context("failed to write branch target")?,
+            None => delete_branch_target(gb_repository, project_repository, &branch.id)?,
+        }
+    }
+
+    // without a target there is nothing to integrate the branches onto
+    if snapshot.default_target.is_some() {
+        integration::update_gitbutler_integration(gb_repository, project_repository)?;
+    }
+
+    Ok(())
+}
+
+// makes the branch use the default target again.
+fn delete_branch_target(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    id: &BranchId,
+) -> Result<()> {
+    gb_repository
+        .mark_active_session()
+        .context("failed to get or create current session")?;
+    writer::DirWriter::open(gb_repository.root())
+        .and_then(|writer| writer.remove(format!("branches/{}/target", id)))
+        .context("failed to delete branch target")?;
+    VirtualBranchesHandle::new(&project_repository.project().gb_dir())
+        .remove_branch_target(id)
+        .context("failed to delete branch target")
+}
+
+// to be called after integrating upstream changes, with the snapshot and working directory taken
+// right before. if the integration left the project conflicted, they are kept around so that it
+// can be aborted, otherwise the snapshot is deleted.
+pub fn record_integration(
+    project_repository: &project_repository::Repository,
+    snapshot_id: &SnapshotId,
+    wd_tree: git::Oid,
+) -> Result<()> {
+    let path = integration_path(project_repository);
+    let Some(merge_parent) =
+        conflicts::merge_parent(project_repository).context("failed to read merge parent")?
+    else {
+        delete_snapshot(project_repository, snapshot_id)?;
+        return Ok(());
+    };
+
+    let integration = Integration {
+        snapshot_id: *snapshot_id,
+        wd_tree,
+        merge_parent,
+    };
+    fs::write(
+        &path,
+        toml::to_string(&integration).context("failed to serialize integration")?,
+    )
+    .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(())
+}
+
+// undoes a conflicted integration: the branches are restored from the snapshot taken before it,
+// the working directory is checked out as it was and the conflicts are cleared.
+pub fn abort_integration(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<(), errors::AbortIntegrationError> {
+    let not_integrating =
+        || errors::AbortIntegrationError::NotIntegrating(project_repository.project().id);
+
+    let merge_parent = conflicts::merge_parent(project_repository)
+        .context("failed to read merge parent")?
+        .ok_or_else(not_integrating)?;
+    let path = integration_path(project_repository);
+    if !path.exists() {
+        return Err(not_integrating());
+    }
+    let integration: Integration = toml::from_str(
+        &fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?,
+    )
+    .context("failed to parse integration")?;
+    if integration.merge_parent != merge_parent {
+        return Err(not_integrating());
+    }
+
+    restore(gb_repository, project_repository, &integration.snapshot_id)
+        .context("failed to restore snapshot")?;
+
+    let repo = &project_repository.git_repository;
+    let wd_tree = repo
+        .find_tree(integration.wd_tree)
+        .context("failed to find working directory tree")?;
+    repo.checkout_tree(&wd_tree)
+        .force()
+        .remove_untracked()
+        .checkout()
+        .context("failed to checkout working directory tree")?;
+
+    conflicts::clear(project_repository).context("failed to clear conflicts")?;
+
+    fs::remove_file(&path).with_context(|| format!("failed to delete {}", path.display()))?;
+    delete_snapshot(project_repository, &integration.snapshot_id)?;
+
+    Ok(())
+}
@@ -46,6 +46,16 @@ impl VirtualBranchesHandle {
         Ok(())
     }
 
+    /// Removes the default target for the given repository.
+    ///
+    /// Errors if the file cannot be read or written.
+    pub fn remove_default_target(&self) -> Result<()> {
+        let mut virtual_branches = self.read_file()?;
+        virtual_branches.default_target = None;
+        self.write_file(&virtual_branches)?;
+        Ok(())
+    }
+
     /// Gets the default target for the given repository.
     ///
     /// Errors if the file cannot be read or written.
@@ -64,6 +74,17 @@ impl VirtualBranchesHandle {
         Ok(())
     }
 
+    /// Removes the target of the given virtual branch, which then uses the default target.
+    ///
+    /// Errors if the file cannot be read or written.
+    pub fn remove_branch_target(&self, id: &BranchId) -> Result<()> {
+        let mut virtual_branches = self.read_file()?;
+        if virtual_branches.branch_targets.remove(id).is_some() {
+            self.write_file(&virtual_branches)?;
+        }
+        Ok(())
+    }
+
     /// Gets the target for the given virtual branch.
     ///
     /// Errors if the file cannot be read or written.
@@ -1,8 +1,9 @@
 #[cfg(target_family = "unix")]
 use std::os::unix::prelude::*;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     hash::Hash,
+    io::Read,
     path::{Path, PathBuf},
     time, vec,
 };
@@ -12,13 +13,13 @@ use bstr::ByteSlice;
 use diffy::{apply, Patch};
 use git2_hooks::HookResult;
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::{
     branch::{
         self, Branch, BranchCreateRequest, BranchId, BranchOwnershipClaims, Hunk, OwnershipClaim,
     },
-    branch_to_remote_branch, context, errors, target, Iterator, RemoteBranch,
+    branch_to_remote_branch, context, errors, target, BaseBranch, Iterator, RemoteBranch,
     VirtualBranchesHandle,
 };
 use crate::error::Error;
@@ -63,6 +64,7 @@ pub struct VirtualBranch {
     pub base_current: bool, // is this vbranch based on the current base branch? if false, this needs to be manually merged with conflicts
     pub ownership: BranchOwnershipClaims,
     pub updated_at: u128,
+    pub last_activity_at: u128,
     pub selected_for_changes: bool,
     pub head: git::Oid,
 }
@@ -71,7 +73,127 @@ pub struct VirtualBranch {
 #[serde(rename_all = "camelCase")]
 pub struct VirtualBranches {
     pub branches: Vec<VirtualBranch>,
-    pub skipped_files: Vec<git::diff::FileDiff>,
+    pub skipped_files: Vec<SkippedFile>,
+}
+
+// everything the UI needs to show a project when it's opened
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceStatus {
+    pub base_branch: Option<BaseBranch>,
+    pub branches: Vec<VirtualBranch>,
+    pub conflicts: Vec<conflicts::ConflictedFile>,
+    pub head: git::Oid,
+}
+
+// controls how much work listing virtual branches does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListOptions {
+    // when false, the working directory is not diffed and branches are listed without files
+    pub include_files: bool,
+    pub sort: BranchSort,
+    // when set, only this branch is listed and the others are not diffed or logged at all
+    pub branch_id: Option<BranchId>,
+    // when true, the files changed by each commit are listed as well, as by `list_commit_files`
+    pub include_commit_files: bool,
+    // when true, nothing is written. instead of writing the ownership it recalculates for the
+    // applied branches, listing fails with `ListVirtualBranchesError::StatusOutdated`, and the
+    // integration commit is left as it is
+    pub read_only: bool,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self {
+            include_files: true,
+            sort: BranchSort::default(),
+            branch_id: None,
+            include_commit_files: false,
+            read_only: false,
+        }
+    }
+}
+
+// controls how a commit is created
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommitOptions {
+    // runs the commit-msg and pre-commit hooks, which can reject the commit
+    pub run_hooks: bool,
+    // fails the commit with `NothingToCommit` if it wouldn't change anything, instead of creating
+    // an empty commit
+    pub reject_empty: bool,
+    // added to the end of the message as git trailers, such as `Signed-off-by`, leaving out
+    // those it already ends with
+    pub trailers: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BranchSort {
+    // the order the user arranged the lanes in
+    #[default]
+    Order,
+    // most recently active first, going by the last commit, hunk move or rename
+    RecentActivity,
+}
+
+fn sort_branches(branches: &mut [VirtualBranch], sort: BranchSort) {
+    match sort {
+        BranchSort::Order => branches.sort_by(|a, b| a.order.cmp(&b.order)),
+        BranchSort::RecentActivity => branches.sort_by(|a, b| {
+            b.last_activity_at
+                .cmp(&a.last_activity_at)
+                .then(a.order.cmp(&b.order))
+        }),
+    }
+}
+
+// a file that was left out of the virtual branches status, along with the reason why
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+// large and binary files are listed as changed as a whole, so only conflicts, which can't be
+// told resolved without a diff, leave a file out
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    Conflicted,
+}
+
+// a file changed by a single commit compared to its first parent, or between two commits
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitFile {
+    pub path: PathBuf,
+    pub change_type: diff::ChangeType,
+    pub binary: bool,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+// the versions of a conflicted file, e.g. to show them in a three-way merge editor. sides that
+// don't have the file, such as the base of a file added on both sides, are None. `merged` is the
+// file in the working directory, with the conflict markers.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictVersions {
+    pub base: Option<reader::Content>,
+    pub ours: Option<reader::Content>,
+    pub theirs: Option<reader::Content>,
+    pub merged: reader::Content,
+}
+
+// totals of a diff, without the diff itself
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
 }
 
 // this is the struct that maps to the view `Commit` type in Typescript
@@ -94,6 +216,9 @@ pub struct VirtualBranchCommit {
     pub is_integrated: bool,
     pub parent_ids: Vec<git::Oid>,
     pub branch_id: BranchId,
+    // the files changed compared to the first parent, only listed when asked for with
+    // `ListOptions::include_commit_files`
+    pub changed_files: Option<Vec<CommitFile>>,
 }
 
 // this struct is a mapping to the view `File` type in Typescript
@@ -114,6 +239,21 @@ pub struct VirtualBranchFile {
     pub conflicted: bool,
     pub binary: bool,
     pub large: bool,
+    // how many bytes a large file grew by, or shrank by if negative. large files are not diffed,
+    // so this is all there is to tell about the change.
+    pub size_delta_bytes: Option<i64>,
+    pub status: FileStatus,
+}
+
+// how a file changed, derived from the change type of its hunks. a file that is deleted and a file
+// that is added with the same content within the same branch are reported as a rename.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed { from: PathBuf },
 }
 
 // this struct is a mapping to the view `Hunk` type in Typescript
@@ -173,6 +313,30 @@ pub fn normalize_branch_name(name: &str) -> String {
     pattern.replace_all(name, "-").to_string()
 }
 
+// checks that the name is still a valid git ref name once normalized for pushing. characters
+// like spaces are fine, as normalizing replaces them, but e.g. `..` or a `.lock` suffix are not.
+pub fn validate_branch_name(name: &str) -> Result<(), errors::BranchNameError> {
+    let normalized = normalize_branch_name(name);
+    if normalized.is_empty() {
+        return Err(errors::BranchNameError::Empty);
+    }
+    if normalized.contains("..") {
+        return Err(errors::BranchNameError::ConsecutiveDots(name.to_string()));
+    }
+    if normalized.starts_with('/') || normalized.ends_with('/') || normalized.contains("//") {
+        return Err(errors::BranchNameError::InvalidSlash(name.to_string()));
+    }
+    for component in normalized.split('/') {
+        if component.ends_with(".lock") {
+            return Err(errors::BranchNameError::LockSuffix(name.to_string()));
+        }
+        if component.starts_with('.') || component.ends_with('.') {
+            return Err(errors::BranchNameError::InvalidDot(name.to_string()));
+        }
+    }
+    Ok(())
+}
+
 fn get_default_target(
     session_reader: &sessions::Reader,
     project: &projects::Project,
@@ -637,6 +801,60 @@ pub fn reset_files(
     Ok(())
 }
 
+// reverts the uncommitted changes of a branch in the working directory, either all of them or only
+// the ones in the given paths. the commits of the branch are left untouched.
+pub fn discard_branch_changes(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    paths: Option<&[PathBuf]>,
+) -> Result<(), errors::DiscardBranchChangesError> {
+    if conflicts::is_resolving(project_repository) {
+        return Err(errors::DiscardBranchChangesError::Conflict(
+            errors::ProjectConflict {
+                project_id: project_repository.project().id,
+            },
+        ));
+    }
+
+    // the status is calculated first so that the ownership of the branch is up to date
+    let (statuses, _) = get_status_by_branch(gb_repository, project_repository)
+        .context("failed to get status by branch")?;
+    let (branch, _) = statuses
+        .into_iter()
+        .find(|(branch, _)| branch.id == *branch_id && branch.applied)
+        .ok_or_else(|| {
+            errors::DiscardBranchChangesError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+        })?;
+
+    let ownership = BranchOwnershipClaims {
+        claims: branch
+            .ownership
+            .claims
+            .into_iter()
+            .filter(|claim| paths.map_or(true, |paths| paths.contains(&claim.file_path)))
+            .collect(),
+    };
+    if ownership.is_empty() {
+        return Ok(());
+    }
+
+    unapply_ownership(gb_repository, project_repository, &ownership).map_err(|error| match error {
+        errors::UnapplyOwnershipError::DefaultTargetNotSet(error) => {
+            errors::DiscardBranchChangesError::DefaultTargetNotSet(error)
+        }
+        errors::UnapplyOwnershipError::Conflict(error) => {
+            errors::DiscardBranchChangesError::Conflict(error)
+        }
+        errors::UnapplyOwnershipError::Other(error) => {
+            errors::DiscardBranchChangesError::Other(error)
+        }
+    })
+}
+
 // to unapply a branch, we need to write the current tree out, then remove those file changes from the wd
 pub fn unapply_branch(
     gb_repository: &gb_repository::Repository,
@@ -821,9 +1039,48 @@ fn find_base_tree<'a>(
 pub fn list_virtual_branches(
     gb_repository: &gb_repository::Repository,
     project_repository: &project_repository::Repository,
-) -> Result<(Vec<VirtualBranch>, bool, Vec<diff::FileDiff>), errors::ListVirtualBranchesError> {
+    include_archived: bool,
+) -> Result<(Vec<VirtualBranch>, bool, Vec<SkippedFile>), errors::ListVirtualBranchesError> {
+    list_virtual_branches_with_options(
+        gb_repository,
+        project_repository,
+        include_archived,
+        &ListOptions::default(),
+    )
+}
+
+pub fn list_virtual_branches_with_options(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    include_archived: bool,
+    options: &ListOptions,
+) -> Result<(Vec<VirtualBranch>, bool, Vec<SkippedFile>), errors::ListVirtualBranchesError> {
     let mut branches: Vec<VirtualBranch> = Vec::new();
+    let (uses_diff_context, skipped_files) = for_each_virtual_branch(
+        gb_repository,
+        project_repository,
+        include_archived,
+        options,
+        |branch| {
+            branches.push(branch);
+            Ok(())
+        },
+    )?;
+    sort_branches(&mut branches, options.sort);
+    Ok((branches, uses_diff_context, skipped_files))
+}
 
+// like `list_virtual_branches_with_options`, but hands each branch to `on_branch` as soon as it is
+// done instead of collecting them, so that they can be shown before all are listed. branches come
+// in the order they are stored in, `options.sort` is not applied. listing stops at the first
+// error returned by `on_branch`.
+pub fn for_each_virtual_branch(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    include_archived: bool,
+    options: &ListOptions,
+    mut on_branch: impl FnMut(VirtualBranch) -> Result<()>,
+) -> Result<(bool, Vec<SkippedFile>), errors::ListVirtualBranchesError> {
     let default_target = gb_repository
         .default_target()
         .context("failed to get default target")?
@@ -833,18 +1090,53 @@ pub fn list_virtual_branches(
             })
         })?;
 
-    let (statuses, skipped_files) = get_status_by_branch(gb_repository, project_repository)?;
+    let (statuses, skipped_files) = if options.read_only && options.include_files {
+        read_status_by_branch(gb_repository, project_repository)?
+            .ok_or(errors::ListVirtualBranchesError::StatusOutdated)?
+    } else if options.include_files {
+        get_status_by_branch(gb_repository, project_repository)?
+    } else {
+        let statuses = all_branches(gb_repository, project_repository)?
+            .into_iter()
+            .map(|branch| (branch, BranchStatus::default()))
+            .collect::<Vec<_>>();
+        (statuses, vec![])
+    };
     let max_selected_for_changes = statuses
         .iter()
         .filter_map(|(branch, _)| branch.selected_for_changes)
         .max()
         .unwrap_or(-1);
 
-    for (branch, files) in &statuses {
+    let statuses = statuses
+        .iter()
+        .filter(|(branch, _)| include_archived || !branch.archived)
+        .filter(|(branch, _)| {
+            options
+                .branch_id
+                .map_or(true, |branch_id| branch_id == branch.id)
+        })
+        .collect::<Vec<_>>();
+
+    // hunks can be locked to the commits of any listed branch, so those are diffed up front
+    let committed_hunks = if options.include_files {
+        committed_hunks_of_applied_branches(
+            project_repository,
+            &default_target,
+            statuses.iter().map(|(branch, _)| branch),
+        )?
+    } else {
+        vec![]
+    };
+
+    for (branch, files) in statuses {
         // check if head tree does not match target tree
         // if so, we diff the head tree and the new write_tree output to see what is new and filter the hunks to just those
-        let files =
-            calculate_non_commited_diffs(project_repository, branch, &default_target, files)?;
+        let files = if options.include_files {
+            calculate_non_commited_diffs(project_repository, branch, &default_target, files)?
+        } else {
+            BranchStatus::default()
+        };
 
         let repo = &project_repository.git_repository;
 
@@ -909,13 +1201,20 @@ pub fn list_virtual_branches(
                     is_commit_integrated(project_repository, &default_target, commit)?
                 };
 
-                commit_to_vbranch_commit(
+                let mut commit = commit_to_vbranch_commit(
                     project_repository,
                     branch,
                     commit,
                     is_integrated,
                     is_remote,
-                )
+                )?;
+                if options.include_commit_files {
+                    commit.changed_files = Some(
+                        list_commit_files(project_repository, commit.id)
+                            .context("failed to list commit files")?,
+                    );
+                }
+                Ok(commit)
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -973,102 +1272,237 @@ pub fn list_virtual_branches(
             base_current,
             ownership: branch.ownership.clone(),
             updated_at: branch.updated_timestamp_ms,
+            last_activity_at: branch.last_activity_ms,
             selected_for_changes: branch.selected_for_changes == Some(max_selected_for_changes),
             head: branch.head,
         };
-        branches.push(branch);
-    }
 
-    let branches = branches_with_large_files_abridged(branches);
-    let mut branches = branches_with_hunk_locks(branches, project_repository)?;
+        let branch = if options.include_files {
+            let branch = branch_with_large_files_abridged(branch);
+            let branch = branch_with_large_files_summarized(branch, project_repository)?;
+            let mut branch = branch_with_hunk_locks(branch, &committed_hunks);
 
-    // If there no context lines are used internally, add them here, before returning to the UI
-    if context_lines(project_repository) == 0 {
-        for branch in &mut branches {
-            branch.files = files_with_hunk_context(
-                &project_repository.git_repository,
-                branch.files.clone(),
-                3,
-                branch.head,
-            )
-            .context("failed to add hunk context")?;
-        }
-    }
+            // If there no context lines are used internally, add them here, before returning to the UI
+            if context_lines(project_repository) == 0 {
+                branch.files = files_with_hunk_context(
+                    &project_repository.git_repository,
+                    branch.files.clone(),
+                    3,
+                    branch.head,
+                )
+                .context("failed to add hunk context")?;
+            }
+            branch
+        } else {
+            branch
+        };
 
-    branches.sort_by(|a, b| a.order.cmp(&b.order));
+        on_branch(branch)?;
+    }
 
-    super::integration::update_gitbutler_integration(gb_repository, project_repository)?;
+    if options.include_files && !options.read_only {
+        super::integration::update_gitbutler_integration(gb_repository, project_repository)?;
+    }
 
     let uses_diff_context = project_repository
         .project()
         .use_diff_context
         .unwrap_or(false);
-    Ok((branches, uses_diff_context, skipped_files))
+    Ok((uses_diff_context, skipped_files))
 }
 
-fn branches_with_large_files_abridged(mut branches: Vec<VirtualBranch>) -> Vec<VirtualBranch> {
-    for branch in &mut branches {
-        for file in &mut branch.files {
-            // Diffs larger than 500kb are considered large
-            if file.hunks.iter().any(|hunk| hunk.diff.len() > 500_000) {
-                file.large = true;
-                file.hunks
-                    .iter_mut()
-                    .for_each(|hunk| hunk.diff = String::new());
-            }
+// lists just the branch with the given id, archived or not. see `ListOptions::read_only` for
+// `read_only`.
+pub fn get_virtual_branch(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    read_only: bool,
+) -> Result<VirtualBranch, errors::GetVirtualBranchError> {
+    let (mut branches, _, _) = list_virtual_branches_with_options(
+        gb_repository,
+        project_repository,
+        true,
+        &ListOptions {
+            branch_id: Some(*branch_id),
+            read_only,
+            ..ListOptions::default()
+        },
+    )?;
+    branches.pop().ok_or_else(|| {
+        errors::GetVirtualBranchError::BranchNotFound(errors::BranchNotFound {
+            project_id: project_repository.project().id,
+            branch_id: *branch_id,
+        })
+    })
+}
+
+// see `ListOptions::read_only` for `read_only`.
+pub fn workspace_status(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    read_only: bool,
+) -> Result<WorkspaceStatus, errors::ListVirtualBranchesError> {
+    let base_branch = super::get_base_branch_data(gb_repository, project_repository)
+        .context("failed to get base branch data")?;
+    let (branches, _, _) = list_virtual_branches_with_options(
+        gb_repository,
+        project_repository,
+        false,
+        &ListOptions {
+            read_only,
+            ..ListOptions::default()
+        },
+    )?;
+    let conflicts =
+        conflicts::conflicted_files(project_repository).context("failed to list conflicts")?;
+    let head = project_repository
+        .get_head()
+        .context("failed to get head")?
+        .peel_to_commit()
+        .context("failed to peel head to commit")?
+        .id();
+    Ok(WorkspaceStatus {
+        base_branch,
+        branches,
+        conflicts,
+        head,
+    })
+}
+
+fn branch_with_large_files_abridged(mut branch: VirtualBranch) -> VirtualBranch {
+    for file in &mut branch.files {
+        // Diffs larger than 500kb are considered large
+        if file.hunks.iter().any(|hunk| hunk.diff.len() > 500_000) {
+            file.large = true;
+            file.hunks
+                .iter_mut()
+                .for_each(|hunk| hunk.diff = String::new());
         }
     }
-    branches
+    branch
 }
 
-fn branches_with_hunk_locks(
-    mut branches: Vec<VirtualBranch>,
+// files above the size limit are not diffed, but changed as a whole by a single hunk that holds the
+// id of the new blob. they are marked as large, along with how much they grew and whether they
+// actually are binary.
+fn branch_with_large_files_summarized(
+    mut branch: VirtualBranch,
     project_repository: &project_repository::Repository,
-) -> Result<Vec<VirtualBranch>> {
-    let all_commits: Vec<VirtualBranchCommit> = branches
-        .clone()
-        .iter()
-        .filter(|branch| branch.active)
-        .flat_map(|vbranch| vbranch.commits.clone())
-        .collect();
-
-    for commit in all_commits {
-        let commit = project_repository.git_repository.find_commit(commit.id)?;
-        let parent = commit.parent(0).context("failed to get parent commit")?;
-        let commit_tree = commit.tree().context("failed to get commit tree")?;
-        let parent_tree = parent.tree().context("failed to get parent tree")?;
-        let commited_file_diffs = diff::trees(
-            &project_repository.git_repository,
-            &parent_tree,
-            &commit_tree,
-            context_lines(project_repository),
-        )?;
-        let commited_file_diffs = diff::diff_files_to_hunks(&commited_file_diffs);
-        for branch in &mut branches {
-            for file in &mut branch.files {
-                for hunk in &mut file.hunks {
-                    let locked =
-                        commited_file_diffs
-                            .get(&file.path)
-                            .map_or(false, |committed_hunks| {
-                                committed_hunks.iter().any(|committed_hunk| {
-                                    joined(
-                                        committed_hunk.old_start,
-                                        committed_hunk.old_start + committed_hunk.new_lines,
-                                        hunk.start,
-                                        hunk.end,
-                                    )
-                                })
-                            });
-                    if locked {
-                        hunk.locked = true;
-                        hunk.locked_to = Some(commit.id());
-                    }
+) -> Result<VirtualBranch> {
+    let repo = &project_repository.git_repository;
+    let head_tree = repo
+        .find_commit(branch.head)
+        .context("failed to find branch head")?
+        .tree()
+        .context("failed to find branch head tree")?;
+    for file in &mut branch.files {
+        let [hunk] = file.hunks.as_slice() else {
+            continue;
+        };
+        if !hunk.binary || hunk.change_type == diff::ChangeType::Deleted {
+            continue;
+        }
+        let Ok(blob_id) = hunk.diff.parse::<git::Oid>() else {
+            continue;
+        };
+        // large files in the working directory are only hashed until committed
+        let new_size = match repo.blob_size(blob_id) {
+            Ok(size) => size as u64,
+            Err(_) => std::fs::metadata(project_repository.path().join(&file.path))
+                .map(|metadata| metadata.len())
+                .with_context(|| format!("failed to read size of {}", file.path.display()))?,
+        };
+        if new_size <= diff::LARGE_FILE_SIZE_BYTES {
+            continue;
+        }
+        let old_size = match head_tree.get_path(&file.path) {
+            Ok(entry) => repo
+                .blob_size(entry.id())
+                .with_context(|| format!("failed to read size of {}", file.path.display()))?
+                as u64,
+            Err(_) => 0,
+        };
+
+        let prefix = match std::fs::File::open(project_repository.path().join(&file.path)) {
+            Ok(disk_file) => {
+                let mut prefix = Vec::new();
+                disk_file.take(8000).read_to_end(&mut prefix)?;
+                prefix
+            }
+            // files of unapplied branches are only in the odb
+            Err(_) => {
+                let blob = repo.find_blob(blob_id).context("failed to find blob")?;
+                blob.content().iter().take(8000).copied().collect()
+            }
+        };
+
+        file.large = true;
+        file.binary = matches!(
+            reader::Content::from_prefix(&prefix),
+            reader::Content::Binary { .. }
+        );
+        file.size_delta_bytes = Some(new_size as i64 - old_size as i64);
+    }
+    Ok(branch)
+}
+
+// the hunks of every commit of the given branches that are applied, in the order of the branches
+// and their commits
+fn committed_hunks_of_applied_branches<'a>(
+    project_repository: &project_repository::Repository,
+    default_target: &target::Target,
+    branches: impl Iterator<Item = &'a branch::Branch>,
+) -> Result<Vec<(git::Oid, HashMap<PathBuf, Vec<diff::GitHunk>>)>> {
+    let mut committed_hunks = vec![];
+    for branch in branches.filter(|branch| branch.applied) {
+        for commit_id in project_repository
+            .l(branch.head, LogUntil::Commit(default_target.sha))
+            .context(format!("failed to get log for branch {}", branch.name))?
+        {
+            let commit = project_repository.git_repository.find_commit(commit_id)?;
+            let parent = commit.parent(0).context("failed to get parent commit")?;
+            let commit_tree = commit.tree().context("failed to get commit tree")?;
+            let parent_tree = parent.tree().context("failed to get parent tree")?;
+            let commited_file_diffs = diff::trees(
+                &project_repository.git_repository,
+                &parent_tree,
+                &commit_tree,
+                context_lines(project_repository),
+            )?;
+            committed_hunks.push((commit_id, diff::diff_files_to_hunks(&commited_file_diffs)));
+        }
+    }
+    Ok(committed_hunks)
+}
+
+fn branch_with_hunk_locks(
+    mut branch: VirtualBranch,
+    committed_hunks: &[(git::Oid, HashMap<PathBuf, Vec<diff::GitHunk>>)],
+) -> VirtualBranch {
+    for (commit_id, commited_file_diffs) in committed_hunks {
+        for file in &mut branch.files {
+            for hunk in &mut file.hunks {
+                let locked = commited_file_diffs
+                    .get(&file.path)
+                    .map_or(false, |committed_hunks| {
+                        committed_hunks.iter().any(|committed_hunk| {
+                            joined(
+                                committed_hunk.old_start,
+                                committed_hunk.old_start + committed_hunk.new_lines,
+                                hunk.start,
+                                hunk.end,
+                            )
+                        })
+                    });
+                if locked {
+                    hunk.locked = true;
+                    hunk.locked_to = Some(*commit_id);
                 }
             }
         }
     }
-    Ok(branches)
+    branch
 }
 
 fn joined(start_a: u32, end_a: u32, start_b: u32, end_b: u32) -> bool {
@@ -1082,7 +1516,7 @@ fn files_with_hunk_context(
     branch_head: git::Oid,
 ) -> Result<Vec<VirtualBranchFile>> {
     for file in &mut files {
-        if file.binary {
+        if file.binary || file.large {
             continue;
         }
         // Get file content as it looked before the diffs
@@ -1297,6 +1731,169 @@ fn list_virtual_commit_files(
     ))
 }
 
+pub fn commit_author(
+    project_repository: &project_repository::Repository,
+    commit_oid: git::Oid,
+) -> Result<Author, errors::CommitAuthorError> {
+    match project_repository.git_repository.find_commit(commit_oid) {
+        Ok(commit) => Ok(commit.author().into()),
+        Err(git::Error::NotFound(_)) => Err(errors::CommitAuthorError::CommitNotFound(commit_oid)),
+        Err(error) => Err(errors::CommitAuthorError::Other(
+            anyhow::Error::from(error).context(format!("failed to find commit {}", commit_oid)),
+        )),
+    }
+}
+
+pub fn conflict_versions(
+    project_repository: &project_repository::Repository,
+    path: &Path,
+) -> Result<ConflictVersions, errors::ConflictVersionsError> {
+    let conflicted_file = conflicts::conflicted_files(project_repository)?
+        .into_iter()
+        .find(|conflicted_file| conflicted_file.path == path)
+        .ok_or_else(|| errors::ConflictVersionsError::NotConflicted(path.to_path_buf()))?;
+
+    let repo = &project_repository.git_repository;
+    let read_blob = |oid: Option<git::Oid>| {
+        oid.map(|oid| {
+            repo.find_blob(oid)
+                .map(|blob| reader::Content::from(&blob))
+                .context(format!("failed to find blob {}", oid))
+        })
+        .transpose()
+    };
+    let merged = reader::Content::read_from_file(project_repository.project().path.join(path))
+        .context(format!("failed to read {}", path.display()))?;
+
+    Ok(ConflictVersions {
+        base: read_blob(conflicted_file.base)?,
+        ours: read_blob(conflicted_file.ours)?,
+        theirs: read_blob(conflicted_file.theirs)?,
+        merged,
+    })
+}
+
+pub fn list_commit_files(
+    project_repository: &project_repository::Repository,
+    commit_oid: git::Oid,
+) -> Result<Vec<CommitFile>> {
+    let repo = &project_repository.git_repository;
+    let (parent_tree, commit_tree) = super::files::commit_trees(repo, commit_oid)?;
+    tree_files(repo, parent_tree.as_ref(), &commit_tree)
+}
+
+// the files that differ between the trees of `from` and `to`, as if `to` was committed on top of
+// `from`. swapping the two swaps additions with deletions.
+pub fn diff_commits(
+    project_repository: &project_repository::Repository,
+    from: git::Oid,
+    to: git::Oid,
+) -> Result<Vec<CommitFile>> {
+    let repo = &project_repository.git_repository;
+    let commit_tree = |oid: git::Oid| {
+        repo.find_commit(oid)
+            .context(format!("failed to find commit {}", oid))?
+            .tree()
+            .context(format!("failed to get tree of commit {}", oid))
+    };
+    let from_tree = commit_tree(from)?;
+    let to_tree = commit_tree(to)?;
+    tree_files(repo, Some(&from_tree), &to_tree)
+}
+
+// totals of all uncommitted changes in the working directory, whichever branch they belong to.
+// the head commit already has the commits of all applied branches, so it's diffed against that.
+pub fn uncommitted_stats(project_repository: &project_repository::Repository) -> Result<DiffStats> {
+    let head_tree = project_repository
+        .get_head()
+        .context("failed to get head")?
+        .peel_to_tree()
+        .context("failed to peel head to tree")?;
+
+    let mut diff_opts = git2::DiffOptions::new();
+    diff_opts
+        .recurse_untracked_dirs(true)
+        .include_untracked(true)
+        .show_untracked_content(true)
+        .ignore_submodules(true);
+    let stats = project_repository
+        .git_repository
+        .diff_tree_to_workdir(Some(&head_tree), Some(&mut diff_opts))
+        .context("failed to diff workdir")?
+        .stats()
+        .context("failed to get diff stats")?;
+
+    Ok(DiffStats {
+        files_changed: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+// files in the working directory that git ignores, so that changes to them never show up in any
+// branch. ignored directories are listed as a whole rather than file by file.
+pub fn ignored_changes(
+    project_repository: &project_repository::Repository,
+) -> Result<Vec<PathBuf>> {
+    let statuses = project_repository
+        .git_repository
+        .statuses(Some(
+            git2::StatusOptions::new()
+                .show(git2::StatusShow::Workdir)
+                .include_untracked(true)
+                .include_ignored(true)
+                .recurse_ignored_dirs(false),
+        ))
+        .context("failed to get status")?;
+
+    statuses
+        .iter()
+        .filter(|entry| entry.status().is_ignored())
+        .map(|entry| {
+            entry
+                .path_bytes()
+                .to_path()
+                .map(Path::to_path_buf)
+                .context("failed to convert path")
+        })
+        .collect()
+}
+
+fn tree_files(
+    repo: &git::Repository,
+    old_tree: Option<&git::Tree>,
+    new_tree: &git::Tree,
+) -> Result<Vec<CommitFile>> {
+    let diff = repo
+        .diff_tree_to_tree(old_tree, Some(new_tree), None)
+        .context("failed to diff trees")?;
+
+    diff.deltas()
+        .enumerate()
+        .map(|(idx, delta)| -> Result<CommitFile> {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .context("failed to get file path from diff")?
+                .to_path_buf();
+            let (_, insertions, deletions) = git2::Patch::from_diff(&diff, idx)
+                .context("failed to get patch")?
+                .map(|patch| patch.line_stats())
+                .transpose()
+                .context("failed to get line stats")?
+                .unwrap_or_default();
+            Ok(CommitFile {
+                path,
+                change_type: delta.status().into(),
+                binary: delta.new_file().is_binary() || delta.old_file().is_binary(),
+                insertions,
+                deletions,
+            })
+        })
+        .collect()
+}
+
 fn commit_to_vbranch_commit(
     repository: &project_repository::Repository,
     branch: &branch::Branch,
@@ -1323,6 +1920,7 @@ fn commit_to_vbranch_commit(
         is_integrated,
         parent_ids,
         branch_id: branch.id,
+        changed_files: None,
     };
 
     Ok(commit)
@@ -1333,6 +1931,10 @@ pub fn create_virtual_branch(
     project_repository: &project_repository::Repository,
     create: &BranchCreateRequest,
 ) -> Result<branch::Branch, errors::CreateVirtualBranchError> {
+    if let Some(name) = &create.name {
+        validate_branch_name(name)?;
+    }
+
     let current_session = gb_repository
         .get_or_create_current_session()
         .context("failed to get or create currnt session")?;
@@ -1441,9 +2043,12 @@ pub fn create_virtual_branch(
         head: default_target.sha,
         created_timestamp_ms: now,
         updated_timestamp_ms: now,
+        last_activity_ms: now,
         ownership: BranchOwnershipClaims::default(),
         order,
         selected_for_changes,
+        archived: false,
+        pushed_name: None,
     };
 
     if let Some(ownership) = &create.ownership {
@@ -1466,34 +2071,80 @@ pub fn create_virtual_branch(
     Ok(branch)
 }
 
-pub fn merge_virtual_branch_upstream(
+// creates a new branch named `name` that takes over the given hunks from `from_branch`.
+pub fn split_to_new_branch(
     gb_repository: &gb_repository::Repository,
     project_repository: &project_repository::Repository,
-    branch_id: &BranchId,
-    signing_key: Option<&keys::PrivateKey>,
-    user: Option<&users::User>,
-) -> Result<(), errors::MergeVirtualBranchUpstreamError> {
-    if conflicts::is_conflicting::<&Path>(project_repository, None)? {
-        return Err(errors::MergeVirtualBranchUpstreamError::Conflict(
-            errors::ProjectConflict {
-                project_id: project_repository.project().id,
-            },
-        ));
-    }
-
+    from_branch: &BranchId,
+    ownership: &[OwnershipClaim],
+    name: &str,
+) -> Result<branch::Branch, errors::SplitBranchError> {
     let current_session = gb_repository
         .get_or_create_current_session()
-        .context("failed to get current session")?;
+        .context("failed to get or create current session")?;
     let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
         .context("failed to open current session")?;
-
-    // get the branch
     let branch_reader = branch::Reader::new(
         &current_session_reader,
         VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
         project_repository.project().use_toml_vbranches_state(),
     );
-    let mut branch = match branch_reader.read(branch_id) {
+    match branch_reader.read(from_branch) {
+        Ok(branch) if branch.applied => {}
+        Ok(_) | Err(reader::Error::NotFound) => {
+            return Err(errors::SplitBranchError::BranchNotFound(
+                errors::BranchNotFound {
+                    project_id: project_repository.project().id,
+                    branch_id: *from_branch,
+                },
+            ))
+        }
+        Err(error) => return Err(errors::SplitBranchError::Other(error.into())),
+    }
+
+    let branch = create_virtual_branch(
+        gb_repository,
+        project_repository,
+        &BranchCreateRequest {
+            name: Some(name.to_string()),
+            ownership: Some(BranchOwnershipClaims {
+                claims: ownership.to_vec(),
+            }),
+            ..Default::default()
+        },
+    )?;
+
+    Ok(branch)
+}
+
+pub fn merge_virtual_branch_upstream(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    signing_key: Option<&keys::PrivateKey>,
+    user: Option<&users::User>,
+) -> Result<(), errors::MergeVirtualBranchUpstreamError> {
+    if conflicts::is_conflicting::<&Path>(project_repository, None)? {
+        return Err(errors::MergeVirtualBranchUpstreamError::Conflict(
+            errors::ProjectConflict {
+                project_id: project_repository.project().id,
+            },
+        ));
+    }
+
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+
+    // get the branch
+    let branch_reader = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    );
+    let mut branch = match branch_reader.read(branch_id) {
         Ok(branch) => Ok(branch),
         Err(reader::Error::NotFound) => Err(
             errors::MergeVirtualBranchUpstreamError::BranchNotFound(errors::BranchNotFound {
@@ -1707,11 +2358,92 @@ pub fn merge_virtual_branch_upstream(
     Ok(())
 }
 
+// applies all updates in order. if any of them fails, every branch is restored to the state
+// it had before the first update was applied.
+pub fn update_branches(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_updates: Vec<branch::BranchUpdateRequest>,
+) -> Result<Vec<branch::Branch>, errors::UpdateBranchError> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create currnt session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+
+    let snapshot = Iterator::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .context("failed to create branch iterator")?
+    .collect::<Result<Vec<branch::Branch>, reader::Error>>()
+    .context("failed to read virtual branches")?;
+
+    let mut updated_branches = Vec::with_capacity(branch_updates.len());
+    for branch_update in branch_updates {
+        match update_branch(gb_repository, project_repository, branch_update) {
+            Ok(branch) => updated_branches.push(branch),
+            Err(error) => {
+                restore_branches(gb_repository, project_repository, snapshot)
+                    .context("failed to restore branches")?;
+                return Err(error);
+            }
+        }
+    }
+
+    Ok(updated_branches)
+}
+
+fn restore_branches(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    snapshot: Vec<branch::Branch>,
+) -> Result<()> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create currnt session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+    let branch_reader = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    );
+    let branch_writer = branch::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create writer")?;
+
+    for mut branch in snapshot {
+        let current = branch_reader
+            .read(&branch.id)
+            .context("failed to read branch")?;
+        if current == branch {
+            continue;
+        }
+        if current.name != branch.name {
+            project_repository.delete_branch_reference(&current)?;
+            project_repository.add_branch_reference(&branch)?;
+        }
+        branch_writer
+            .write(&mut branch)
+            .context("failed to write branch")?;
+    }
+
+    Ok(())
+}
+
 pub fn update_branch(
     gb_repository: &gb_repository::Repository,
     project_repository: &project_repository::Repository,
     branch_update: branch::BranchUpdateRequest,
 ) -> Result<branch::Branch, errors::UpdateBranchError> {
+    if let Some(name) = &branch_update.name {
+        validate_branch_name(name)?;
+    }
+
     let current_session = gb_repository
         .get_or_create_current_session()
         .context("failed to get or create currnt session")?;
@@ -1749,6 +2481,7 @@ pub fn update_branch(
             project_repository.project(),
         )
         .context("failed to set ownership")?;
+        branch.mark_active();
     }
 
     if let Some(name) = branch_update.name {
@@ -1772,6 +2505,7 @@ pub fn update_branch(
         );
 
         project_repository.add_branch_reference(&branch)?;
+        branch.mark_active();
     };
 
     if let Some(updated_upstream) = branch_update.upstream {
@@ -1878,6 +2612,175 @@ pub fn delete_branch(
     Ok(())
 }
 
+// archived branches are unapplied and hidden from the branch list, but kept in storage
+// so that they can be restored later.
+pub fn set_branch_archived(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    archived: bool,
+) -> Result<(), Error> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create currnt session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+    let branch_reader = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    );
+    let branch_writer = branch::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create writer")?;
+
+    let mut branch = branch_reader.read(branch_id).map_err(|error| match error {
+        reader::Error::NotFound => {
+            errors::UpdateBranchError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+        }
+        error => errors::UpdateBranchError::Other(error.into()),
+    })?;
+
+    if branch.archived == archived {
+        return Ok(());
+    }
+
+    if archived && branch.applied {
+        // unapplying a branch without any changes removes it, so there is nothing left to archive
+        match unapply_branch(gb_repository, project_repository, branch_id)? {
+            Some(unapplied) => branch = unapplied,
+            None => return Ok(()),
+        }
+    }
+
+    branch.archived = archived;
+    branch_writer
+        .write(&mut branch)
+        .context("failed to write target branch")?;
+
+    Ok(())
+}
+
+// rewrites the order of the branches to match `ordered_ids`. branches that are not listed
+// keep their relative order and are placed after the listed ones.
+pub fn reorder_branches(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    ordered_ids: &[BranchId],
+) -> Result<(), Error> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create currnt session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+    let branch_writer = branch::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create writer")?;
+
+    let mut all_virtual_branches = Iterator::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .context("failed to create branch iterator")?
+    .collect::<Result<Vec<branch::Branch>, reader::Error>>()
+    .context("failed to read virtual branches")?;
+    all_virtual_branches.sort_by_key(|branch| branch.order);
+
+    if let Some(branch_id) = ordered_ids
+        .iter()
+        .find(|id| !all_virtual_branches.iter().any(|branch| &branch.id == *id))
+    {
+        return Err(
+            errors::UpdateBranchError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+            .into(),
+        );
+    }
+
+    all_virtual_branches.sort_by_key(|branch| {
+        ordered_ids
+            .iter()
+            .position(|id| id == &branch.id)
+            .unwrap_or(ordered_ids.len())
+    });
+
+    for (order, mut branch) in all_virtual_branches.into_iter().enumerate() {
+        if branch.order != order {
+            branch.order = order;
+            branch_writer
+                .write(&mut branch)
+                .context("failed to write branch")?;
+        }
+    }
+
+    Ok(())
+}
+
+// returns the ids of all applied branches, in the order they are displayed in.
+pub fn applied_branch_ids(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<Vec<BranchId>> {
+    Ok(applied_branches(gb_repository, project_repository)?
+        .into_iter()
+        .map(|branch| branch.id)
+        .collect())
+}
+
+// returns the ids and names of the applied branches in lane order, without computing any diffs.
+pub fn applied_branch_names(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<Vec<(String, String)>> {
+    Ok(applied_branches(gb_repository, project_repository)?
+        .into_iter()
+        .map(|branch| (branch.id.to_string(), branch.name))
+        .collect())
+}
+
+fn applied_branches(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<Vec<branch::Branch>> {
+    let mut applied_branches = all_branches(gb_repository, project_repository)?
+        .into_iter()
+        .filter(|branch| branch.applied)
+        .collect::<Vec<_>>();
+    applied_branches.sort_by_key(|branch| branch.order);
+
+    Ok(applied_branches)
+}
+
+fn all_branches(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<Vec<branch::Branch>> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create currnt session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+
+    Iterator::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .context("failed to create branch iterator")?
+    .collect::<Result<Vec<branch::Branch>, reader::Error>>()
+    .context("failed to read virtual branches")
+}
+
 fn ensure_selected_for_changes(
     current_session_reader: &sessions::Reader,
     branch_writer: &branch::Writer,
@@ -2012,37 +2915,17 @@ pub type BranchStatus = HashMap<PathBuf, Vec<diff::GitHunk>>;
 pub fn get_status_by_branch(
     gb_repository: &gb_repository::Repository,
     project_repository: &project_repository::Repository,
-) -> Result<(Vec<(branch::Branch, BranchStatus)>, Vec<diff::FileDiff>)> {
-    let latest_session = gb_repository
-        .get_latest_session()
-        .context("failed to get latest session")?
-        .context("latest session not found")?;
-    let session_reader = sessions::Reader::open(gb_repository, &latest_session)
-        .context("failed to open current session")?;
-
-    let default_target = match get_default_target(&session_reader, project_repository.project())
-        .context("failed to read default target")?
-    {
-        Some(target) => target,
-        None => {
-            return Ok((vec![], vec![]));
-        }
+) -> Result<(Vec<(branch::Branch, BranchStatus)>, Vec<SkippedFile>)> {
+    let Some((default_target, virtual_branches)) =
+        read_virtual_branches(gb_repository, project_repository)?
+    else {
+        return Ok((vec![], vec![]));
     };
 
-    let virtual_branches = Iterator::new(
-        &session_reader,
-        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
-        project_repository.project().use_toml_vbranches_state(),
-    )
-    .context("failed to create branch iterator")?
-    .collect::<Result<Vec<branch::Branch>, reader::Error>>()
-    .context("failed to read virtual branches")?;
-
-    let applied_virtual_branches = virtual_branches
-        .iter()
-        .filter(|branch| branch.applied)
-        .cloned()
-        .collect::<Vec<_>>();
+    let (applied_virtual_branches, non_applied_virtual_branches): (Vec<_>, Vec<_>) =
+        virtual_branches
+            .into_iter()
+            .partition(|branch| branch.applied);
 
     let (applied_status, skipped_files) = get_applied_status(
         gb_repository,
@@ -2051,11 +2934,6 @@ pub fn get_status_by_branch(
         applied_virtual_branches,
     )?;
 
-    let non_applied_virtual_branches = virtual_branches
-        .into_iter()
-        .filter(|branch| !branch.applied)
-        .collect::<Vec<_>>();
-
     let non_applied_status = get_non_applied_status(
         project_repository,
         &default_target,
@@ -2071,17 +2949,92 @@ pub fn get_status_by_branch(
     ))
 }
 
-// given a list of non applied virtual branches, return the status of each file, comparing the default target with
-// virtual branch latest tree
-//
-// ownerships are not taken into account here, as they are not relevant for non applied branches
-fn get_non_applied_status(
+// like `get_status_by_branch`, but without writing anything. `None` if the ownership of the
+// applied branches has to be written, see `read_applied_status`.
+#[allow(clippy::type_complexity)]
+fn read_status_by_branch(
+    gb_repository: &gb_repository::Repository,
     project_repository: &project_repository::Repository,
-    default_target: &target::Target,
-    virtual_branches: Vec<branch::Branch>,
-) -> Result<Vec<(branch::Branch, BranchStatus)>> {
-    virtual_branches
-        .into_iter()
+) -> Result<Option<(Vec<(branch::Branch, BranchStatus)>, Vec<SkippedFile>)>> {
+    let Some((default_target, virtual_branches)) =
+        read_virtual_branches(gb_repository, project_repository)?
+    else {
+        return Ok(Some((vec![], vec![])));
+    };
+
+    let (applied_virtual_branches, non_applied_virtual_branches): (Vec<_>, Vec<_>) =
+        virtual_branches
+            .into_iter()
+            .partition(|branch| branch.applied);
+
+    let Some((applied_status, skipped_files)) = read_applied_status(
+        project_repository,
+        &default_target,
+        applied_virtual_branches,
+    )?
+    else {
+        return Ok(None);
+    };
+
+    let non_applied_status = get_non_applied_status(
+        project_repository,
+        &default_target,
+        non_applied_virtual_branches,
+    )?;
+
+    Ok(Some((
+        applied_status
+            .into_iter()
+            .chain(non_applied_status)
+            .collect(),
+        skipped_files,
+    )))
+}
+
+// the default target and all virtual branches, or `None` if there is no default target
+fn read_virtual_branches(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<Option<(target::Target, Vec<branch::Branch>)>> {
+    let latest_session = gb_repository
+        .get_latest_session()
+        .context("failed to get latest session")?
+        .context("latest session not found")?;
+    let session_reader = sessions::Reader::open(gb_repository, &latest_session)
+        .context("failed to open current session")?;
+
+    let default_target = match get_default_target(&session_reader, project_repository.project())
+        .context("failed to read default target")?
+    {
+        Some(target) => target,
+        None => {
+            return Ok(None);
+        }
+    };
+
+    let virtual_branches = Iterator::new(
+        &session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .context("failed to create branch iterator")?
+    .collect::<Result<Vec<branch::Branch>, reader::Error>>()
+    .context("failed to read virtual branches")?;
+
+    Ok(Some((default_target, virtual_branches)))
+}
+
+// given a list of non applied virtual branches, return the status of each file, comparing the default target with
+// virtual branch latest tree
+//
+// ownerships are not taken into account here, as they are not relevant for non applied branches
+fn get_non_applied_status(
+    project_repository: &project_repository::Repository,
+    default_target: &target::Target,
+    virtual_branches: Vec<branch::Branch>,
+) -> Result<Vec<(branch::Branch, BranchStatus)>> {
+    virtual_branches
+        .into_iter()
         .map(
             |branch| -> Result<(branch::Branch, HashMap<PathBuf, Vec<diff::GitHunk>>)> {
                 if branch.applied {
@@ -2121,22 +3074,8 @@ fn get_applied_status(
     project_repository: &project_repository::Repository,
     default_target: &target::Target,
     mut virtual_branches: Vec<branch::Branch>,
-) -> Result<(AppliedStatuses, Vec<diff::FileDiff>)> {
-    let base_file_diffs = diff::workdir(
-        &project_repository.git_repository,
-        &default_target.sha,
-        context_lines(project_repository),
-    )
-    .context("failed to diff workdir")?;
-
-    let mut base_diffs: HashMap<PathBuf, Vec<git::diff::GitHunk>> =
-        diff_files_to_hunks(&base_file_diffs);
-    let mut skipped_files: Vec<diff::FileDiff> = Vec::new();
-    for (_, file_diff) in base_file_diffs {
-        if file_diff.skipped {
-            skipped_files.push(file_diff);
-        }
-    }
+) -> Result<(AppliedStatuses, Vec<SkippedFile>)> {
+    let (base_diffs, skipped_files) = workdir_diffs(project_repository, default_target)?;
 
     // sort by order, so that the default branch is first (left in the ui)
     virtual_branches.sort_by(|a, b| a.order.cmp(&b.order));
@@ -2151,6 +3090,106 @@ fn get_applied_status(
         .context("failed to create default branch")?];
     }
 
+    let mut hunks_by_branch = claim_hunks(virtual_branches, base_diffs)?;
+
+    // write updated state if not resolving
+    if !project_repository.is_resolving() {
+        let branch_writer = branch::Writer::new(
+            gb_repository,
+            VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        )
+        .context("failed to create writer")?;
+        for (vbranch, files) in &mut hunks_by_branch {
+            vbranch.tree = write_tree(project_repository, default_target, files)?;
+            branch_writer
+                .write(vbranch)
+                .context(format!("failed to write virtual branch {}", vbranch.name))?;
+        }
+    }
+
+    Ok((hunks_by_branch, skipped_files))
+}
+
+// like `get_applied_status`, but without writing anything. `None` if there is something to write:
+// the recalculated ownership of a branch differs from its written one, or there is no branch to
+// own the changes in the working directory.
+fn read_applied_status(
+    project_repository: &project_repository::Repository,
+    default_target: &target::Target,
+    mut virtual_branches: Vec<branch::Branch>,
+) -> Result<Option<(AppliedStatuses, Vec<SkippedFile>)>> {
+    let (base_diffs, skipped_files) = workdir_diffs(project_repository, default_target)?;
+
+    if virtual_branches.is_empty() && !base_diffs.is_empty() {
+        return Ok(None);
+    }
+
+    // sort by order, so that the default branch is first (left in the ui)
+    virtual_branches.sort_by(|a, b| a.order.cmp(&b.order));
+
+    // compared as written, as unlike `==` that includes the timestamps of the hunks
+    let written_ownership = virtual_branches
+        .iter()
+        .map(|branch| (branch.id, branch.ownership.to_string()))
+        .collect::<HashMap<_, _>>();
+
+    let hunks_by_branch = claim_hunks(virtual_branches, base_diffs)?;
+
+    // nothing is written while resolving conflicts either
+    let outdated = !project_repository.is_resolving()
+        && hunks_by_branch.iter().any(|(branch, _)| {
+            written_ownership.get(&branch.id) != Some(&branch.ownership.to_string())
+        });
+    if outdated {
+        return Ok(None);
+    }
+
+    Ok(Some((hunks_by_branch, skipped_files)))
+}
+
+// the hunks of the working directory compared to the default target by file, leaving out the
+// files that are skipped
+fn workdir_diffs(
+    project_repository: &project_repository::Repository,
+    default_target: &target::Target,
+) -> Result<(HashMap<PathBuf, Vec<diff::GitHunk>>, Vec<SkippedFile>)> {
+    let base_file_diffs = diff::workdir(
+        &project_repository.git_repository,
+        &default_target.sha,
+        context_lines(project_repository),
+    )
+    .context("failed to diff workdir")?;
+
+    let mut base_diffs: HashMap<PathBuf, Vec<git::diff::GitHunk>> =
+        diff_files_to_hunks(&base_file_diffs);
+    let conflicting_files = conflicts::conflicting_files(project_repository)?;
+    let mut skipped_files: Vec<SkippedFile> = Vec::new();
+    for (path, file_diff) in base_file_diffs {
+        if !file_diff.skipped {
+            continue;
+        }
+        // large files are changed as a whole by a single hunk and are listed like any other,
+        // unless they are conflicted, as there is no telling if the conflict is resolved
+        if !conflicting_files.contains(&path.display().to_string()) {
+            continue;
+        }
+        base_diffs.remove(&path);
+        skipped_files.push(SkippedFile {
+            path,
+            reason: SkipReason::Conflicted,
+        });
+    }
+
+    Ok((base_diffs, skipped_files))
+}
+
+// assigns the hunks of the working directory to the applied branches, by the ownership claims of
+// the branches. hunks nobody claims go to the branch selected for changes. the ownership of the
+// branches is updated to match, but not written.
+fn claim_hunks(
+    mut virtual_branches: Vec<branch::Branch>,
+    mut base_diffs: HashMap<PathBuf, Vec<diff::GitHunk>>,
+) -> Result<AppliedStatuses> {
     // align branch ownership to the real hunks:
     // - update shifted hunks
     // - remove non existent hunks
@@ -2278,7 +3317,7 @@ fn get_applied_status(
         }
     }
 
-    let mut hunks_by_branch = diffs_by_branch
+    let hunks_by_branch = diffs_by_branch
         .into_iter()
         .map(|(branch_id, hunks)| {
             (
@@ -2292,29 +3331,14 @@ fn get_applied_status(
         })
         .collect::<Vec<_>>();
 
-    // write updated state if not resolving
-    if !project_repository.is_resolving() {
-        let branch_writer = branch::Writer::new(
-            gb_repository,
-            VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
-        )
-        .context("failed to create writer")?;
-        for (vbranch, files) in &mut hunks_by_branch {
-            vbranch.tree = write_tree(project_repository, default_target, files)?;
-            branch_writer
-                .write(vbranch)
-                .context(format!("failed to write virtual branch {}", vbranch.name))?;
-        }
-    }
-
-    Ok((hunks_by_branch, skipped_files))
+    Ok(hunks_by_branch)
 }
 
 fn virtual_hunks_to_virtual_files(
     project_repository: &project_repository::Repository,
     hunks: &[VirtualBranchHunk],
 ) -> Vec<VirtualBranchFile> {
-    hunks
+    let mut files = hunks
         .iter()
         .fold(HashMap::<PathBuf, Vec<_>>::new(), |mut acc, hunk| {
             acc.entry(hunk.file_path.clone())
@@ -2326,17 +3350,57 @@ fn virtual_hunks_to_virtual_files(
         .map(|(file_path, hunks)| VirtualBranchFile {
             id: file_path.display().to_string(),
             path: file_path.clone(),
-            hunks: hunks.clone(),
             binary: hunks.iter().any(|h| h.binary),
             large: false,
+            size_delta_bytes: None,
             modified_at: hunks.iter().map(|h| h.modified_at).max().unwrap_or(0),
             conflicted: conflicts::is_conflicting(
                 project_repository,
                 Some(&file_path.display().to_string()),
             )
             .unwrap_or(false),
+            status: file_status(&hunks),
+            hunks,
         })
-        .collect::<Vec<_>>()
+        .collect::<Vec<_>>();
+    detect_renames(&mut files);
+    files
+}
+
+fn file_status(hunks: &[VirtualBranchHunk]) -> FileStatus {
+    match hunks.first().map(|hunk| hunk.change_type) {
+        Some(diff::ChangeType::Added) => FileStatus::Added,
+        Some(diff::ChangeType::Deleted) => FileStatus::Deleted,
+        _ => FileStatus::Modified,
+    }
+}
+
+fn detect_renames(files: &mut [VirtualBranchFile]) {
+    fn changed_lines(file: &VirtualBranchFile, origin: char) -> Vec<&str> {
+        file.hunks
+            .iter()
+            .flat_map(|hunk| hunk.diff.lines())
+            .filter_map(|line| line.strip_prefix(origin))
+            .collect()
+    }
+
+    let mut deleted = files
+        .iter()
+        .filter(|file| file.status == FileStatus::Deleted && !file.binary)
+        .map(|file| (file.path.clone(), changed_lines(file, '-').join("\n")))
+        .filter(|(_, content)| !content.is_empty())
+        .collect::<Vec<_>>();
+
+    for file in files
+        .iter_mut()
+        .filter(|file| file.status == FileStatus::Added && !file.binary)
+    {
+        let content = changed_lines(file, '+').join("\n");
+        if let Some(pos) = deleted.iter().position(|(_, deleted)| *deleted == content) {
+            let (from, _) = deleted.remove(pos);
+            file.status = FileStatus::Renamed { from };
+        }
+    }
 }
 
 // reset virtual branch to a specific commit
@@ -2508,7 +3572,13 @@ pub fn write_tree_onto_tree(
                 if hunks.len() == 1 && hunks[0].binary {
                     let new_blob_oid = &hunks[0].diff;
                     // convert string to Oid
-                    let new_blob_oid = new_blob_oid.parse().context("failed to diff as oid")?;
+                    let mut new_blob_oid = new_blob_oid.parse().context("failed to diff as oid")?;
+                    // large files are only hashed when diffed, so their blob may not be written yet
+                    if git_repository.blob_size(new_blob_oid).is_err() {
+                        new_blob_oid = git_repository
+                            .blob_path(&full_path)
+                            .context(format!("failed to create blob from path {:?}", &full_path))?;
+                    }
                     builder.upsert(rel_path, new_blob_oid, filemode);
                 } else {
                     // blob from tree_entry
@@ -2594,6 +3664,54 @@ fn _print_tree(repo: &git2::Repository, tree: &git2::Tree) -> Result<()> {
     Ok(())
 }
 
+// appends `trailers` to the message like `git interpret-trailers` does. trailers that are already
+// there are left out, and if the message ends with trailers the new ones are added to them instead
+// of to a paragraph of their own.
+fn with_trailers(message: &str, trailers: &[(String, String)]) -> String {
+    let parse = |line: &str| {
+        let (token, value) = line.split_once(':')?;
+        let is_token = !token.is_empty()
+            && token
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric() || char == '-');
+        is_token.then(|| (token.to_ascii_lowercase(), value.trim().to_string()))
+    };
+
+    let body = message.trim_end();
+    // the subject is never a trailer, even if it looks like one
+    let mut seen = body
+        .rsplit_once("\n\n")
+        .and_then(|(_, last_paragraph)| {
+            last_paragraph
+                .lines()
+                .map(parse)
+                .collect::<Option<Vec<_>>>()
+        })
+        .unwrap_or_default();
+    let ends_with_trailers = !seen.is_empty();
+
+    let mut lines = vec![];
+    for (token, value) in trailers {
+        let (token, value) = (token.trim(), value.trim());
+        let key = (token.to_ascii_lowercase(), value.to_string());
+        if !seen.contains(&key) {
+            seen.push(key);
+            lines.push(format!("{}: {}", token, value));
+        }
+    }
+
+    match (lines.is_empty(), body.is_empty()) {
+        (true, _) => message.to_owned(),
+        (false, true) => lines.join("\n"),
+        (false, false) => format!(
+            "{}{}{}",
+            body,
+            if ends_with_trailers { "\n" } else { "\n\n" },
+            lines.join("\n")
+        ),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn commit(
     gb_repository: &gb_repository::Repository,
@@ -2603,11 +3721,11 @@ pub fn commit(
     ownership: Option<&branch::BranchOwnershipClaims>,
     signing_key: Option<&keys::PrivateKey>,
     user: Option<&users::User>,
-    run_hooks: bool,
+    options: &CommitOptions,
 ) -> Result<git::Oid, errors::CommitError> {
-    let mut message_buffer = message.to_owned();
+    let mut message_buffer = with_trailers(message, &options.trailers);
 
-    if run_hooks {
+    if options.run_hooks {
         let hook_result = project_repository
             .git_repository
             .run_hook_commit_msg(&mut message_buffer)
@@ -2703,6 +3821,11 @@ pub fn commit(
     let extra_merge_parent =
         conflicts::merge_parent(project_repository).context("failed to get merge parent")?;
 
+    // a merge commit records the resolution even if the tree is unchanged
+    if options.reject_empty && extra_merge_parent.is_none() && tree_oid == parent_commit.tree_id() {
+        return Err(errors::CommitError::NothingToCommit(*branch_id));
+    }
+
     let commit_oid = match extra_merge_parent {
         Some(merge_parent) => {
             let merge_parent = git_repository
@@ -2721,11 +3844,22 @@ pub fn commit(
         None => project_repository.commit(user, message, &tree, &[&parent_commit], signing_key)?,
     };
 
-    if run_hooks {
-        project_repository
+    if options.run_hooks {
+        let hook_result = project_repository
             .git_repository
             .run_hook_post_commit()
             .context("failed to run hook")?;
+
+        // the commit is already recorded, so a failing hook can't undo it
+        if let HookResult::RunNotSuccessful { stdout, stderr, .. } = hook_result {
+            tracing::warn!(
+                project_id = %project_repository.project().id,
+                %commit_oid,
+                stdout,
+                stderr,
+                "post-commit hook failed"
+            );
+        }
     }
 
     // update the virtual branch head
@@ -2736,6 +3870,7 @@ pub fn commit(
     .context("failed to create writer")?;
     branch.tree = tree_oid;
     branch.head = commit_oid;
+    branch.mark_active();
     writer.write(branch).context("failed to write branch")?;
 
     super::integration::update_gitbutler_integration(gb_repository, project_repository)
@@ -2744,6 +3879,12 @@ pub fn commit(
     Ok(commit_oid)
 }
 
+/// How long to wait before the first push retry. The wait doubles with every retry. The caller
+/// holds the lock of the controller while waiting, so the wait is kept short.
+const PUSH_BACKOFF: time::Duration = time::Duration::from_millis(200);
+
+// pushes the branch, retrying the push itself up to `retries` times when it fails with a network
+// error, see `push_with_retries`. the pre-push hook is run only once.
 pub fn push(
     project_repository: &project_repository::Repository,
     gb_repository: &gb_repository::Repository,
@@ -2751,6 +3892,7 @@ pub fn push(
     with_force: bool,
     credentials: &git::credentials::Helper,
     askpass: Option<(AskpassBroker, Option<BranchId>)>,
+    retries: usize,
 ) -> Result<(), errors::PushError> {
     let current_session = gb_repository
         .get_or_create_current_session()
@@ -2779,7 +3921,21 @@ pub fn push(
         error => errors::PushError::Other(error.into()),
     })?;
 
-    let remote_branch = if let Some(upstream_branch) = vbranch.upstream.as_ref() {
+    // a branch renamed since it was last pushed is pushed under its new name and the remote branch
+    // of the old name is deleted. upstreams not derived from the branch name are left alone.
+    let renamed_upstream = match (&vbranch.upstream, &vbranch.pushed_name) {
+        (Some(upstream), Some(pushed_name))
+            if *pushed_name != vbranch.name
+                && upstream.branch() == normalize_branch_name(pushed_name) =>
+        {
+            Some(upstream.clone())
+        }
+        _ => None,
+    };
+
+    let remote_branch = if let (Some(upstream_branch), None) =
+        (vbranch.upstream.as_ref(), renamed_upstream.as_ref())
+    {
         upstream_branch.clone()
     } else {
         let default_target =
@@ -2791,9 +3947,16 @@ pub fn push(
                     })
                 })?;
 
+        // the base branch is always fetched from the target's remote, but forks push elsewhere
+        let push_remote = project_repository
+            .project()
+            .push_remote_name
+            .as_deref()
+            .unwrap_or(default_target.branch.remote());
+
         let remote_branch = format!(
             "refs/remotes/{}/{}",
-            default_target.branch.remote(),
+            push_remote,
             normalize_branch_name(&vbranch.name)
         )
         .parse::<git::RemoteRefname>()
@@ -2816,17 +3979,44 @@ pub fn push(
         ))
     };
 
-    project_repository.push(
-        &vbranch.head,
-        &remote_branch,
-        with_force,
-        credentials,
-        None,
-        askpass.clone(),
-    )?;
+    run_pre_push_hook(project_repository, &vbranch, &remote_branch)?;
+
+    push_with_retries(retries, PUSH_BACKOFF, || {
+        project_repository
+            .push(
+                &vbranch.head,
+                &remote_branch,
+                with_force,
+                credentials,
+                None,
+                askpass.clone(),
+            )
+            .map_err(Into::into)
+    })?;
+
+    if let Some(renamed_upstream) = &renamed_upstream {
+        project_repository.push(
+            &vbranch.head,
+            renamed_upstream,
+            false,
+            credentials,
+            Some(format!(":refs/heads/{}", renamed_upstream.branch())),
+            askpass.clone(),
+        )?;
+        // fetching doesn't prune, so the remote tracking branch has to go as well
+        if let Ok(mut reference) = project_repository
+            .git_repository
+            .find_reference(&renamed_upstream.clone().into())
+        {
+            reference
+                .delete()
+                .context("failed to delete remote tracking branch")?;
+        }
+    }
 
     vbranch.upstream = Some(remote_branch.clone());
     vbranch.upstream_head = Some(vbranch.head);
+    vbranch.pushed_name = Some(vbranch.name.clone());
     branch_writer
         .write(&mut vbranch)
         .context("failed to write target branch after push")?;
@@ -2839,6 +4029,141 @@ pub fn push(
     Ok(())
 }
 
+// pushes the branch to `upstream` on the push remote from now on, instead of a name derived from
+// the branch name. unlike the derived one, it is used as is and is not renamed with the branch.
+pub fn set_branch_remote_name(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    upstream: &str,
+) -> Result<(), errors::SetBranchRemoteNameError> {
+    validate_branch_name(upstream)?;
+    if !git2::Reference::is_valid_name(&format!("refs/heads/{}", upstream)) {
+        return Err(errors::BranchNameError::InvalidRefname(upstream.to_string()).into());
+    }
+
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+
+    let default_target = get_default_target(&current_session_reader, project_repository.project())
+        .context("failed to read default target")?
+        .ok_or_else(|| {
+            errors::SetBranchRemoteNameError::DefaultTargetNotSet(errors::DefaultTargetNotSet {
+                project_id: project_repository.project().id,
+            })
+        })?;
+
+    let mut branch = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .read(branch_id)
+    .map_err(|error| match error {
+        reader::Error::NotFound => {
+            errors::SetBranchRemoteNameError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+        }
+        error => errors::SetBranchRemoteNameError::Other(error.into()),
+    })?;
+
+    let push_remote = project_repository
+        .project()
+        .push_remote_name
+        .as_deref()
+        .unwrap_or(default_target.branch.remote());
+    let remote_branch = format!("refs/remotes/{}/{}", push_remote, upstream)
+        .parse::<git::RemoteRefname>()
+        .context("failed to parse remote branch name")?;
+
+    if branch.upstream.as_ref() == Some(&remote_branch) {
+        return Ok(());
+    }
+    // what was pushed to the previous upstream says nothing about the new one
+    branch.upstream = Some(remote_branch);
+    branch.upstream_head = None;
+    branch::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create writer")?
+    .write(&mut branch)
+    .context("failed to write branch")?;
+
+    Ok(())
+}
+
+/// Calls `push` until it succeeds, fails with an error that isn't worth retrying, or has been
+/// retried `retries` times. The wait before the first retry is `backoff`, and doubles after that.
+pub fn push_with_retries<T>(
+    retries: usize,
+    backoff: time::Duration,
+    mut push: impl FnMut() -> Result<T, errors::PushError>,
+) -> Result<T, errors::PushError> {
+    let mut backoff = backoff;
+    let mut attempt = 0;
+    loop {
+        match push() {
+            Err(errors::PushError::Remote(error)) if error.is_retryable() && attempt < retries => {
+                attempt += 1;
+                tracing::warn!(?error, attempt, ?backoff, "push failed, retrying");
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn run_pre_push_hook(
+    project_repository: &project_repository::Repository,
+    vbranch: &branch::Branch,
+    remote_branch: &git::RemoteRefname,
+) -> Result<(), errors::PushError> {
+    let remote_url = project_repository
+        .git_repository
+        .find_remote(remote_branch.remote())
+        .context("failed to find remote")?
+        .url()
+        .context("failed to get remote url")?
+        .map(|url| url.to_string())
+        .unwrap_or_default();
+
+    // what the remote branch is known to point to, or all zeros if it is being created
+    let remote_head = vbranch
+        .upstream
+        .as_ref()
+        .filter(|upstream| *upstream == remote_branch)
+        .and(vbranch.upstream_head)
+        .map_or_else(|| "0".repeat(40), |oid| oid.to_string());
+
+    let updates = format!(
+        "{} {} refs/heads/{} {}\n",
+        git::VirtualRefname::from(vbranch),
+        vbranch.head,
+        remote_branch.branch(),
+        remote_head
+    );
+
+    let hook_result = project_repository
+        .git_repository
+        .run_hook_pre_push(remote_branch.remote(), &remote_url, &updates)
+        .context("failed to run hook")?;
+
+    if let HookResult::RunNotSuccessful { stdout, stderr, .. } = hook_result {
+        return Err(errors::PushError::PrePushHookRejected(
+            [stdout, stderr].concat(),
+        ));
+    }
+
+    Ok(())
+}
+
 fn is_commit_integrated(
     project_repository: &project_repository::Repository,
     target: &target::Target,
@@ -2958,16 +4283,471 @@ pub fn is_remote_branch_mergeable(
         &target_commit,
     )?;
 
-    let wd_tree = project_repository.get_wd_tree()?;
+    let wd_tree = project_repository.get_wd_tree()?;
+
+    let branch_tree = branch_commit.tree().context("failed to find branch tree")?;
+    let mergeable = !project_repository
+        .git_repository
+        .merge_trees(&base_tree, &branch_tree, &wd_tree)
+        .context("failed to merge trees")?
+        .has_conflicts();
+
+    Ok(mergeable)
+}
+
+// the remote branch a virtual branch was pushed to
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamInfo {
+    pub remote_ref: String,
+    // as of the last fetch. a deleted remote branch is neither ahead nor behind.
+    pub exists_on_remote: bool,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+// returns where the branch was pushed to, or `None` if it was never pushed.
+pub fn branch_upstream(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+) -> Result<Option<UpstreamInfo>, errors::BranchUpstreamError> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+    let branch = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .read(branch_id)
+    .map_err(|error| match error {
+        reader::Error::NotFound => {
+            errors::BranchUpstreamError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+        }
+        error => errors::BranchUpstreamError::Other(error.into()),
+    })?;
+
+    let Some(upstream) = &branch.upstream else {
+        return Ok(None);
+    };
+    let remote_ref = upstream.to_string();
+
+    let upstream_oid = match project_repository.git_repository.refname_to_id(&remote_ref) {
+        Ok(oid) => oid,
+        Err(git::Error::NotFound(_)) => {
+            return Ok(Some(UpstreamInfo {
+                remote_ref,
+                exists_on_remote: false,
+                ahead: 0,
+                behind: 0,
+            }))
+        }
+        Err(error) => {
+            return Err(anyhow::Error::from(error)
+                .context("failed to find upstream reference")
+                .into())
+        }
+    };
+
+    let (ahead, behind) = project_repository
+        .git_repository
+        .graph_ahead_behind(branch.head, upstream_oid)
+        .context("failed to count commits ahead and behind upstream")?;
+
+    Ok(Some(UpstreamInfo {
+        remote_ref,
+        exists_on_remote: true,
+        ahead,
+        behind,
+    }))
+}
+
+// a file as it was right after a commit that changed it
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRevision {
+    pub commit_id: git::Oid,
+    pub author: Author,
+    pub content: reader::Content,
+}
+
+// returns the revisions of the file at `path` across the commits of the branch, newest first.
+// commits that didn't change the file are skipped, and so are those that deleted it, as there is
+// no content to show for them.
+pub fn file_history(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    path: &Path,
+) -> Result<Vec<FileRevision>, errors::FileHistoryError> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+
+    let default_target = get_default_target(&current_session_reader, project_repository.project())
+        .context("failed to read default target")?
+        .ok_or_else(|| {
+            errors::FileHistoryError::DefaultTargetNotSet(errors::DefaultTargetNotSet {
+                project_id: project_repository.project().id,
+            })
+        })?;
+
+    let branch = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .read(branch_id)
+    .map_err(|error| match error {
+        reader::Error::NotFound => {
+            errors::FileHistoryError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+        }
+        error => errors::FileHistoryError::Other(error.into()),
+    })?;
+
+    let repo = &project_repository.git_repository;
+    let blob_id = |commit: &git::Commit| -> Result<Option<git::Oid>> {
+        let tree = commit.tree().context("failed to get commit tree")?;
+        Ok(tree.get_path(path).ok().map(|entry| entry.id()))
+    };
+
+    let mut revisions = vec![];
+    for commit in project_repository.log(branch.head, LogUntil::Commit(default_target.sha))? {
+        let Some(blob_id) = blob_id(&commit)? else {
+            continue;
+        };
+        let parent_blob_id = if commit.parent_count() == 0 {
+            None
+        } else {
+            blob_id(&commit.parent(0).context("failed to get parent commit")?)?
+        };
+        if parent_blob_id == Some(blob_id) {
+            continue;
+        }
+
+        let content = reader::Reader::from_commit(repo, &commit)?
+            .read(path)
+            .with_context(|| format!("failed to read {} at {}", path.display(), commit.id()))?;
+        revisions.push(FileRevision {
+            commit_id: commit.id(),
+            author: Author::from(commit.author()),
+            content,
+        });
+    }
+
+    Ok(revisions)
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitGraphNode {
+    pub id: git::Oid,
+    pub author: Author,
+    pub created_at: u128,
+    pub description: String,
+}
+
+// points from a commit to one of its parents
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitGraphEdge {
+    pub child: git::Oid,
+    pub parent: git::Oid,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitGraph {
+    pub nodes: Vec<CommitGraphNode>,
+    pub edges: Vec<CommitGraphEdge>,
+}
+
+// returns the commits of all applied branches down to the base commit, which is included as well.
+// commits that are shared between branches are only part of the graph once. edges only point to
+// commits of the graph, so parents below the base are left out.
+pub fn commit_graph(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+) -> Result<CommitGraph, errors::CommitGraphError> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+
+    let default_target = get_default_target(&current_session_reader, project_repository.project())
+        .context("failed to read default target")?
+        .ok_or_else(|| {
+            errors::CommitGraphError::DefaultTargetNotSet(errors::DefaultTargetNotSet {
+                project_id: project_repository.project().id,
+            })
+        })?;
+
+    let mut commits = vec![project_repository
+        .git_repository
+        .find_commit(default_target.sha)
+        .context("failed to find base commit")?];
+    for branch in applied_branches(gb_repository, project_repository)? {
+        commits.extend(project_repository.log(branch.head, LogUntil::Commit(default_target.sha))?);
+    }
+
+    let mut nodes = vec![];
+    let mut edges = vec![];
+    let mut seen = HashSet::new();
+    for commit in commits {
+        if !seen.insert(commit.id()) {
+            continue;
+        }
+        for parent in commit.parents()? {
+            edges.push(CommitGraphEdge {
+                child: commit.id(),
+                parent: parent.id(),
+            });
+        }
+        nodes.push(CommitGraphNode {
+            id: commit.id(),
+            author: Author::from(commit.author()),
+            created_at: u128::try_from(commit.time().seconds())
+                .context("failed to convert commit time")?
+                * 1000,
+            description: commit.message().unwrap_or_default().to_string(),
+        });
+    }
+    edges.retain(|edge| seen.contains(&edge.parent));
+
+    Ok(CommitGraph { nodes, edges })
+}
+
+// returns how many commits the branch is ahead and behind its upstream.
+// branches that were never pushed are neither ahead nor behind.
+pub fn ahead_behind(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+) -> Result<(usize, usize), errors::AheadBehindError> {
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+    let branch = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .read(branch_id)
+    .map_err(|error| match error {
+        reader::Error::NotFound => {
+            errors::AheadBehindError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+        }
+        error => errors::AheadBehindError::Other(error.into()),
+    })?;
+
+    let upstream = if let Some(upstream) = &branch.upstream {
+        upstream
+    } else {
+        return Ok((0, 0));
+    };
+
+    let upstream_oid = match project_repository
+        .git_repository
+        .refname_to_id(&upstream.to_string())
+    {
+        Ok(oid) => oid,
+        Err(git::Error::NotFound(_)) => return Ok((0, 0)),
+        Err(error) => {
+            return Err(anyhow::Error::from(error)
+                .context("failed to find upstream reference")
+                .into())
+        }
+    };
+
+    project_repository
+        .git_repository
+        .graph_ahead_behind(branch.head, upstream_oid)
+        .context("failed to count commits ahead and behind upstream")
+        .map_err(Into::into)
+}
+
+// brings a single unapplied branch that fell behind the base branch up to date, by rebasing its
+// commits onto the target commit. pushed branches get a merge commit instead, unless force pushing
+// is fine. nothing is written if the branch conflicts with the base branch.
+pub fn integrate_upstream(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    signing_key: Option<&keys::PrivateKey>,
+    user: Option<&users::User>,
+) -> Result<(), errors::IntegrateUpstreamError> {
+    if project_repository.is_resolving() {
+        return Err(errors::IntegrateUpstreamError::Conflict(
+            errors::ProjectConflict {
+                project_id: project_repository.project().id,
+            },
+        ));
+    }
+
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+
+    let default_target = get_default_target(&current_session_reader, project_repository.project())
+        .context("failed to get default target")?
+        .ok_or_else(|| {
+            errors::IntegrateUpstreamError::DefaultTargetNotSet(errors::DefaultTargetNotSet {
+                project_id: project_repository.project().id,
+            })
+        })?;
+
+    let mut branch = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .read(branch_id)
+    .map_err(|error| match error {
+        reader::Error::NotFound => {
+            errors::IntegrateUpstreamError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+        }
+        error => errors::IntegrateUpstreamError::Other(error.into()),
+    })?;
+
+    let repo = &project_repository.git_repository;
+
+    // applied branches are always kept up to date with the base branch
+    let merge_base = repo
+        .merge_base(default_target.sha, branch.head)
+        .context("failed to find merge base")?;
+    if branch.applied || merge_base == default_target.sha {
+        return Ok(());
+    }
+
+    let target_commit = repo
+        .find_commit(default_target.sha)
+        .context("failed to find target commit")?;
+    let target_tree = target_commit.tree().context("failed to get target tree")?;
+    let merge_base_tree = repo
+        .find_commit(merge_base)
+        .context("failed to find merge base commit")?
+        .tree()
+        .context("failed to find merge base tree")?;
+    let branch_tree = repo
+        .find_tree(branch.tree)
+        .context("failed to find branch tree")?;
+
+    let mut merge_index = repo
+        .merge_trees(&merge_base_tree, &branch_tree, &target_tree)
+        .context("failed to merge trees")?;
+    if merge_index.has_conflicts() {
+        return Err(errors::IntegrateUpstreamError::BranchConflicts(*branch_id));
+    }
+    let merged_branch_tree_oid = merge_index
+        .write_tree_to(repo)
+        .context("failed to write tree")?;
+
+    let head_commit = repo
+        .find_commit(branch.head)
+        .context("failed to find head commit")?;
+
+    let ok_with_force_push = project_repository.project().ok_with_force_push;
+    let new_head = if branch.upstream.is_some() && !ok_with_force_push {
+        let mut head_merge_index = repo
+            .merge_trees(
+                &merge_base_tree,
+                &head_commit.tree().context("failed to find head tree")?,
+                &target_tree,
+            )
+            .context("failed to merge head tree")?;
+        if head_merge_index.has_conflicts() {
+            return Err(errors::IntegrateUpstreamError::BranchConflicts(*branch_id));
+        }
+        let head_merge_tree = repo
+            .find_tree(
+                head_merge_index
+                    .write_tree_to(repo)
+                    .context("failed to write head merge tree")?,
+            )
+            .context("failed to find head merge tree")?;
+
+        project_repository
+            .commit(
+                user,
+                format!(
+                    "Merged {}/{} into {}",
+                    default_target.branch.remote(),
+                    default_target.branch.branch(),
+                    branch.name
+                )
+                .as_str(),
+                &head_merge_tree,
+                &[&head_commit, &target_commit],
+                signing_key,
+            )
+            .context("failed to commit merge")?
+    } else {
+        let (_, committer) = project_repository.git_signatures(user)?;
+        let mut rebase_options = git2::RebaseOptions::new();
+        rebase_options.quiet(true);
+        rebase_options.inmemory(true);
+        let mut rebase = repo
+            .rebase(
+                Some(branch.head),
+                Some(target_commit.id()),
+                None,
+                Some(&mut rebase_options),
+            )
+            .context("failed to rebase")?;
+
+        let mut last_rebase_head = target_commit.id();
+        while rebase.next().is_some() {
+            let index = rebase
+                .inmemory_index()
+                .context("failed to get inmemory index")?;
+            if index.has_conflicts() {
+                rebase.abort().context("failed to abort rebase")?;
+                return Err(errors::IntegrateUpstreamError::BranchConflicts(*branch_id));
+            }
+            last_rebase_head = rebase
+                .commit(None, &committer.clone().into(), None)
+                .context("failed to commit rebase")?
+                .into();
+        }
+        rebase.finish(None).context("failed to finish rebase")?;
+        last_rebase_head
+    };
+
+    branch.head = new_head;
+    branch.tree = merged_branch_tree_oid;
+    branch::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create branch writer")?
+    .write(&mut branch)
+    .context("failed to write branch")?;
 
-    let branch_tree = branch_commit.tree().context("failed to find branch tree")?;
-    let mergeable = !project_repository
-        .git_repository
-        .merge_trees(&base_tree, &branch_tree, &wd_tree)
-        .context("failed to merge trees")?
-        .has_conflicts();
+    super::integration::update_gitbutler_integration(gb_repository, project_repository)?;
 
-    Ok(mergeable)
+    Ok(())
 }
 
 pub fn is_virtual_branch_mergeable(
@@ -3430,6 +5210,39 @@ pub fn squash(
     project_repository: &project_repository::Repository,
     branch_id: &BranchId,
     commit_oid: git::Oid,
+) -> Result<(), errors::SquashError> {
+    squash_into_parent(
+        gb_repository,
+        project_repository,
+        branch_id,
+        commit_oid,
+        false,
+    )
+}
+
+/// like `squash`, but the resulting commit keeps the message and author of the parent, as with
+/// `git commit --fixup`.
+pub fn fixup(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    commit_oid: git::Oid,
+) -> Result<(), errors::SquashError> {
+    squash_into_parent(
+        gb_repository,
+        project_repository,
+        branch_id,
+        commit_oid,
+        true,
+    )
+}
+
+fn squash_into_parent(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    commit_oid: git::Oid,
+    keep_parent_message: bool,
 ) -> Result<(), errors::SquashError> {
     if conflicts::is_conflicting::<&Path>(project_repository, None)? {
         return Err(errors::SquashError::Conflict(errors::ProjectConflict {
@@ -3516,23 +5329,35 @@ pub fn squash(
 
     // create a commit that:
     //  * has the tree of the target commit
-    //  * has the message combined of the target commit and parent commit
+    //  * has the message combined of the target commit and parent commit, or only the parent's
     //  * has parents of the parents commit.
     let parents = parent_commit
         .parents()
         .context("failed to find head commit parents")?;
 
+    let (author, message) = if keep_parent_message {
+        (
+            parent_commit.author(),
+            parent_commit.message().unwrap_or_default().to_string(),
+        )
+    } else {
+        (
+            commit_to_squash.author(),
+            format!(
+                "{}\n{}",
+                parent_commit.message().unwrap_or_default(),
+                commit_to_squash.message().unwrap_or_default(),
+            ),
+        )
+    };
+
     let new_commit_oid = project_repository
         .git_repository
         .commit(
             None,
-            &commit_to_squash.author(),
+            &author,
             &commit_to_squash.committer(),
-            &format!(
-                "{}\n{}",
-                parent_commit.message().unwrap_or_default(),
-                commit_to_squash.message().unwrap_or_default(),
-            ),
+            &message,
             &commit_to_squash.tree().context("failed to find tree")?,
             &parents.iter().collect::<Vec<_>>(),
         )
@@ -3616,13 +5441,381 @@ pub fn squash(
     Ok(())
 }
 
+// replays the commits of the branch that `onto` doesn't have on top of it, like `git rebase --onto`
+// does. `onto` has to be based on the base commit. if any commit, the uncommitted changes or the
+// working directory conflict, nothing is changed. rebasing an applied branch brings the changes of
+// `onto` into the working directory.
+pub fn rebase_branch(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+    onto: git::Oid,
+) -> Result<(), errors::RebaseBranchError> {
+    if conflicts::is_conflicting::<&Path>(project_repository, None)? {
+        return Err(errors::RebaseBranchError::Conflict(
+            errors::ProjectConflict {
+                project_id: project_repository.project().id,
+            },
+        ));
+    }
+
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+
+    let default_target = get_default_target(&current_session_reader, project_repository.project())
+        .context("failed to read default target")?
+        .ok_or_else(|| {
+            errors::RebaseBranchError::DefaultTargetNotSet(errors::DefaultTargetNotSet {
+                project_id: project_repository.project().id,
+            })
+        })?;
+
+    let mut branch = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .read(branch_id)
+    .map_err(|error| match error {
+        reader::Error::NotFound => {
+            errors::RebaseBranchError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+        }
+        error => errors::RebaseBranchError::Other(error.into()),
+    })?;
+
+    let repo = &project_repository.git_repository;
+    let onto_commit = repo.find_commit(onto).map_err(|error| match error {
+        git::Error::NotFound(_) => errors::RebaseBranchError::CommitNotFound(onto),
+        error => errors::RebaseBranchError::Other(
+            anyhow::Error::from(error).context(format!("failed to find commit {}", onto)),
+        ),
+    })?;
+    let onto_base = repo
+        .merge_base(onto, default_target.sha)
+        .context("failed to find merge base of onto and base commit")?;
+    if onto_base != default_target.sha {
+        return Err(errors::RebaseBranchError::NotOnBase(onto));
+    }
+
+    let fork_point = repo
+        .merge_base(branch.head, onto)
+        .context("failed to find merge base of branch and onto")?;
+    let mut commit_oids = project_repository.l(branch.head, LogUntil::Commit(fork_point))?;
+
+    let pushed_commit_oids = branch.upstream_head.map_or_else(
+        || Ok(vec![]),
+        |upstream_head| project_repository.l(upstream_head, LogUntil::Commit(default_target.sha)),
+    )?;
+    if !project_repository.project().ok_with_force_push
+        && commit_oids
+            .iter()
+            .any(|oid| pushed_commit_oids.contains(oid))
+    {
+        // rewriting pushed commits will cause a force push that is not allowed
+        return Err(errors::RebaseBranchError::ForcePushNotAllowed(
+            errors::ForcePushNotAllowed {
+                project_id: project_repository.project().id,
+            },
+        ));
+    }
+
+    // the commits are written right away, but they are not referenced until the branch is
+    commit_oids.reverse();
+    let new_head = cherry_pick_onto(repo, onto_commit, &commit_oids)?
+        .ok_or(errors::RebaseBranchError::BranchConflicts(*branch_id))?;
+
+    // the uncommitted changes move along with the commits
+    let old_head_tree = repo
+        .find_commit(branch.head)
+        .and_then(|commit| commit.tree())
+        .context("failed to find branch head tree")?;
+    let new_head_tree = new_head.tree().context("failed to find new head tree")?;
+    let branch_tree = repo
+        .find_tree(branch.tree)
+        .context("failed to find branch tree")?;
+    let mut branch_tree_index = repo
+        .merge_trees(&old_head_tree, &branch_tree, &new_head_tree)
+        .context("failed to merge branch tree")?;
+    if branch_tree_index.has_conflicts() {
+        return Err(errors::RebaseBranchError::BranchConflicts(*branch_id));
+    }
+    let new_branch_tree_oid = branch_tree_index
+        .write_tree_to(repo)
+        .context("failed to write branch tree")?;
+
+    let wd_index = if branch.applied {
+        let wd_tree = project_repository.get_wd_tree()?;
+        let new_branch_tree = repo
+            .find_tree(new_branch_tree_oid)
+            .context("failed to find new branch tree")?;
+        let wd_index = repo
+            .merge_trees(&branch_tree, &wd_tree, &new_branch_tree)
+            .context("failed to merge working directory")?;
+        if wd_index.has_conflicts() {
+            return Err(errors::RebaseBranchError::BranchConflicts(*branch_id));
+        }
+        Some(wd_index)
+    } else {
+        None
+    };
+
+    branch.head = new_head.id();
+    branch.tree = new_branch_tree_oid;
+    branch::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create writer")?
+    .write(&mut branch)
+    .context("failed to write branch")?;
+
+    if let Some(mut wd_index) = wd_index {
+        repo.checkout_index(&mut wd_index)
+            .force()
+            .checkout()
+            .context("failed to checkout index")?;
+    }
+
+    super::integration::update_gitbutler_integration(gb_repository, project_repository)?;
+
+    Ok(())
+}
+
+// replays the commits, oldest first, on top of `onto`. the new commits are not referenced by
+// anything. returns the new head, or `None` if any of the commits conflicts.
+fn cherry_pick_onto<'repo>(
+    repo: &'repo git::Repository,
+    onto: git::Commit<'repo>,
+    commit_oids: &[git::Oid],
+) -> Result<Option<git::Commit<'repo>>> {
+    let mut new_head = onto;
+    for commit_oid in commit_oids {
+        let to_rebase = repo
+            .find_commit(*commit_oid)
+            .context("failed to find commit to rebase")?;
+        let mut cherrypick_index = repo
+            .cherry_pick(&new_head, &to_rebase)
+            .context("failed to cherry pick")?;
+        if cherrypick_index.has_conflicts() {
+            return Ok(None);
+        }
+        let merge_tree = cherrypick_index
+            .write_tree_to(repo)
+            .and_then(|tree_oid| repo.find_tree(tree_oid))
+            .context("failed to write merge tree")?;
+        let rebased_oid = repo
+            .commit(
+                None,
+                &to_rebase.author(),
+                &to_rebase.committer(),
+                to_rebase.message().unwrap_or_default(),
+                &merge_tree,
+                &[&new_head],
+            )
+            .context("failed to create commit")?;
+        new_head = repo
+            .find_commit(rebased_oid)
+            .context("failed to find rebased commit")?;
+    }
+    Ok(Some(new_head))
+}
+
+// fast-forwards the base to the head of a branch, as if its commits were integrated upstream, and
+// rebases the other applied branches onto it. the landed branch is deleted. nothing is changed if
+// any of the branches would conflict.
+pub fn land_branch(
+    gb_repository: &gb_repository::Repository,
+    project_repository: &project_repository::Repository,
+    branch_id: &BranchId,
+) -> Result<(), errors::LandBranchError> {
+    if conflicts::is_conflicting::<&Path>(project_repository, None)? {
+        return Err(errors::LandBranchError::Conflict(errors::ProjectConflict {
+            project_id: project_repository.project().id,
+        }));
+    }
+
+    let current_session = gb_repository
+        .get_or_create_current_session()
+        .context("failed to get or create current session")?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)
+        .context("failed to open current session")?;
+
+    let default_target = get_default_target(&current_session_reader, project_repository.project())
+        .context("failed to read default target")?
+        .ok_or_else(|| {
+            errors::LandBranchError::DefaultTargetNotSet(errors::DefaultTargetNotSet {
+                project_id: project_repository.project().id,
+            })
+        })?;
+
+    let branch = branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    )
+    .read(branch_id)
+    .map_err(|error| match error {
+        reader::Error::NotFound => {
+            errors::LandBranchError::BranchNotFound(errors::BranchNotFound {
+                project_id: project_repository.project().id,
+                branch_id: *branch_id,
+            })
+        }
+        error => errors::LandBranchError::Other(error.into()),
+    })?;
+
+    let repo = &project_repository.git_repository;
+    let fork_point = repo
+        .merge_base(branch.head, default_target.sha)
+        .context("failed to find merge base of branch and base commit")?;
+    if fork_point != default_target.sha {
+        return Err(errors::LandBranchError::NotOnBase(*branch_id));
+    }
+
+    let new_base = repo
+        .find_commit(branch.head)
+        .context("failed to find branch head")?;
+    let new_base_tree = new_base.tree().context("failed to find branch head tree")?;
+    if new_base_tree.id() != branch.tree {
+        return Err(errors::LandBranchError::UncommittedChanges(*branch_id));
+    }
+    let old_base_tree = repo
+        .find_commit(default_target.sha)
+        .and_then(|commit| commit.tree())
+        .context("failed to find base tree")?;
+
+    // everything is rebased up front, so that nothing is written if any branch conflicts
+    let mut rebased_branches = vec![];
+    for other_branch in applied_branches(gb_repository, project_repository)?
+        .into_iter()
+        .filter(|other_branch| other_branch.id != *branch_id)
+    {
+        let mut commit_oids =
+            project_repository.l(other_branch.head, LogUntil::Commit(default_target.sha))?;
+
+        let pushed_commit_oids = other_branch.upstream_head.map_or_else(
+            || Ok(vec![]),
+            |upstream_head| {
+                project_repository.l(upstream_head, LogUntil::Commit(default_target.sha))
+            },
+        )?;
+        if !project_repository.project().ok_with_force_push
+            && commit_oids
+                .iter()
+                .any(|oid| pushed_commit_oids.contains(oid))
+        {
+            // rewriting pushed commits will cause a force push that is not allowed
+            return Err(errors::LandBranchError::ForcePushNotAllowed(
+                errors::ForcePushNotAllowed {
+                    project_id: project_repository.project().id,
+                },
+            ));
+        }
+
+        commit_oids.reverse();
+        let onto = repo
+            .find_commit(new_base.id())
+            .context("failed to find branch head")?;
+        let new_head = cherry_pick_onto(repo, onto, &commit_oids)?
+            .ok_or(errors::LandBranchError::BranchConflicts(other_branch.id))?;
+
+        let old_head_tree = repo
+            .find_commit(other_branch.head)
+            .and_then(|commit| commit.tree())
+            .context("failed to find branch head tree")?;
+        let new_head_tree = new_head.tree().context("failed to find new head tree")?;
+        let other_branch_tree = repo
+            .find_tree(other_branch.tree)
+            .context("failed to find branch tree")?;
+        let mut branch_tree_index = repo
+            .merge_trees(&old_head_tree, &other_branch_tree, &new_head_tree)
+            .context("failed to merge branch tree")?;
+        if branch_tree_index.has_conflicts() {
+            return Err(errors::LandBranchError::BranchConflicts(other_branch.id));
+        }
+        let new_branch_tree_oid = branch_tree_index
+            .write_tree_to(repo)
+            .context("failed to write branch tree")?;
+
+        rebased_branches.push((other_branch, new_head.id(), new_branch_tree_oid));
+    }
+
+    // the changes of an applied branch are in the working directory already
+    let wd_index = if branch.applied {
+        None
+    } else {
+        let wd_tree = project_repository.get_wd_tree()?;
+        let wd_index = repo
+            .merge_trees(&old_base_tree, &wd_tree, &new_base_tree)
+            .context("failed to merge working directory")?;
+        if wd_index.has_conflicts() {
+            return Err(errors::LandBranchError::BranchConflicts(*branch_id));
+        }
+        Some(wd_index)
+    };
+
+    target::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create target writer")?
+    .write_default(&target::Target {
+        sha: new_base.id(),
+        ..default_target
+    })
+    .context("failed to write default target")?;
+
+    let branch_writer = branch::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+    )
+    .context("failed to create writer")?;
+    for (mut other_branch, new_head, new_tree) in rebased_branches {
+        other_branch.head = new_head;
+        other_branch.tree = new_tree;
+        branch_writer
+            .write(&mut other_branch)
+            .context("failed to write branch")?;
+    }
+
+    branch_writer
+        .delete(&branch)
+        .context("failed to delete branch")?;
+    project_repository.delete_branch_reference(&branch)?;
+    ensure_selected_for_changes(
+        &current_session_reader,
+        &branch_writer,
+        project_repository.project(),
+    )
+    .context("failed to ensure selected for changes")?;
+
+    if let Some(mut wd_index) = wd_index {
+        repo.checkout_index(&mut wd_index)
+            .force()
+            .checkout()
+            .context("failed to checkout index")?;
+    }
+
+    super::integration::update_gitbutler_integration(gb_repository, project_repository)?;
+
+    Ok(())
+}
+
+/// rewrites the message of a commit, rebasing the commits on top of it. returns the oid of the
+/// rewritten commit
 pub fn update_commit_message(
     gb_repository: &gb_repository::Repository,
     project_repository: &project_repository::Repository,
     branch_id: &BranchId,
     commit_oid: git::Oid,
     message: &str,
-) -> Result<(), errors::UpdateCommitMessageError> {
+) -> Result<git::Oid, errors::UpdateCommitMessageError> {
     if message.is_empty() {
         return Err(errors::UpdateCommitMessageError::EmptyMessage);
     }
@@ -3795,7 +5988,7 @@ pub fn update_commit_message(
 
     super::integration::update_gitbutler_integration(gb_repository, project_repository)?;
 
-    Ok(())
+    Ok(new_commit_oid)
 }
 
 /// moves commit on top of the to target branch
@@ -4000,10 +6193,13 @@ pub fn move_commit(
     Ok(())
 }
 
+// creates a virtual branch from a local or remote branch. unless `apply` is set, the branch is
+// only imported and left unapplied, e.g. to import several branches and pick which to apply.
 pub fn create_virtual_branch_from_branch(
     gb_repository: &gb_repository::Repository,
     project_repository: &project_repository::Repository,
     upstream: &git::Refname,
+    apply: bool,
     signing_key: Option<&keys::PrivateKey>,
     user: Option<&users::User>,
 ) -> Result<BranchId, errors::CreateVirtualBranchFromBranchError> {
@@ -4140,9 +6336,12 @@ pub fn create_virtual_branch_from_branch(
         head: head_commit.id(),
         created_timestamp_ms: now,
         updated_timestamp_ms: now,
+        last_activity_ms: now,
         ownership,
         order,
         selected_for_changes,
+        archived: false,
+        pushed_name: None,
     };
 
     let writer = branch::Writer::new(
@@ -4156,6 +6355,10 @@ pub fn create_virtual_branch_from_branch(
 
     project_repository.add_branch_reference(&branch)?;
 
+    if !apply {
+        return Ok(branch.id);
+    }
+
     match apply_branch(
         gb_repository,
         project_repository,
@@ -4203,6 +6406,32 @@ mod tests {
         assert!(!joined(10, 13, 14, 17));
     }
 
+    #[test]
+    fn with_trailers_test() {
+        let signed_off = ("Signed-off-by".to_string(), "A <a@example.com>".to_string());
+        let co_authored = (
+            "Co-authored-by".to_string(),
+            "B <b@example.com>".to_string(),
+        );
+
+        assert_eq!(with_trailers("subject\n", &[]), "subject\n");
+        assert_eq!(
+            with_trailers("subject\n\nbody\n", &[signed_off.clone()]),
+            "subject\n\nbody\n\nSigned-off-by: A <a@example.com>"
+        );
+        assert_eq!(
+            with_trailers("Fix: subject", &[signed_off.clone()]),
+            "Fix: subject\n\nSigned-off-by: A <a@example.com>"
+        );
+        assert_eq!(
+            with_trailers(
+                "subject\n\nsigned-off-by: A <a@example.com>",
+                &[signed_off.clone(), co_authored.clone(), co_authored]
+            ),
+            "subject\n\nsigned-off-by: A <a@example.com>\nCo-authored-by: B <b@example.com>"
+        );
+    }
+
     #[test]
     fn normalize_branch_name_test() {
         assert_eq!(normalize_branch_name("feature/branch"), "feature/branch");
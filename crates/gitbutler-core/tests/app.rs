@@ -13,6 +13,7 @@ mod keys;
 mod lock;
 mod reader;
 mod sessions;
+mod tokens;
 mod types;
 pub mod virtual_branches;
 mod zip;
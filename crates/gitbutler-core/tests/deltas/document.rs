@@ -226,7 +226,7 @@ fn multiline_remove() {
 
 #[test]
 fn binary_to_text() {
-    let latest = reader::Content::Binary;
+    let latest = reader::Content::from(&[0xff, 0x00][..]);
     let current = reader::Content::UTF8("test".to_string());
     let mut document = Document::new(Some(&latest), vec![]).unwrap();
     let new_deltas = document.update(Some(&current)).unwrap();
@@ -236,8 +236,8 @@ fn binary_to_text() {
 
 #[test]
 fn binary_to_binary() {
-    let latest = reader::Content::Binary;
-    let current = reader::Content::Binary;
+    let latest = reader::Content::from(&[0xff, 0x00][..]);
+    let current = reader::Content::from(&[0xff, 0x00][..]);
     let mut document = Document::new(Some(&latest), vec![]).unwrap();
     let new_deltas = document.update(Some(&current)).unwrap();
     assert!(new_deltas.is_some());
@@ -247,7 +247,7 @@ fn binary_to_binary() {
 #[test]
 fn text_to_binary() {
     let latest = reader::Content::UTF8("text".to_string());
-    let current = reader::Content::Binary;
+    let current = reader::Content::from(&[0xff, 0x00][..]);
     let mut document = Document::new(Some(&latest), vec![]).unwrap();
     let new_deltas = document.update(Some(&current)).unwrap();
     assert!(new_deltas.is_some());
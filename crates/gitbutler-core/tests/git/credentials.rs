@@ -2,10 +2,10 @@ use std::path::PathBuf;
 
 use gitbutler_core::{
     git::credentials::{Credential, Helper, HttpsCredential, SshCredential},
-    keys, project_repository, projects, users,
+    keys, project_repository, projects, tokens, users,
 };
 
-use gitbutler_testsupport::{temp_dir, test_repository};
+use gitbutler_testsupport::{secrets, temp_dir, test_repository};
 
 #[derive(Default)]
 struct TestCase<'a> {
@@ -13,6 +13,8 @@ struct TestCase<'a> {
     github_access_token: Option<&'a str>,
     preferred_key: projects::AuthKey,
     home_dir: Option<PathBuf>,
+    use_ssh_agent: bool,
+    token: Option<(&'a str, tokens::Token)>,
 }
 
 impl TestCase<'_> {
@@ -27,7 +29,12 @@ impl TestCase<'_> {
         users.set_user(&user).unwrap();
 
         let keys = keys::Controller::from_path(&local_app_data);
-        let helper = Helper::new(keys, users, self.home_dir.clone());
+        let tokens = tokens::Controller::from_path(&local_app_data);
+        if let Some((host, token)) = &self.token {
+            secrets::setup_in_memory();
+            tokens.set_token(host, token).unwrap();
+        }
+        let helper = Helper::new(keys, users, tokens, self.home_dir.clone());
 
         let (repo, _tmp) = test_repository();
         repo.remote(
@@ -38,6 +45,7 @@ impl TestCase<'_> {
         let project = projects::Project {
             path: repo.workdir().unwrap().to_path_buf(),
             preferred_key: self.preferred_key.clone(),
+            use_ssh_agent: Some(self.use_ssh_agent),
             ..Default::default()
         };
         let project_repository = project_repository::Repository::open(&project).unwrap();
@@ -67,6 +75,7 @@ mod not_github {
                 github_access_token: Some("token"),
                 preferred_key: projects::AuthKey::Local {
                     private_key_path: PathBuf::from("/tmp/id_rsa"),
+                    passphrase: None,
                 },
                 ..Default::default()
             };
@@ -92,6 +101,7 @@ mod not_github {
                 github_access_token: Some("token"),
                 preferred_key: projects::AuthKey::Local {
                     private_key_path: PathBuf::from("/tmp/id_rsa"),
+                    passphrase: None,
                 },
                 ..Default::default()
             };
@@ -268,6 +278,7 @@ mod github {
                     github_access_token: Some("token"),
                     preferred_key: projects::AuthKey::Local {
                         private_key_path: PathBuf::from("/tmp/id_rsa"),
+                        passphrase: None,
                     },
                     ..Default::default()
                 };
@@ -293,6 +304,7 @@ mod github {
                     github_access_token: Some("token"),
                     preferred_key: projects::AuthKey::Local {
                         private_key_path: PathBuf::from("/tmp/id_rsa"),
+                        passphrase: None,
                     },
                     ..Default::default()
                 };
@@ -313,3 +325,210 @@ mod github {
         }
     }
 }
+
+mod passphrase_protected_key {
+    use super::*;
+    use gitbutler_core::{
+        git::credentials::is_wrong_passphrase, project_repository::RemoteError, secret,
+    };
+
+    fn key_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/keys/id_ed25519_encrypted")
+    }
+
+    fn fetch_with_passphrase(handle: &str, passphrase: Option<&str>) -> Result<(), RemoteError> {
+        secrets::setup_in_memory();
+        match passphrase {
+            Some(passphrase) => secret::persist(handle, passphrase).unwrap(),
+            None => secret::delete(handle).unwrap(),
+        }
+
+        let (repo, _tmp) = test_repository();
+        // nothing listens on port 1, the key has to be loaded before connecting to fail otherwise
+        repo.remote(
+            "origin",
+            &"ssh://git@127.0.0.1:1/test-gitbutler/test.git"
+                .parse()
+                .unwrap(),
+        )
+        .unwrap();
+        let project = projects::Project {
+            path: repo.workdir().unwrap().to_path_buf(),
+            preferred_key: projects::AuthKey::Local {
+                private_key_path: key_path(),
+                passphrase: Some(handle.to_string()),
+            },
+            ..Default::default()
+        };
+        let project_repository = project_repository::Repository::open(&project).unwrap();
+        let helper = Helper::from_path(temp_dir());
+
+        project_repository.fetch("origin", &helper, None)
+    }
+
+    #[test]
+    fn passphrase_is_passed_along() {
+        secrets::setup_in_memory();
+        let handle = "passphrase-is-passed-along";
+        secret::persist(handle, "passphrase").unwrap();
+
+        let key_path = key_path();
+        let test_case = TestCase {
+            remote_url: "git@gitlab.com:test-gitbutler/test.git",
+            preferred_key: projects::AuthKey::Local {
+                private_key_path: key_path.clone(),
+                passphrase: Some(handle.to_string()),
+            },
+            ..Default::default()
+        };
+        let flow = test_case.run();
+        assert_eq!(flow.len(), 1);
+        assert_eq!(
+            flow[0].1,
+            vec![Credential::Ssh(SshCredential::Keyfile {
+                key_path,
+                passphrase: Some("passphrase".to_string()),
+            })]
+        );
+    }
+
+    #[test]
+    fn wrong_passphrase_is_told_apart_from_rejection() {
+        let wrong_passphrase = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Ssh,
+            "Unable to extract public key from private key file: Wrong passphrase or invalid/unrecognized private key file format",
+        );
+        assert!(is_wrong_passphrase(&wrong_passphrase));
+
+        let rejected = git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Ssh,
+            "Failed to authenticate SSH session: Unable to send userauth-publickey request",
+        );
+        assert!(!is_wrong_passphrase(&rejected));
+    }
+
+    #[test]
+    fn other_ssh_errors_are_not_a_wrong_passphrase() {
+        let handshake = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Ssh,
+            "Failed to start SSH session: Unable to exchange encryption keys",
+        );
+        assert!(!is_wrong_passphrase(&handshake));
+
+        let host_key = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Ssh,
+            "remote host key is not available",
+        );
+        assert!(!is_wrong_passphrase(&host_key));
+    }
+
+    #[test]
+    fn fetch_with_wrong_passphrase() {
+        assert!(matches!(
+            fetch_with_passphrase("fetch-with-wrong-passphrase", Some("wrong")),
+            Err(RemoteError::WrongPassphrase)
+        ));
+    }
+
+    #[test]
+    fn fetch_without_passphrase() {
+        assert!(matches!(
+            fetch_with_passphrase("fetch-without-passphrase", None),
+            Err(RemoteError::WrongPassphrase)
+        ));
+    }
+
+    #[test]
+    fn fetch_with_passphrase_gets_past_the_key() {
+        assert!(!matches!(
+            fetch_with_passphrase("fetch-with-passphrase", Some("passphrase")),
+            Err(RemoteError::WrongPassphrase)
+        ));
+    }
+}
+
+mod ssh_agent {
+    use super::*;
+
+    #[test]
+    fn agent_is_tried_before_generated_key() {
+        let test_case = TestCase {
+            remote_url: "git@gitlab.com:test-gitbutler/test.git",
+            preferred_key: projects::AuthKey::Generated,
+            use_ssh_agent: true,
+            ..Default::default()
+        };
+        let flow = test_case.run();
+        assert_eq!(flow[0].1.len(), 2);
+        assert_eq!(flow[0].1[0], Credential::Ssh(SshCredential::Agent));
+        assert!(matches!(
+            flow[0].1[1],
+            Credential::Ssh(SshCredential::GitButlerKey(_))
+        ));
+    }
+
+    #[test]
+    fn agent_is_not_used_unless_enabled() {
+        let test_case = TestCase {
+            remote_url: "git@gitlab.com:test-gitbutler/test.git",
+            ..Default::default()
+        };
+        let flow = test_case.run();
+        assert!(flow
+            .iter()
+            .flat_map(|(_, credentials)| credentials)
+            .all(|credential| *credential != Credential::Ssh(SshCredential::Agent)));
+    }
+}
+
+mod personal_access_token {
+    use super::*;
+
+    #[test]
+    fn https() {
+        let token = tokens::Token {
+            username: "user".to_string(),
+            token: "secret".to_string(),
+        };
+        let test_case = TestCase {
+            remote_url: "https://gitlab.com/test-gitbutler/test.git",
+            token: Some(("gitlab.com", token)),
+            ..Default::default()
+        };
+        let flow = test_case.run();
+        assert_eq!(
+            flow[0].0,
+            "https://gitlab.com/test-gitbutler/test.git".to_string(),
+        );
+        assert_eq!(
+            flow[0].1,
+            vec![Credential::Https(HttpsCredential::PersonalAccessToken {
+                username: "user".to_string(),
+                token: "secret".to_string(),
+            })]
+        );
+    }
+
+    #[test]
+    fn ignored_for_other_hosts() {
+        let token = tokens::Token {
+            username: "user".to_string(),
+            token: "secret".to_string(),
+        };
+        let test_case = TestCase {
+            remote_url: "https://gitlab.com/test-gitbutler/test.git",
+            token: Some(("bitbucket.org", token)),
+            ..Default::default()
+        };
+        let flow = test_case.run();
+        assert_eq!(flow.len(), 1);
+        assert_eq!(
+            flow[0].0,
+            "git@gitlab.com:test-gitbutler/test.git".to_string(),
+        );
+    }
+}
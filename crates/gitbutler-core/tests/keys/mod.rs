@@ -7,7 +7,7 @@ mod controller {
         #[cfg(target_family = "unix")]
         use std::os::unix::prelude::*;
 
-        use gitbutler_core::keys::{storage::Storage, Controller};
+        use gitbutler_core::keys::{storage::Storage, Controller, KeyType};
 
         use gitbutler_testsupport::Suite;
 
@@ -27,6 +27,19 @@ mod controller {
             let perms = format!("{:o}", permissions.mode());
             assert_eq!(perms, "100600");
         }
+
+        #[test]
+        fn get_or_create_with_ed25519() {
+            let suite = Suite::default();
+            let storage = Storage::from_path(suite.local_app_data());
+
+            let created = storage.get_or_create_with(KeyType::Ed25519).unwrap();
+            assert_eq!(created.key_type(), KeyType::Ed25519);
+
+            let stored = storage.get_with(KeyType::Ed25519).unwrap();
+            assert_eq!(stored, Some(created));
+            assert!(suite.local_app_data().join("keys/ed25519.pub").exists());
+        }
     }
 }
 
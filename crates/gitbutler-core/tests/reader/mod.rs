@@ -1,7 +1,14 @@
-use std::{fs, path::Path};
+use std::{
+    fs, io,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
-use gitbutler_core::reader::{CommitReader, Content, Reader};
+use gitbutler_core::{
+    git,
+    reader::{self, CommitReader, Content, FromError, GitConfigReader, Reader, ResolvedSource},
+};
 
 use gitbutler_testsupport::{commit_all, temp_dir, test_repository};
 
@@ -18,6 +25,58 @@ fn directory_reader_read_file() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn directory_reader_read_big_file() -> Result<()> {
+    let dir = temp_dir();
+
+    // several times the size of a typical read buffer, but below the size limit
+    let text = "line of text\n".repeat(3 * 1024 * 1024 / 13);
+    fs::write(dir.path().join("big.txt"), &text)?;
+    let mut bytes = vec![0_u8; 3 * 1024 * 1024];
+    bytes[0] = 0xff;
+    fs::write(dir.path().join("big.bin"), &bytes)?;
+
+    let reader = Reader::open(dir.path())?;
+    assert_eq!(reader.read("big.txt")?, Content::UTF8(text));
+    assert_eq!(reader.read("big.bin")?, Content::from(bytes.as_slice()));
+
+    Ok(())
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn directory_reader_read_unreadable_file() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = temp_dir();
+
+    let path = dir.path().join("test.txt");
+    fs::write(&path, "test")?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o000))?;
+    if fs::read(&path).is_ok() {
+        // permissions don't apply, e.g. when running as root
+        return Ok(());
+    }
+
+    let reader = Reader::open(dir.path())?;
+    match reader.read("test.txt") {
+        Err(reader::Error::IoAt {
+            path: error_path,
+            source,
+        }) => {
+            assert!(error_path.ends_with("test.txt"));
+            assert_eq!(source.kind(), io::ErrorKind::PermissionDenied);
+        }
+        result => panic!("expected an error with the path, got {:?}", result),
+    }
+    assert!(matches!(
+        reader.read("missing.txt"),
+        Err(reader::Error::NotFound)
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn commit_reader_read_file() -> Result<()> {
     let (repository, _tmp) = test_repository();
@@ -35,6 +94,40 @@ fn commit_reader_read_file() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn commit_reader_bare_repository() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::create_dir_all(workdir.join("dir"))?;
+    fs::write(workdir.join("dir").join("test.txt"), "test")?;
+    let oid = commit_all(&repository);
+
+    let bare_dir = temp_dir();
+    git2::build::RepoBuilder::new()
+        .bare(true)
+        .clone(workdir.to_str().unwrap(), bare_dir.path())?;
+    let bare = git::Repository::open(bare_dir.path())?;
+    assert!(bare.workdir().is_none());
+
+    let reader = Reader::from_commit(&bare, &bare.find_commit(oid)?)?;
+    assert_eq!(
+        reader.read("dir/test.txt")?,
+        Content::UTF8("test".to_string())
+    );
+    assert!(matches!(
+        reader.read("missing.txt"),
+        Err(reader::Error::NotFound)
+    ));
+    assert!(reader.exists("dir/test.txt")?);
+    assert!(!reader.exists("missing.txt")?);
+    assert!(reader
+        .list_files("dir")?
+        .contains(&PathBuf::from("test.txt")));
+
+    Ok(())
+}
+
 #[test]
 fn reader_list_files_should_return_relative() -> Result<()> {
     let dir = temp_dir();
@@ -162,12 +255,77 @@ fn commit_reader_exists() -> Result<()> {
 fn from_bytes() {
     for (bytes, expected) in [
         ("test".as_bytes(), Content::UTF8("test".to_string())),
-        (&[0, 159, 146, 150, 159, 146, 150], Content::Binary),
+        (
+            &[0, 159, 146, 150, 159, 146, 150],
+            Content::Binary {
+                hash: "aaa1f5c4989939d4bed6b10bd592dc7727d04bf8".to_string(),
+            },
+        ),
     ] {
         assert_eq!(Content::from(bytes), expected);
     }
 }
 
+#[test]
+fn from_bytes_lossy() {
+    let bytes = b"hello \xff world";
+    assert!(matches!(Content::from(&bytes[..]), Content::Binary { .. }));
+
+    let content = Content::from_bytes_lossy(bytes);
+    assert_eq!(
+        content,
+        Content::LossyUTF8("hello \u{fffd} world".to_string())
+    );
+    assert_eq!(content.as_str(), Some("hello \u{fffd} world"));
+    assert!(String::try_from(&content).is_err());
+    assert_eq!(
+        serde_json::to_string(&content).unwrap(),
+        "{\"type\":\"utf8\",\"value\":\"hello \u{fffd} world\",\"lossy\":true}"
+    );
+
+    assert_eq!(
+        Content::from_bytes_lossy(b"test"),
+        Content::UTF8("test".to_string())
+    );
+}
+
+#[test]
+fn from_reader() -> Result<()> {
+    assert_eq!(
+        Content::from_reader(io::Cursor::new("test"))?,
+        Content::UTF8("test".to_string())
+    );
+    assert_eq!(
+        Content::from_reader(io::Cursor::new([0xff, 0x00]))?,
+        binary()
+    );
+    Ok(())
+}
+
+fn binary() -> Content {
+    Content::from(&[0xff, 0x00][..])
+}
+
+#[test]
+fn binary_hash_matches_blob_id() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::write(workdir.join("image.bin"), [0xff, 0x00, 0x01])?;
+    let oid = commit_all(&repository);
+
+    let commit_reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    let dir_reader = Reader::open(workdir)?;
+    let committed = commit_reader.read("image.bin")?;
+    assert!(matches!(committed, Content::Binary { .. }));
+    assert_eq!(committed, dir_reader.read("image.bin")?);
+
+    fs::write(workdir.join("image.bin"), [0xff, 0x00, 0x02])?;
+    assert_ne!(committed, dir_reader.read("image.bin")?);
+
+    Ok(())
+}
+
 #[test]
 fn serialize_content() {
     for (content, expected) in [
@@ -175,9 +333,885 @@ fn serialize_content() {
             Content::UTF8("test".to_string()),
             r#"{"type":"utf8","value":"test"}"#,
         ),
-        (Content::Binary, r#"{"type":"binary"}"#),
+        (
+            Content::Binary {
+                hash: "aaa1f5c4989939d4bed6b10bd592dc7727d04bf8".to_string(),
+            },
+            r#"{"type":"binary","hash":"aaa1f5c4989939d4bed6b10bd592dc7727d04bf8"}"#,
+        ),
         (Content::Large, r#"{"type":"large"}"#),
     ] {
         assert_eq!(serde_json::to_string(&content).unwrap(), expected);
     }
 }
+
+#[test]
+fn normalized_readers_compare_equal() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    let file_path = Path::new("test.txt");
+    fs::write(workdir.join(file_path), "line1\r\nline2\r\n")?;
+    let oid = commit_all(&repository);
+    fs::write(workdir.join(file_path), "line1\nline2\n")?;
+
+    let commit_reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    let dir_reader = Reader::open(workdir)?;
+
+    let committed = commit_reader.read(file_path)?;
+    let checked_out = dir_reader.read(file_path)?;
+    assert_ne!(committed, checked_out);
+    assert_eq!(committed.normalized(), checked_out.normalized());
+
+    Ok(())
+}
+
+#[test]
+fn normalized_keeps_binary() {
+    assert_eq!(binary().normalized(), binary());
+    assert_eq!(Content::Large.normalized(), Content::Large);
+}
+
+#[test]
+fn normalized_for_honors_gitattributes() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::write(workdir.join(".gitattributes"), "*.txt text=auto\n")?;
+
+    let content = Content::UTF8("line1\r\nline2\r\n".to_string());
+    assert_eq!(
+        content
+            .clone()
+            .normalized_for(&repository, Path::new("test.txt"))?,
+        Content::UTF8("line1\nline2\n".to_string())
+    );
+    assert_eq!(
+        content
+            .clone()
+            .normalized_for(&repository, Path::new("test.bin"))?,
+        content
+    );
+
+    Ok(())
+}
+
+#[test]
+fn directory_reader_read_prefix() -> Result<()> {
+    let dir = temp_dir();
+
+    let file_path = Path::new("test.txt");
+    fs::write(dir.path().join(file_path), "test content")?;
+
+    let reader = Reader::open(dir.path())?;
+    assert_eq!(
+        reader.read_prefix(file_path, 4)?,
+        Content::UTF8("test".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn commit_reader_read_prefix() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+
+    let file_path = Path::new("test.txt");
+    fs::write(
+        repository.path().parent().unwrap().join(file_path),
+        "test content",
+    )?;
+
+    let oid = commit_all(&repository);
+
+    let reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    assert_eq!(
+        reader.read_prefix(file_path, 4)?,
+        Content::UTF8("test".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn read_prefix_does_not_split_characters() -> Result<()> {
+    let dir = temp_dir();
+
+    let file_path = Path::new("test.txt");
+    fs::write(dir.path().join(file_path), "tesü")?;
+
+    let reader = Reader::open(dir.path())?;
+    assert_eq!(
+        reader.read_prefix(file_path, 4)?,
+        Content::UTF8("tes".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn line_count() {
+    for (content, expected) in [
+        (Content::UTF8(String::new()), Some(0)),
+        (Content::UTF8("one".to_string()), Some(1)),
+        (Content::UTF8("one\n".to_string()), Some(1)),
+        (Content::UTF8("one\ntwo".to_string()), Some(2)),
+        (Content::UTF8("one\ntwo\nthree\n".to_string()), Some(3)),
+        (Content::UTF8("\n\n".to_string()), Some(2)),
+        (binary(), None),
+        (Content::Large, None),
+    ] {
+        assert_eq!(content.line_count(), expected);
+    }
+}
+
+#[test]
+fn is_empty_and_byte_len() {
+    for (content, is_empty, byte_len) in [
+        (Content::UTF8(String::new()), true, Some(0)),
+        (Content::UTF8("one".to_string()), false, Some(3)),
+        (Content::UTF8("ä".to_string()), false, Some(2)),
+        (Content::from(&[0xff, 0x00][..]), false, None),
+        (binary(), false, None),
+        (Content::Large, false, None),
+    ] {
+        assert_eq!(content.is_empty(), is_empty);
+        assert_eq!(content.byte_len(), byte_len);
+    }
+}
+
+#[test]
+fn as_str() {
+    let content = Content::UTF8("text".to_string());
+    let text = content.as_str().unwrap();
+    assert_eq!(text, "text");
+    // borrowed from the content, not a copy of it
+    let Content::UTF8(owned) = &content else {
+        unreachable!()
+    };
+    assert_eq!(text.as_ptr(), owned.as_ptr());
+
+    assert_eq!(binary().as_str(), None);
+    assert_eq!(Content::Large.as_str(), None);
+}
+
+#[test]
+fn read_as() -> Result<()> {
+    let dir = temp_dir();
+
+    fs::write(dir.path().join("number"), "42")?;
+
+    let reader = Reader::open(dir.path())?;
+    assert_eq!(reader.read_as::<usize, _>("number")?, 42);
+
+    Ok(())
+}
+
+#[test]
+fn read_as_reports_path() -> Result<()> {
+    let dir = temp_dir();
+
+    fs::write(dir.path().join("number"), "not a number")?;
+
+    let reader = Reader::open(dir.path())?;
+    let error = reader.read_as::<usize, _>("number").unwrap_err();
+    assert!(matches!(
+        &error,
+        reader::Error::From(FromError::WithPath { path, source })
+            if path == Path::new("number") && matches!(**source, FromError::ParseInt(_))
+    ));
+    assert!(error.to_string().contains("number"));
+
+    Ok(())
+}
+
+#[test]
+fn stash_reader_read_file() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    let file_path = Path::new("test.txt");
+    fs::write(workdir.join(file_path), "test")?;
+    commit_all(&repository);
+
+    fs::write(workdir.join(file_path), "stashed")?;
+    let mut git2_repository = git2::Repository::open(workdir)?;
+    let signature = git2::Signature::now("test", "test@email.com")?;
+    git2_repository.stash_save(&signature, "stash", None)?;
+
+    assert_eq!(fs::read_to_string(workdir.join(file_path))?, "test");
+
+    let reader = Reader::from_stash(&repository, 0)?;
+    assert_eq!(
+        reader.read(file_path)?,
+        Content::UTF8("stashed".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn stash_reader_out_of_range() {
+    let (repository, _tmp) = test_repository();
+
+    let error = Reader::from_stash(&repository, 0).err().unwrap();
+    assert!(matches!(
+        error.downcast_ref::<reader::Error>(),
+        Some(reader::Error::NotFound)
+    ));
+}
+
+#[test]
+fn eq_ignoring_trailing_ws() {
+    for (left, right, expected) in [
+        (
+            Content::UTF8("one  \ntwo\t\n".to_string()),
+            Content::UTF8("one\ntwo\n".to_string()),
+            true,
+        ),
+        (
+            Content::UTF8("one\ntwo".to_string()),
+            Content::UTF8("one \ntwo ".to_string()),
+            true,
+        ),
+        (
+            Content::UTF8("  one\n".to_string()),
+            Content::UTF8("one\n".to_string()),
+            false,
+        ),
+        (
+            Content::UTF8("one\ntwo\n".to_string()),
+            Content::UTF8("one\nthree\n".to_string()),
+            false,
+        ),
+        (
+            Content::UTF8("one\n".to_string()),
+            Content::UTF8("one\n\n".to_string()),
+            false,
+        ),
+        (binary(), binary(), true),
+        (binary(), Content::from(&[0xfe, 0x00][..]), false),
+        (binary(), Content::UTF8(String::new()), false),
+    ] {
+        assert_eq!(left.eq_ignoring_trailing_ws(&right), expected);
+    }
+}
+
+#[test]
+fn diff() {
+    let old = Content::UTF8("one\ntwo\nthree\n".to_string());
+
+    let hunks = old
+        .diff(&Content::UTF8("one\n2\nthree\n".to_string()))
+        .unwrap();
+    assert_eq!(hunks.len(), 1);
+    assert_eq!(
+        (
+            hunks[0].old_start,
+            hunks[0].old_lines,
+            hunks[0].new_start,
+            hunks[0].new_lines
+        ),
+        (1, 3, 1, 3)
+    );
+    assert_eq!(hunks[0].diff, "@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n");
+
+    // adding to an empty file starts after line 0
+    let hunks = Content::UTF8(String::new()).diff(&old).unwrap();
+    assert_eq!(hunks.len(), 1);
+    assert_eq!(
+        (
+            hunks[0].old_start,
+            hunks[0].old_lines,
+            hunks[0].new_start,
+            hunks[0].new_lines
+        ),
+        (0, 0, 1, 3)
+    );
+
+    assert_eq!(old.diff(&old), Some(vec![]));
+    assert_eq!(old.diff(&binary()), None);
+    assert_eq!(Content::Large.diff(&old), None);
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+fn commit_reader_file_mode() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::write(workdir.join("regular.txt"), "test")?;
+    fs::write(workdir.join("script.sh"), "#!/bin/sh\n")?;
+    fs::set_permissions(workdir.join("script.sh"), fs::Permissions::from_mode(0o755))?;
+
+    let oid = commit_all(&repository);
+
+    let reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    assert_eq!(reader.file_mode("regular.txt")?, 0o100644);
+    assert_eq!(reader.file_mode("script.sh")?, 0o100755);
+    assert!(matches!(
+        reader.file_mode("missing.txt"),
+        Err(reader::Error::NotFound)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn directory_reader_file_mode() -> Result<()> {
+    let dir = temp_dir();
+
+    fs::write(dir.path().join("test.txt"), "test")?;
+
+    let reader = Reader::open(dir.path())?;
+    assert!(matches!(
+        reader.file_mode("test.txt"),
+        Err(reader::Error::Io(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn commit_reader_read_many() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    let mut paths = vec![];
+    for i in 0..9 {
+        let path = PathBuf::from(format!("dir{}/file{}.txt", i % 3, i));
+        fs::create_dir_all(workdir.join(&path).parent().unwrap())?;
+        fs::write(workdir.join(&path), format!("content {}", i))?;
+        paths.push(path);
+    }
+    paths.push(PathBuf::from("dir0/missing.txt"));
+
+    let oid = commit_all(&repository);
+
+    let reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    let results = reader.read_many(&paths);
+    assert_eq!(results.len(), paths.len());
+    for ((path, content), expected_path) in results.into_iter().zip(&paths) {
+        assert_eq!(&path, expected_path);
+        match (content, reader.read(&path)) {
+            (Ok(content), Ok(expected)) => assert_eq!(content, expected),
+            (Err(reader::Error::NotFound), Err(reader::Error::NotFound)) => {}
+            (content, expected) => panic!("{}: {:?} != {:?}", path.display(), content, expected),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn mtime() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::write(workdir.join("file.txt"), "content")?;
+    let oid = commit_all(&repository);
+
+    // pretend the file is old, so that touching it is guaranteed to produce a newer mtime
+    filetime::set_file_mtime(
+        workdir.join("file.txt"),
+        filetime::FileTime::from_unix_time(1_000_000_000, 0),
+    )?;
+
+    let reader = Reader::open(workdir)?;
+    let before = reader.mtime("file.txt")?.unwrap();
+
+    fs::write(workdir.join("file.txt"), "changed")?;
+    let after = reader.mtime("file.txt")?.unwrap();
+    assert!(after > before);
+
+    assert!(matches!(
+        reader.mtime("missing.txt"),
+        Err(reader::Error::NotFound)
+    ));
+
+    let reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    assert_eq!(reader.mtime("file.txt")?, None);
+    assert!(matches!(
+        reader.mtime("missing.txt"),
+        Err(reader::Error::NotFound)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn list_files_page() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::create_dir_all(workdir.join("dir"))?;
+    for i in 0..100 {
+        fs::write(workdir.join(format!("dir/file{:03}.txt", i)), "content")?;
+    }
+    fs::write(workdir.join("outside.txt"), "content")?;
+    let oid = commit_all(&repository);
+
+    let commit_reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    let filesystem_reader = Reader::open(workdir)?;
+    for reader in [commit_reader, filesystem_reader] {
+        let mut all = vec![];
+        for page in 0..4 {
+            let (files, has_more) = reader.list_files_page("dir", page * 25, 25)?;
+            assert_eq!(files.len(), 25);
+            assert_eq!(has_more, page < 3);
+            all.extend(files);
+        }
+        let (files, has_more) = reader.list_files_page("dir", 100, 25)?;
+        assert!(files.is_empty());
+        assert!(!has_more);
+
+        assert_eq!(all, reader.list_files("dir")?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn list_files_page_of_commit_is_sorted_like_list_files() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    // git orders `a.txt` before the tree `a`, paths order `a/b` first
+    fs::create_dir_all(workdir.join("dir/a"))?;
+    fs::write(workdir.join("dir/a.txt"), "content")?;
+    fs::write(workdir.join("dir/a/b"), "content")?;
+    let oid = commit_all(&repository);
+
+    let reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    let files = reader.list_files("dir")?;
+    assert_eq!(files, vec![PathBuf::from("a/b"), PathBuf::from("a.txt")]);
+
+    let (first, has_more) = reader.list_files_page("dir", 0, 1)?;
+    assert!(has_more);
+    let (second, has_more) = reader.list_files_page("dir", 1, 1)?;
+    assert!(!has_more);
+    assert_eq!([first, second].concat(), files);
+
+    Ok(())
+}
+
+#[test]
+fn sub_reader_refuses_paths_outside_prefix() -> Result<()> {
+    let dir = temp_dir();
+    fs::create_dir_all(dir.path().join("config"))?;
+    fs::write(dir.path().join("config/settings"), "inside")?;
+    fs::write(dir.path().join("secrets"), "outside")?;
+
+    let reader = Reader::open(dir.path())?;
+    let sub_reader = reader.sub("config");
+    assert_eq!(
+        sub_reader.read("settings")?,
+        Content::UTF8("inside".to_string())
+    );
+    assert!(matches!(
+        sub_reader.read("../secrets"),
+        Err(reader::Error::OutsideRoot(_))
+    ));
+    assert!(matches!(
+        sub_reader.read("../../etc/passwd"),
+        Err(reader::Error::OutsideRoot(_))
+    ));
+    assert!(matches!(
+        sub_reader.read("/etc/passwd"),
+        Err(reader::Error::OutsideRoot(_))
+    ));
+    assert!(!sub_reader.exists("../secrets")?);
+    assert!(sub_reader.list_files("..").is_err());
+
+    let contents = sub_reader.batch(&["settings", "../secrets"])?;
+    assert_eq!(contents.len(), 2);
+    assert_eq!(
+        contents[0].as_ref().unwrap(),
+        &Content::UTF8("inside".to_string())
+    );
+    assert!(matches!(contents[1], Err(reader::Error::OutsideRoot(_))));
+
+    Ok(())
+}
+
+#[test]
+fn reader_from_annotated_tag() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+
+    let file_path = Path::new("test.txt");
+    fs::write(
+        repository.path().parent().unwrap().join(file_path),
+        "tagged",
+    )?;
+    let oid = commit_all(&repository);
+    fs::write(repository.path().parent().unwrap().join(file_path), "later")?;
+    commit_all(&repository);
+
+    let git2_repository: &git2::Repository = (&repository).into();
+    git2_repository.tag(
+        "v1.0.0",
+        &git2_repository.find_object(oid.into(), None)?,
+        &git2::Signature::now("test", "test@email.com")?,
+        "release",
+        false,
+    )?;
+
+    let reference = repository.find_reference(&"refs/tags/v1.0.0".parse()?)?;
+    let reader = Reader::from_reference(&repository, &reference)?;
+    assert_eq!(reader.commit_id(), Some(oid));
+    assert_eq!(reader.read(file_path)?, Content::UTF8("tagged".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn reader_from_reference_to_tree_fails() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+
+    let git2_repository: &git2::Repository = (&repository).into();
+    let tree = git2_repository.head()?.peel_to_tree()?;
+    git2_repository.tag_lightweight("tree", tree.as_object(), false)?;
+
+    let reference = repository.find_reference(&"refs/tags/tree".parse()?)?;
+    let error = Reader::from_reference(&repository, &reference)
+        .err()
+        .expect("a tree is not a commit");
+    assert_eq!(
+        error.to_string(),
+        "reference refs/tags/tree does not point to a commit"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn commit_reader_parent() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::write(workdir.join("file.txt"), "before")?;
+    commit_all(&repository);
+    fs::write(workdir.join("file.txt"), "after")?;
+    let oid = commit_all(&repository);
+
+    let reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    assert_eq!(reader.read("file.txt")?, Content::UTF8("after".to_string()));
+
+    let parent = reader.parent(0)?;
+    assert_eq!(
+        parent.read("file.txt")?,
+        Content::UTF8("before".to_string())
+    );
+    assert_eq!(
+        parent.commit_id(),
+        Some(repository.find_commit(oid)?.parent(0)?.id())
+    );
+
+    assert!(matches!(reader.parent(1), Err(reader::Error::NotFound)));
+
+    Ok(())
+}
+
+#[test]
+fn commit_reader_parent_of_root_commit() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+
+    let root = repository.head()?.peel_to_commit()?;
+    assert_eq!(root.parent_count(), 0);
+    let reader = Reader::from_commit(&repository, &root)?;
+    assert!(matches!(reader.parent(0), Err(reader::Error::NotFound)));
+
+    Ok(())
+}
+
+#[test]
+fn list_files_is_sorted() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::create_dir_all(workdir.join("a"))?;
+    for name in ["a.txt", "a-c.txt", "a/b.txt"] {
+        fs::write(workdir.join(name), "content")?;
+    }
+    let oid = commit_all(&repository);
+
+    let commit_reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    let filesystem_reader = Reader::open(workdir)?;
+    for reader in [commit_reader, filesystem_reader] {
+        assert_eq!(
+            reader.list_files("")?,
+            vec![
+                PathBuf::from("a/b.txt"),
+                PathBuf::from("a-c.txt"),
+                PathBuf::from("a.txt"),
+            ]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn walk_stops_when_visitor_breaks() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::create_dir_all(workdir.join("src"))?;
+    for name in ["a.txt", "b.rs", "c.rs", "d.txt"] {
+        fs::write(workdir.join("src").join(name), "content")?;
+    }
+    let oid = commit_all(&repository);
+
+    let commit_reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    let filesystem_reader = Reader::open(workdir)?;
+    for reader in [commit_reader, filesystem_reader] {
+        let mut visited = vec![];
+        reader.walk("src", |path| {
+            visited.push(path.to_path_buf());
+            if path.extension() == Some("rs".as_ref()) {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })?;
+        assert_eq!(visited, vec![Path::new("a.txt"), Path::new("b.rs")]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn read_located() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::create_dir_all(workdir.join("config"))?;
+    fs::write(workdir.join("config/committed"), "committed")?;
+    let oid = commit_all(&repository);
+    fs::write(workdir.join("config/settings"), "settings")?;
+
+    let dir_reader = Reader::open(workdir)?;
+    assert_eq!(
+        dir_reader.sub("config").read_located("settings")?,
+        (
+            Content::UTF8("settings".to_string()),
+            ResolvedSource::Disk(workdir.join("config/settings"))
+        )
+    );
+
+    fs::remove_file(workdir.join("config/committed"))?;
+    let reader = Reader::overlay(
+        Reader::open(workdir)?,
+        Reader::from_commit(&repository, &repository.find_commit(oid)?)?,
+    );
+    assert_eq!(
+        reader.sub("config").read_located("committed")?,
+        (
+            Content::UTF8("committed".to_string()),
+            ResolvedSource::Commit(oid, PathBuf::from("config/committed"))
+        )
+    );
+    assert!(matches!(
+        reader.read_located("missing"),
+        Err(reader::Error::NotFound)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn tree_hash() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::create_dir_all(workdir.join("dir"))?;
+    fs::write(workdir.join("dir/file.txt"), "content")?;
+    fs::write(workdir.join("other.txt"), "content")?;
+    let oid = commit_all(&repository);
+
+    let dir_reader = Reader::open(workdir)?;
+    let hash = dir_reader.tree_hash("dir")?;
+    assert_eq!(dir_reader.tree_hash("dir")?, hash);
+
+    // changes outside of the directory don't count
+    fs::write(workdir.join("other.txt"), "edited")?;
+    assert_eq!(dir_reader.tree_hash("dir")?, hash);
+
+    fs::write(workdir.join("dir/file.txt"), "edited content")?;
+    assert_ne!(dir_reader.tree_hash("dir")?, hash);
+
+    let commit = repository.find_commit(oid)?;
+    let commit_reader = Reader::from_commit(&repository, &commit)?;
+    let subtree_oid = commit.tree()?.get_path(Path::new("dir"))?.id();
+    assert_eq!(commit_reader.tree_hash("dir")?, subtree_oid.to_string());
+    assert_eq!(
+        commit_reader.tree_hash("")?,
+        commit.tree()?.id().to_string()
+    );
+
+    assert!(matches!(
+        commit_reader.tree_hash("dir/file.txt"),
+        Err(reader::Error::NotFound)
+    ));
+    assert!(matches!(
+        dir_reader.tree_hash("missing"),
+        Err(reader::Error::NotFound)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn overlay_reader_prefers_top() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::write(workdir.join("shadowed.txt"), "committed")?;
+    fs::write(workdir.join("committed.txt"), "committed")?;
+    let oid = commit_all(&repository);
+
+    fs::write(workdir.join("shadowed.txt"), "edited")?;
+    fs::write(workdir.join("new.txt"), "new")?;
+
+    let reader = Reader::overlay(
+        Reader::open(workdir)?,
+        Reader::from_commit(&repository, &repository.find_commit(oid)?)?,
+    );
+    assert_eq!(
+        reader.read("shadowed.txt")?,
+        Content::UTF8("edited".to_string())
+    );
+    assert_eq!(
+        reader.read("committed.txt")?,
+        Content::UTF8("committed".to_string())
+    );
+    assert_eq!(reader.read("new.txt")?, Content::UTF8("new".to_string()));
+    assert!(matches!(
+        reader.read("missing.txt"),
+        Err(reader::Error::NotFound)
+    ));
+
+    // the overlaid working directory doesn't hide committed files that were deleted
+    fs::remove_file(workdir.join("committed.txt"))?;
+    assert!(reader.exists("committed.txt")?);
+
+    assert_eq!(
+        reader.list_files("")?,
+        vec![
+            PathBuf::from("committed.txt"),
+            PathBuf::from("new.txt"),
+            PathBuf::from("shadowed.txt"),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn git_config_reader() -> Result<()> {
+    let dir = temp_dir();
+    fs::write(
+        dir.path().join("config"),
+        r#"# top level comment
+[core]
+    bare = false
+    repositoryformatversion = 0
+    FileMode = true ; trailing comment
+[remote "origin"]
+    url = "git@example.com:org/repo.git"
+    fetch = +refs/heads/*:refs/remotes/origin/*
+[gitbutler]
+    signCommits
+    contextLines = -3
+"#,
+    )?;
+
+    let reader = Reader::open(dir.path())?;
+    let config = GitConfigReader::open(&reader, "config")?;
+
+    assert_eq!(config.get_bool("core.bare")?, Some(false));
+    assert_eq!(config.get_bool("core.filemode")?, Some(true));
+    assert_eq!(config.get_int("core.repositoryFormatVersion")?, Some(0));
+    assert_eq!(
+        config.get_str("remote.origin.url")?.as_deref(),
+        Some("git@example.com:org/repo.git")
+    );
+    assert_eq!(config.get_bool("gitbutler.signcommits")?, Some(true));
+    assert_eq!(config.get_int("gitbutler.contextLines")?, Some(-3));
+    assert_eq!(config.get_str("remote.upstream.url")?, None);
+    assert!(matches!(
+        config.get_int("core.bare"),
+        Err(reader::Error::From(FromError::ParseInt(_)))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn directory_reader_list_files_with_ignored() -> Result<()> {
+    let dir = temp_dir();
+
+    fs::create_dir_all(dir.path().join(".git"))?;
+    fs::create_dir_all(dir.path().join("node_modules/package"))?;
+    fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/master")?;
+    fs::write(dir.path().join("node_modules/package/index.js"), "content")?;
+    fs::write(dir.path().join("index.js"), "content")?;
+
+    let reader = Reader::open(dir.path())?;
+    assert_eq!(
+        reader.list_files("")?,
+        vec![
+            PathBuf::from("index.js"),
+            PathBuf::from("node_modules/package/index.js"),
+        ]
+    );
+
+    let reader = Reader::open_with_ignored(dir.path(), vec![PathBuf::from("node_modules")])?;
+    assert_eq!(reader.list_files("")?, vec![PathBuf::from("index.js")]);
+
+    let mut walked = vec![];
+    reader.walk("", |path| {
+        walked.push(path.to_path_buf());
+        ControlFlow::Continue(())
+    })?;
+    assert_eq!(walked, vec![PathBuf::from("index.js")]);
+
+    Ok(())
+}
+
+#[test]
+fn commit_reader_read_case_insensitive() -> Result<()> {
+    let (repository, _tmp) = test_repository();
+    let workdir = repository.path().parent().unwrap();
+
+    fs::create_dir_all(workdir.join("Docs"))?;
+    fs::write(workdir.join("Readme.md"), "readme")?;
+    fs::write(workdir.join("Docs/Guide.md"), "guide")?;
+    let oid = commit_all(&repository);
+
+    let reader = Reader::from_commit(&repository, &repository.find_commit(oid)?)?;
+    assert!(matches!(
+        reader.read("README.md"),
+        Err(reader::Error::NotFound)
+    ));
+    assert_eq!(
+        reader.read_case_insensitive("README.md")?,
+        Content::UTF8("readme".to_string())
+    );
+    assert_eq!(
+        reader.read_case_insensitive("docs/guide.md")?,
+        Content::UTF8("guide".to_string())
+    );
+    assert_eq!(
+        reader.sub("docs").read_case_insensitive("GUIDE.md")?,
+        Content::UTF8("guide".to_string())
+    );
+    assert!(matches!(
+        reader.read_case_insensitive("missing.md"),
+        Err(reader::Error::NotFound)
+    ));
+
+    Ok(())
+}
@@ -69,3 +69,78 @@ mod add {
         }
     }
 }
+
+mod projects_in_conflict {
+    use gitbutler_core::project_repository::{self, conflicts};
+
+    use super::*;
+
+    #[test]
+    fn only_conflicted() {
+        let (controller, _tmp) = new();
+        let clean = gitbutler_testsupport::TestProject::default();
+        let conflicted = gitbutler_testsupport::TestProject::default();
+        controller.add(clean.path()).unwrap();
+        let project = controller.add(conflicted.path()).unwrap();
+
+        let parent = conflicted.commit_all("parent");
+        let project_repository = project_repository::Repository::open(&project).unwrap();
+        conflicts::mark(&project_repository, ["file.txt"], Some(parent)).unwrap();
+
+        assert_eq!(controller.projects_in_conflict().unwrap(), vec![project.id]);
+    }
+}
+
+#[cfg(not(windows))]
+mod key_passphrase {
+    use gitbutler_core::{
+        projects::{AuthKey, UpdateRequest},
+        secret,
+    };
+    use gitbutler_testsupport::secrets;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn kept_out_of_project_storage() {
+        secrets::setup_in_memory();
+        let (controller, data_dir) = new();
+        let repository = gitbutler_testsupport::TestProject::default();
+        let project = controller.add(repository.path()).unwrap();
+        let private_key_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/keys/id_ed25519_encrypted");
+        controller
+            .update(&UpdateRequest {
+                id: project.id,
+                preferred_key: Some(AuthKey::Local {
+                    private_key_path: private_key_path.clone(),
+                    passphrase: None,
+                }),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let project = controller
+            .set_key_passphrase(&project.id, Some("correct horse"))
+            .unwrap();
+        let handle = project.key_passphrase_handle();
+        assert_eq!(
+            project.preferred_key,
+            AuthKey::Local {
+                private_key_path,
+                passphrase: Some(handle.clone()),
+            }
+        );
+        assert_eq!(
+            secret::retrieve(&handle).unwrap().as_deref(),
+            Some("correct horse")
+        );
+        let stored = std::fs::read_to_string(data_dir.path().join("projects.json")).unwrap();
+        assert!(stored.contains(&handle));
+        assert!(!stored.contains("correct horse"));
+
+        controller.set_key_passphrase(&project.id, None).unwrap();
+        assert_eq!(secret::retrieve(&handle).unwrap(), None);
+    }
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+#[tokio::test]
+async fn restores_state_before_conflicted_integration() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "first\n").unwrap();
+    let first = controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "first",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "second\n").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "second",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    // the second commit is only upstream now, and conflicts with the local change
+    controller
+        .reset_virtual_branch(project_id, &branch_id, first)
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "local\n").unwrap();
+
+    controller
+        .merge_virtual_branch_upstream(project_id, &branch_id)
+        .await
+        .unwrap();
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert!(branches[0].conflicted);
+
+    controller.abort_integration(project_id).await.unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 1);
+    assert!(!branches[0].conflicted);
+    assert_eq!(branches[0].head, first);
+    assert!(controller
+        .list_conflicts(project_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        fs::read_to_string(repository.path().join("file.txt")).unwrap(),
+        "local\n"
+    );
+}
+
+#[tokio::test]
+async fn without_conflicted_integration() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .abort_integration(project_id)
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::AbortIntegrationError::NotIntegrating(_))
+    ));
+}
@@ -74,7 +74,13 @@ async fn forcepush_allowed() {
         // create commit
         fs::write(repository.path().join("file.txt"), "content").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
     };
@@ -141,7 +147,13 @@ async fn forcepush_forbidden() {
         // create commit
         fs::write(repository.path().join("file.txt"), "content").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
     };
@@ -188,7 +200,13 @@ async fn non_locked_hunk() {
         // create commit
         fs::write(repository.path().join("file.txt"), "content").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
@@ -251,7 +269,13 @@ async fn locked_hunk() {
         // create commit
         fs::write(repository.path().join("file.txt"), "content").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
@@ -323,7 +347,13 @@ async fn non_existing_ownership() {
         // create commit
         fs::write(repository.path().join("file.txt"), "content").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
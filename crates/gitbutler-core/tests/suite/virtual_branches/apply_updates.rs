@@ -0,0 +1,106 @@
+use super::*;
+
+#[tokio::test]
+async fn applies_all_updates() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch1_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    let branch2_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    controller
+        .apply_updates(
+            project_id,
+            vec![
+                branch::BranchUpdateRequest {
+                    id: branch1_id,
+                    name: Some("one".to_string()),
+                    ..Default::default()
+                },
+                branch::BranchUpdateRequest {
+                    id: branch2_id,
+                    notes: Some("two".to_string()),
+                    ..Default::default()
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let branch1 = branches.iter().find(|b| b.id == branch1_id).unwrap();
+    let branch2 = branches.iter().find(|b| b.id == branch2_id).unwrap();
+    assert_eq!(branch1.name, "one");
+    assert_eq!(branch2.notes, "two");
+}
+
+#[tokio::test]
+async fn applies_nothing_if_an_update_fails() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("name".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let result = controller
+        .apply_updates(
+            project_id,
+            vec![
+                branch::BranchUpdateRequest {
+                    id: branch_id,
+                    name: Some("renamed".to_string()),
+                    ..Default::default()
+                },
+                branch::BranchUpdateRequest {
+                    id: branch::BranchId::generate(),
+                    notes: Some("missing".to_string()),
+                    ..Default::default()
+                },
+            ],
+        )
+        .await;
+    assert!(result.is_err());
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].name, "name");
+
+    let refnames = repository
+        .references()
+        .into_iter()
+        .filter_map(|reference| reference.name().map(|name| name.to_string()))
+        .collect::<Vec<_>>();
+    assert!(refnames.contains(&"refs/gitbutler/name".to_string()));
+    assert!(!refnames.contains(&"refs/gitbutler/renamed".to_string()));
+}
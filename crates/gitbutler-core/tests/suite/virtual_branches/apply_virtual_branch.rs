@@ -91,7 +91,13 @@ async fn rebase_commit() {
         fs::write(repository.path().join("another_file.txt"), "virtual").unwrap();
 
         controller
-            .create_commit(project_id, &branch1_id, "virtual commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "virtual commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
@@ -0,0 +1,57 @@
+use super::*;
+
+#[tokio::test]
+async fn archived_branch_is_hidden_and_restorable() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    std::fs::write(repository.path().join("file.txt"), "content").unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 1);
+    let branch_id = branches[0].id;
+
+    controller
+        .archive_branch(project_id, &branch_id)
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 0);
+    assert!(!repository.path().join("file.txt").exists());
+
+    let (branches, _, _) = controller
+        .list_virtual_branches_including_archived(project_id)
+        .await
+        .unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].id, branch_id);
+    assert!(!branches[0].active);
+
+    controller
+        .unarchive_branch(project_id, &branch_id)
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].id, branch_id);
+
+    controller
+        .apply_virtual_branch(project_id, &branch_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(repository.path().join("file.txt")).unwrap(),
+        "content"
+    );
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+#[tokio::test]
+async fn matches_upstream_commits() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(controller.base_branch_behind(project_id).await.unwrap(), 0);
+
+    repository.checkout(&"refs/heads/master".parse().unwrap());
+    for content in ["first", "second"] {
+        fs::write(repository.path().join("file.txt"), content).unwrap();
+        repository.commit_all(content);
+    }
+    repository.push_branch(&"refs/heads/master".parse().unwrap());
+    repository.checkout(&"refs/heads/gitbutler/integration".parse().unwrap());
+
+    let behind = controller.base_branch_behind(project_id).await.unwrap();
+    let base_branch = controller
+        .get_base_branch_data(project_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(behind, 2);
+    assert_eq!(behind, base_branch.upstream_commits.len());
+    assert_eq!(behind, base_branch.behind);
+}
+
+#[tokio::test]
+async fn no_default_target() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    assert_eq!(controller.base_branch_behind(project_id).await.unwrap(), 0);
+}
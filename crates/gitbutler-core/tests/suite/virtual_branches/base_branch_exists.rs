@@ -0,0 +1,76 @@
+use super::*;
+
+#[tokio::test]
+async fn reflects_base_branch() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    assert!(!controller.base_branch_exists(project_id).await.unwrap());
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(controller.base_branch_exists(project_id).await.unwrap());
+}
+
+#[cfg(target_family = "unix")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn does_not_wait_for_lock() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+
+    // a slow hook keeps the commit below holding the lock
+    git2_hooks::create_hook(
+        &git2::Repository::open(repository.path()).unwrap(),
+        git2_hooks::HOOK_PRE_COMMIT,
+        b"#!/bin/sh\nsleep 3\n",
+    );
+
+    let commit = tokio::spawn({
+        let controller = controller.clone();
+        let project_id = *project_id;
+        let branch_id = branches[0].id;
+        async move {
+            controller
+                .create_commit(
+                    &project_id,
+                    &branch_id,
+                    "commit",
+                    None,
+                    &CommitOptions {
+                        run_hooks: true,
+                        ..Default::default()
+                    },
+                )
+                .await
+        }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let exists = tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        controller.base_branch_exists(project_id),
+    )
+    .await
+    .expect("should not wait for the running commit");
+    assert!(exists.unwrap());
+
+    commit.await.unwrap().unwrap();
+}
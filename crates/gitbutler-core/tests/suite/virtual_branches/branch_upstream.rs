@@ -0,0 +1,106 @@
+use super::*;
+
+#[tokio::test]
+async fn never_pushed() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        controller
+            .branch_upstream(project_id, &branch_id)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn deleted_on_remote() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("name".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit one",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "more content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit two",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let upstream = controller
+        .branch_upstream(project_id, &branch_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(upstream.remote_ref, "refs/remotes/origin/name");
+    assert!(upstream.exists_on_remote);
+    assert_eq!(upstream.ahead, 1);
+    assert_eq!(upstream.behind, 0);
+
+    repository.delete_remote_branch("name");
+    controller
+        .fetch_from_target(project_id, None)
+        .await
+        .unwrap();
+
+    let upstream = controller
+        .branch_upstream(project_id, &branch_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(upstream.remote_ref, "refs/remotes/origin/name");
+    assert!(!upstream.exists_on_remote);
+}
@@ -26,7 +26,13 @@ mod cleanly {
         let commit_one = {
             fs::write(repository.path().join("file.txt"), "content").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -34,7 +40,13 @@ mod cleanly {
         let commit_two = {
             fs::write(repository.path().join("file.txt"), "content two").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -98,7 +110,13 @@ mod cleanly {
         let commit_one = {
             fs::write(repository.path().join("file.txt"), "content").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -106,7 +124,13 @@ mod cleanly {
         let commit_two = {
             fs::write(repository.path().join("file_two.txt"), "content two").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -181,7 +205,13 @@ mod cleanly {
         let commit_one_oid = {
             fs::write(repository.path().join("file.txt"), "content").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -189,7 +219,13 @@ mod cleanly {
         {
             fs::write(repository.path().join("file_two.txt"), "content two").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -197,7 +233,13 @@ mod cleanly {
         let commit_three_oid = {
             fs::write(repository.path().join("file_three.txt"), "content three").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -249,7 +291,13 @@ mod with_conflicts {
         let commit_one = {
             fs::write(repository.path().join("file.txt"), "content").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit one", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit one",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -257,7 +305,13 @@ mod with_conflicts {
         {
             fs::write(repository.path().join("file_two.txt"), "content two").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit two", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit two",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -265,7 +319,13 @@ mod with_conflicts {
         let commit_three = {
             fs::write(repository.path().join("file_three.txt"), "content three").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "commit three", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "commit three",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap()
         };
@@ -318,7 +378,13 @@ mod with_conflicts {
             // conflict can be resolved
             fs::write(repository.path().join("file_three.txt"), "resolved").unwrap();
             let commited_oid = controller
-                .create_commit(project_id, &branch_id, "resolution", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "resolution",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -0,0 +1,66 @@
+use super::*;
+
+#[tokio::test]
+async fn matches_commit_signature() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    let commit_id = controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let author = controller
+        .commit_author(project_id, commit_id)
+        .await
+        .unwrap();
+
+    let commit = repository.find_commit(commit_id).unwrap();
+    let signature = commit.author();
+    assert_eq!(author.name, signature.name().unwrap());
+    assert_eq!(author.email, signature.email().unwrap());
+}
+
+#[tokio::test]
+async fn unknown_commit() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let oid = git::Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+    assert!(matches!(
+        controller
+            .commit_author(project_id, oid)
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::CommitAuthorError::CommitNotFound(_))
+    ));
+}
@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use super::*;
+
+#[tokio::test]
+async fn shared_base_appears_once() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    let base_branch = controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch1_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file one.txt"), "content").unwrap();
+    let commit1_oid = controller
+        .create_commit(
+            project_id,
+            &branch1_id,
+            "commit one",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let branch2_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file two.txt"), "content").unwrap();
+    let commit2_oid = controller
+        .create_commit(
+            project_id,
+            &branch2_id,
+            "commit two",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let graph = controller.commit_graph(project_id).await.unwrap();
+
+    // the base commit both branches are based on is only there once
+    assert_eq!(graph.nodes.len(), 3);
+    assert_eq!(
+        graph
+            .nodes
+            .iter()
+            .map(|node| node.id)
+            .collect::<HashSet<_>>(),
+        HashSet::from([base_branch.base_sha, commit1_oid, commit2_oid])
+    );
+
+    let commit1 = graph
+        .nodes
+        .iter()
+        .find(|node| node.id == commit1_oid)
+        .unwrap();
+    assert_eq!(commit1.description, "commit one");
+
+    assert_eq!(graph.edges.len(), 2);
+    assert_eq!(
+        graph
+            .edges
+            .iter()
+            .map(|edge| (edge.child, edge.parent))
+            .collect::<HashSet<_>>(),
+        HashSet::from([
+            (commit1_oid, base_branch.base_sha),
+            (commit2_oid, base_branch.base_sha),
+        ])
+    );
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_listings_agree_on_default_branch() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+
+    let listings = futures::future::join_all((0..8).map(|_| {
+        let controller = controller.clone();
+        let project_id = *project_id;
+        tokio::spawn(async move { controller.list_virtual_branches(&project_id).await })
+    }))
+    .await;
+
+    let mut branch_ids = listings
+        .into_iter()
+        .map(|listing| {
+            let (branches, _, _) = listing.unwrap().unwrap();
+            assert_eq!(branches.len(), 1);
+            branches[0].id
+        })
+        .collect::<Vec<_>>();
+    branch_ids.dedup();
+    assert_eq!(branch_ids.len(), 1);
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].id, branch_ids[0]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn listing_while_another_listing_holds_the_lock() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+
+    // writes the ownership of the new hunk, later listings only read
+    let (listed, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(listed.len(), 2);
+
+    // with room for only one of the two branches, the stream holds its lock until it is received
+    let (tx, mut rx) = mpsc::channel(1);
+    let probe = tx.clone();
+    let streaming = tokio::spawn({
+        let controller = controller.clone();
+        let project_id = *project_id;
+        async move { controller.stream_virtual_branches(&project_id, tx).await }
+    });
+    while probe.capacity() > 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(!streaming.is_finished());
+
+    let (branches, _, _) = tokio::time::timeout(
+        Duration::from_secs(10),
+        controller.list_virtual_branches(project_id),
+    )
+    .await
+    .expect("listing waited for the stream")
+    .unwrap();
+    assert_eq!(branches.len(), 2);
+    assert!(!streaming.is_finished());
+
+    drop(probe);
+    let mut streamed = 0;
+    while rx.recv().await.is_some() {
+        streamed += 1;
+    }
+    streaming.await.unwrap().unwrap();
+    assert_eq!(streamed, 2);
+}
@@ -1,5 +1,45 @@
 use super::*;
 
+#[tokio::test]
+async fn returns_commit_oid() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+
+    let commit_id = controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "test",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let commit = repository.find_commit(commit_id).unwrap();
+    assert_eq!(commit.message(), Some("test"));
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let branch = branches.iter().find(|b| b.id == branch_id).unwrap();
+    assert_eq!(branch.head, commit_id);
+}
+
 #[tokio::test]
 async fn should_lock_updated_hunks() {
     let Test {
@@ -39,7 +79,13 @@ async fn should_lock_updated_hunks() {
     }
 
     controller
-        .create_commit(project_id, &branch_id, "test", None, false)
+        .create_commit(
+            project_id,
+            &branch_id,
+            "test",
+            None,
+            &CommitOptions::default(),
+        )
         .await
         .unwrap();
 
@@ -105,7 +151,13 @@ async fn should_not_lock_disjointed_hunks() {
     }
 
     controller
-        .create_commit(project_id, &branch_id, "test commit", None, false)
+        .create_commit(
+            project_id,
+            &branch_id,
+            "test commit",
+            None,
+            &CommitOptions::default(),
+        )
         .await
         .unwrap();
     controller
@@ -196,3 +248,252 @@ async fn should_not_lock_disjointed_hunks() {
         fs::write(repository.path().join("file.txt"), lines.clone().join("\n")).unwrap();
     }
 }
+
+#[tokio::test]
+async fn emits_span() {
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::{layer::SubscriberExt, Layer};
+
+    #[derive(Clone, Default)]
+    struct SpanNames(Arc<Mutex<Vec<&'static str>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for SpanNames {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.0.lock().unwrap().push(attrs.metadata().name());
+        }
+    }
+
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+
+    let span_names = SpanNames::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(span_names.clone()));
+
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "test",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(span_names.0.lock().unwrap().contains(&"create_commit"));
+}
+
+#[tokio::test]
+async fn pre_commit_hook_only_runs_when_asked() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    git2_hooks::create_hook(
+        &git2::Repository::open(repository.path()).unwrap(),
+        git2_hooks::HOOK_PRE_COMMIT,
+        b"#!/bin/sh\necho 'rejected'\nexit 1\n",
+    );
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+
+    let error = controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "test",
+            None,
+            &CommitOptions {
+                run_hooks: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(errors::CommitError::CommitHookRejected(output)) if output == "rejected\n"
+    ));
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert!(branches[0].commits.is_empty());
+
+    // hooks are skipped unless requested
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "test",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches[0].commits.len(), 1);
+}
+
+#[tokio::test]
+async fn empty_commit() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    // empty commits are allowed unless rejected explicitly
+    let commit_oid = controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "empty",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches[0].commits.len(), 1);
+    assert_eq!(branches[0].commits[0].id, commit_oid);
+    assert!(branches[0].commits[0].files.is_empty());
+    assert!(controller
+        .list_commit_files(project_id, commit_oid)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn rejected_empty_commit() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    let options = CommitOptions {
+        reject_empty: true,
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        controller
+            .create_commit(project_id, &branch_id, "empty", None, &options)
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::CommitError::NothingToCommit(id)) if *id == branch_id
+    ));
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert!(branches[0].commits.is_empty());
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(project_id, &branch_id, "not empty", None, &options)
+        .await
+        .unwrap();
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches[0].commits.len(), 1);
+}
+
+#[tokio::test]
+async fn trailers() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    let signed_off_by = (
+        "Signed-off-by".to_string(),
+        "Jane Doe <jane@example.com>".to_string(),
+    );
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit one\n\nsome details",
+            None,
+            &CommitOptions {
+                trailers: vec![signed_off_by.clone(), signed_off_by],
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(
+        branches[0].commits[0].description,
+        "commit one\n\nsome details\n\nSigned-off-by: Jane Doe <jane@example.com>"
+    );
+}
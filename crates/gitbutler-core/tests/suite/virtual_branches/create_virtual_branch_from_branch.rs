@@ -24,7 +24,13 @@ async fn integration() {
 
         std::fs::write(repository.path().join("file.txt"), "first\n").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "first", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "first",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
         controller
@@ -53,7 +59,7 @@ async fn integration() {
 
     // checkout a existing remote branch
     let branch_id = controller
-        .create_virtual_branch_from_branch(project_id, &branch_name)
+        .create_virtual_branch_from_branch(project_id, &branch_name, true)
         .await
         .unwrap();
 
@@ -62,7 +68,13 @@ async fn integration() {
         std::fs::write(repository.path().join("file.txt"), "first\nsecond").unwrap();
 
         controller
-            .create_commit(project_id, &branch_id, "second", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "second",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
     }
@@ -154,6 +166,7 @@ async fn no_conflicts() {
         .create_virtual_branch_from_branch(
             project_id,
             &"refs/remotes/origin/branch".parse().unwrap(),
+            true,
         )
         .await
         .unwrap();
@@ -203,6 +216,7 @@ async fn conflicts_with_uncommited() {
         .create_virtual_branch_from_branch(
             project_id,
             &"refs/remotes/origin/branch".parse().unwrap(),
+            true,
         )
         .await
         .unwrap();
@@ -251,7 +265,13 @@ async fn conflicts_with_commited() {
         assert_eq!(branches.len(), 1);
 
         controller
-            .create_commit(project_id, &branches[0].id, "hej", None, false)
+            .create_commit(
+                project_id,
+                &branches[0].id,
+                "hej",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
     };
@@ -262,6 +282,7 @@ async fn conflicts_with_commited() {
         .create_virtual_branch_from_branch(
             project_id,
             &"refs/remotes/origin/branch".parse().unwrap(),
+            true,
         )
         .await
         .unwrap();
@@ -298,6 +319,7 @@ async fn from_default_target() {
             .create_virtual_branch_from_branch(
                 project_id,
                 &"refs/remotes/origin/master".parse().unwrap(),
+                true,
             )
             .await
             .unwrap_err()
@@ -326,6 +348,7 @@ async fn from_non_existent_branch() {
             .create_virtual_branch_from_branch(
                 project_id,
                 &"refs/remotes/origin/branch".parse().unwrap(),
+                true,
             )
             .await
             .unwrap_err()
@@ -369,6 +392,7 @@ async fn from_state_remote_branch() {
         .create_virtual_branch_from_branch(
             project_id,
             &"refs/remotes/origin/branch".parse().unwrap(),
+            true,
         )
         .await
         .unwrap();
@@ -380,3 +404,71 @@ async fn from_state_remote_branch() {
     assert!(branches[0].files.is_empty());
     assert_eq!(branches[0].commits[0].description, "branch commit");
 }
+
+#[tokio::test]
+async fn without_applying() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    // create two remote branches that change the same file
+    for (name, content) in [("one", "first"), ("two", "second")] {
+        let branch_name: git::LocalRefname = format!("refs/heads/{}", name).parse().unwrap();
+        repository.checkout(&branch_name);
+        fs::write(repository.path().join("file.txt"), content).unwrap();
+        repository.commit_all(content);
+        repository.push_branch(&branch_name);
+        repository.checkout(&"refs/heads/master".parse().unwrap());
+    }
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let one_id = controller
+        .create_virtual_branch_from_branch(
+            project_id,
+            &"refs/remotes/origin/one".parse().unwrap(),
+            false,
+        )
+        .await
+        .unwrap();
+    let two_id = controller
+        .create_virtual_branch_from_branch(
+            project_id,
+            &"refs/remotes/origin/two".parse().unwrap(),
+            false,
+        )
+        .await
+        .unwrap();
+
+    // neither branch touched the working directory
+    assert!(!repository.path().join("file.txt").exists());
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 2);
+    assert!(branches.iter().all(|branch| !branch.active));
+
+    controller
+        .apply_virtual_branch(project_id, &one_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(repository.path().join("file.txt")).unwrap(),
+        "first"
+    );
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let one = branches.iter().find(|branch| branch.id == one_id).unwrap();
+    assert!(one.active);
+    assert_eq!(one.commits.len(), 1);
+    let two = branches.iter().find(|branch| branch.id == two_id).unwrap();
+    assert!(!two.active);
+    assert!(!controller
+        .can_apply_virtual_branch(project_id, &two_id)
+        .await
+        .unwrap());
+}
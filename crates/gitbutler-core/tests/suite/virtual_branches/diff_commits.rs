@@ -0,0 +1,103 @@
+use gitbutler_core::git::diff::ChangeType;
+
+use super::*;
+
+#[tokio::test]
+async fn against_grandparent() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    let commit_one_oid = {
+        fs::write(repository.path().join("file.txt"), "one\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    {
+        fs::write(repository.path().join("file.txt"), "one\ntwo\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    let commit_three_oid = {
+        fs::write(repository.path().join("file.txt"), "one\ntwo\nthree\n").unwrap();
+        fs::write(repository.path().join("another file.txt"), "content\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    let mut files = controller
+        .diff_commits(project_id, commit_one_oid, commit_three_oid)
+        .await
+        .unwrap();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    assert_eq!(files.len(), 2);
+
+    assert_eq!(files[0].path, path::PathBuf::from("another file.txt"));
+    assert_eq!(files[0].change_type, ChangeType::Added);
+    assert_eq!(files[0].insertions, 1);
+    assert_eq!(files[0].deletions, 0);
+
+    assert_eq!(files[1].path, path::PathBuf::from("file.txt"));
+    assert_eq!(files[1].change_type, ChangeType::Modified);
+    assert_eq!(files[1].insertions, 2);
+    assert_eq!(files[1].deletions, 0);
+
+    // the other way around, the same changes are undone
+    let mut files = controller
+        .diff_commits(project_id, commit_three_oid, commit_one_oid)
+        .await
+        .unwrap();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    assert_eq!(files.len(), 2);
+
+    assert_eq!(files[0].path, path::PathBuf::from("another file.txt"));
+    assert_eq!(files[0].change_type, ChangeType::Deleted);
+    assert_eq!(files[0].insertions, 0);
+    assert_eq!(files[0].deletions, 1);
+
+    assert_eq!(files[1].path, path::PathBuf::from("file.txt"));
+    assert_eq!(files[1].change_type, ChangeType::Modified);
+    assert_eq!(files[1].insertions, 0);
+    assert_eq!(files[1].deletions, 2);
+}
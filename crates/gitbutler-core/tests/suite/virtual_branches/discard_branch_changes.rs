@@ -0,0 +1,109 @@
+use super::*;
+
+#[tokio::test]
+async fn discards_only_given_paths() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("one.txt"), "one\n").unwrap();
+    fs::write(repository.path().join("two.txt"), "two\n").unwrap();
+    let commit_id = controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("one.txt"), "one changed\n").unwrap();
+    fs::write(repository.path().join("two.txt"), "two changed\n").unwrap();
+
+    controller
+        .discard_branch_changes(
+            project_id,
+            &branch_id,
+            Some(&[path::PathBuf::from("one.txt")]),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(repository.path().join("one.txt")).unwrap(),
+        "one\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repository.path().join("two.txt")).unwrap(),
+        "two changed\n"
+    );
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let branch = branches.into_iter().find(|b| b.id == branch_id).unwrap();
+    assert_eq!(branch.files.len(), 1);
+    assert_eq!(branch.files[0].path.display().to_string(), "two.txt");
+    assert_eq!(branch.commits.len(), 1);
+    assert_eq!(branch.commits[0].id, commit_id);
+}
+
+#[tokio::test]
+async fn discards_everything_without_paths() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("one.txt"), "one\n").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    fs::write(repository.path().join("one.txt"), "one changed\n").unwrap();
+
+    controller
+        .discard_branch_changes(project_id, &branch_id, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(repository.path().join("one.txt")).unwrap(),
+        "one\n"
+    );
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let branch = branches.into_iter().find(|b| b.id == branch_id).unwrap();
+    assert!(branch.files.is_empty());
+    assert_eq!(branch.commits.len(), 1);
+}
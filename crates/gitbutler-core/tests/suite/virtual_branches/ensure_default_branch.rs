@@ -0,0 +1,47 @@
+use super::*;
+
+#[tokio::test]
+async fn creates_branch_once() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let first = controller.ensure_default_branch(project_id).await.unwrap();
+    let second = controller.ensure_default_branch(project_id).await.unwrap();
+    assert_eq!(first, second);
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].id, first);
+}
+
+#[tokio::test]
+async fn returns_existing_branch() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        controller.ensure_default_branch(project_id).await.unwrap(),
+        branch_id
+    );
+}
@@ -0,0 +1,108 @@
+use gitbutler_core::reader;
+
+use super::*;
+
+#[tokio::test]
+async fn skips_commits_not_touching_file() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    let commit_one_oid = {
+        fs::write(repository.path().join("file.txt"), "one\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    {
+        fs::write(repository.path().join("another file.txt"), "content\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    let commit_three_oid = {
+        fs::write(repository.path().join("file.txt"), "one\ntwo\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    let revisions = controller
+        .file_history(project_id, &branch_id, path::Path::new("file.txt"))
+        .await
+        .unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0].commit_id, commit_three_oid);
+    assert_eq!(
+        revisions[0].content,
+        reader::Content::UTF8("one\ntwo\n".to_string())
+    );
+    assert_eq!(revisions[1].commit_id, commit_one_oid);
+    assert_eq!(
+        revisions[1].content,
+        reader::Content::UTF8("one\n".to_string())
+    );
+}
+
+#[tokio::test]
+async fn unknown_branch() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .file_history(
+                project_id,
+                &branch::BranchId::generate(),
+                path::Path::new("file.txt")
+            )
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::FileHistoryError::BranchNotFound(_))
+    ));
+}
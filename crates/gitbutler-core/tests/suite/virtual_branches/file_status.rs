@@ -0,0 +1,68 @@
+use gitbutler_core::virtual_branches::FileStatus;
+
+use super::*;
+
+#[tokio::test]
+async fn reports_how_files_changed() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("modified.txt"), "one\n").unwrap();
+    fs::write(repository.path().join("deleted.txt"), "two\n").unwrap();
+    fs::write(repository.path().join("old.txt"), "three\nfour\n").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("modified.txt"), "one changed\n").unwrap();
+    fs::remove_file(repository.path().join("deleted.txt")).unwrap();
+    fs::write(repository.path().join("added.txt"), "five\n").unwrap();
+    fs::rename(
+        repository.path().join("old.txt"),
+        repository.path().join("new.txt"),
+    )
+    .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let branch = branches.into_iter().find(|b| b.id == branch_id).unwrap();
+    let status = |path: &str| {
+        branch
+            .files
+            .iter()
+            .find(|file| file.path == path::Path::new(path))
+            .map(|file| file.status.clone())
+            .unwrap()
+    };
+
+    assert_eq!(status("modified.txt"), FileStatus::Modified);
+    assert_eq!(status("deleted.txt"), FileStatus::Deleted);
+    assert_eq!(status("added.txt"), FileStatus::Added);
+    assert_eq!(status("old.txt"), FileStatus::Deleted);
+    assert_eq!(
+        status("new.txt"),
+        FileStatus::Renamed {
+            from: path::PathBuf::from("old.txt")
+        }
+    );
+}
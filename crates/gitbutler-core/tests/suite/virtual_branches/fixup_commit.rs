@@ -0,0 +1,122 @@
+use super::*;
+
+#[tokio::test]
+async fn head_into_parent() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    {
+        fs::write(repository.path().join("file one.txt"), "").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    let commit_two_oid = {
+        fs::write(repository.path().join("file two.txt"), "").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    controller
+        .fixup_commit(project_id, &branch_id, commit_two_oid)
+        .await
+        .unwrap();
+
+    let branch = controller
+        .list_virtual_branches(project_id)
+        .await
+        .unwrap()
+        .0
+        .into_iter()
+        .find(|b| b.id == branch_id)
+        .unwrap();
+
+    assert_eq!(branch.commits.len(), 1);
+    assert_eq!(branch.commits[0].description, "commit one");
+    let mut files = branch.commits[0]
+        .files
+        .iter()
+        .map(|file| file.path.clone())
+        .collect::<Vec<_>>();
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            path::PathBuf::from("file one.txt"),
+            path::PathBuf::from("file two.txt"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn root() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    let commit_one_oid = {
+        fs::write(repository.path().join("file one.txt"), "").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    assert!(matches!(
+        controller
+            .fixup_commit(project_id, &branch_id, commit_one_oid)
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::SquashError::CantSquashRootCommit)
+    ));
+}
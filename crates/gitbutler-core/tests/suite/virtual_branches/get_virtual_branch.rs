@@ -0,0 +1,81 @@
+use super::*;
+
+#[tokio::test]
+async fn same_as_listed() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let first_branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("first.txt"), "first\n").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &first_branch_id,
+            "first",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let second_branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                selected_for_changes: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    fs::write(repository.path().join("second.txt"), "second\n").unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 2);
+
+    for branch_id in [first_branch_id, second_branch_id] {
+        let branch = controller
+            .get_virtual_branch(project_id, &branch_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            &branch,
+            branches.iter().find(|b| b.id == branch_id).unwrap()
+        );
+    }
+}
+
+#[tokio::test]
+async fn missing_branch() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .get_virtual_branch(project_id, &branch::BranchId::generate())
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::GetVirtualBranchError::BranchNotFound(_))
+    ));
+}
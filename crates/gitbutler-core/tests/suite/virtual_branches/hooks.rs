@@ -0,0 +1,110 @@
+use super::*;
+
+#[tokio::test]
+async fn rejecting_pre_push_hook_blocks_push() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("name".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    let commit_oid = controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "test",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    git2_hooks::create_hook(
+        &git2::Repository::open(repository.path()).unwrap(),
+        "pre-push",
+        b"#!/bin/sh\necho \"$1\" > .git/pre-push-input\ncat >> .git/pre-push-input\necho 'rejected'\nexit 1\n",
+    );
+
+    let error = controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(errors::PushError::PrePushHookRejected(output)) if output == "rejected\n"
+    ));
+
+    assert_eq!(
+        fs::read_to_string(repository.path().join(".git/pre-push-input")).unwrap(),
+        format!(
+            "origin\nrefs/gitbutler/name {} refs/heads/name {}\n",
+            commit_oid,
+            "0".repeat(40)
+        )
+    );
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert!(branches[0].upstream.is_none());
+}
+
+#[tokio::test]
+async fn failing_post_commit_hook_keeps_commit() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    git2_hooks::create_hook(
+        &git2::Repository::open(repository.path()).unwrap(),
+        git2_hooks::HOOK_POST_COMMIT,
+        b"#!/bin/sh\nexit 1\n",
+    );
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "test",
+            None,
+            &CommitOptions {
+                run_hooks: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches[0].commits.len(), 1);
+}
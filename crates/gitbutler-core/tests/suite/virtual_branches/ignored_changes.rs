@@ -0,0 +1,42 @@
+use super::*;
+
+#[tokio::test]
+async fn lists_ignored_files() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(controller
+        .ignored_changes(project_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    fs::write(
+        repository.path().join(".gitignore"),
+        "ignored.txt\nbuild/\n",
+    )
+    .unwrap();
+    fs::write(repository.path().join("ignored.txt"), "content").unwrap();
+    fs::create_dir(repository.path().join("build")).unwrap();
+    fs::write(repository.path().join("build").join("output"), "content").unwrap();
+    fs::write(repository.path().join("tracked.txt"), "content").unwrap();
+
+    let mut ignored = controller.ignored_changes(project_id).await.unwrap();
+    ignored.sort();
+    assert_eq!(
+        ignored,
+        vec![
+            path::PathBuf::from("build/"),
+            path::PathBuf::from("ignored.txt")
+        ]
+    );
+}
@@ -56,6 +56,57 @@ async fn twice() {
     }
 }
 
+#[tokio::test]
+async fn seeded_initial_tree() {
+    let data_dir = paths::data_dir();
+    let keys = keys::Controller::from_path(&data_dir);
+    let projects = projects::Controller::from_path(&data_dir);
+    let users = users::Controller::from_path(&data_dir);
+    let helper = git::credentials::Helper::from_path(&data_dir);
+
+    let test_project = TestProject::builder()
+        .file("file.txt", "content")
+        .file("dir/nested.txt", "nested")
+        .build();
+
+    assert_eq!(
+        fs::read_to_string(test_project.path().join("file.txt")).unwrap(),
+        "content"
+    );
+    assert_eq!(
+        fs::read_to_string(test_project.path().join("dir/nested.txt")).unwrap(),
+        "nested"
+    );
+    assert_eq!(test_project.remote_file("master", "file.txt"), "content");
+    assert_eq!(
+        test_project.remote_file("master", "dir/nested.txt"),
+        "nested"
+    );
+
+    let controller = Controller::new(
+        data_dir.path().into(),
+        projects.clone(),
+        users,
+        keys,
+        helper,
+    );
+    let project = projects
+        .add(test_project.path())
+        .expect("failed to add project");
+    controller
+        .set_base_branch(&project.id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    // the seeded files are part of the target, so nothing shows up as changed
+    assert!(controller
+        .list_virtual_branches(&project.id)
+        .await
+        .unwrap()
+        .0
+        .is_empty());
+}
+
 #[tokio::test]
 async fn dirty_non_target() {
     // a situation when you initialize project while being on the local verison of the master
@@ -0,0 +1,136 @@
+use super::*;
+
+// commits the current state of the working directory onto master and pushes it, as if someone else
+// updated the base branch.
+fn advance_upstream(repository: &TestProject, change: impl FnOnce(&path::Path)) {
+    repository.checkout(&"refs/heads/master".parse().unwrap());
+    change(repository.path());
+    repository.commit_all("upstream");
+    repository.push_branch(&"refs/heads/master".parse().unwrap());
+    repository.checkout(&"refs/heads/gitbutler/integration".parse().unwrap());
+}
+
+#[tokio::test]
+async fn integrates_only_the_given_branch() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let mut branch_ids = vec![];
+    for file in ["one.txt", "two.txt"] {
+        let branch_id = controller
+            .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+            .await
+            .unwrap();
+        fs::write(repository.path().join(file), "branch\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                file,
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap();
+        controller
+            .unapply_virtual_branch(project_id, &branch_id)
+            .await
+            .unwrap();
+        branch_ids.push(branch_id);
+    }
+
+    // upstream adds the same files, so both branches fall behind when updating
+    advance_upstream(repository, |path| {
+        fs::write(path.join("one.txt"), "upstream\n").unwrap();
+        fs::write(path.join("two.txt"), "upstream\n").unwrap();
+    });
+    controller.update_base_branch(project_id).await.unwrap();
+
+    // and then removes them again, which still conflicts with the changed base of the branches
+    advance_upstream(repository, |path| {
+        fs::remove_file(path.join("one.txt")).unwrap();
+        fs::remove_file(path.join("two.txt")).unwrap();
+    });
+    controller.update_base_branch(project_id).await.unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert!(branches.iter().all(|branch| !branch.base_current));
+
+    controller
+        .integrate_upstream(project_id, &branch_ids[0])
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let integrated = branches.iter().find(|b| b.id == branch_ids[0]).unwrap();
+    assert!(integrated.base_current);
+    assert!(!integrated.active);
+    assert_eq!(integrated.commits.len(), 1);
+    let behind = branches.iter().find(|b| b.id == branch_ids[1]).unwrap();
+    assert!(!behind.base_current);
+}
+
+#[tokio::test]
+async fn conflicting_branch_is_left_untouched() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "branch\n").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    controller
+        .unapply_virtual_branch(project_id, &branch_id)
+        .await
+        .unwrap();
+
+    advance_upstream(repository, |path| {
+        fs::write(path.join("file.txt"), "upstream\n").unwrap();
+    });
+    controller.update_base_branch(project_id).await.unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let head = branches[0].head;
+
+    let error = controller
+        .integrate_upstream(project_id, &branch_id)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(errors::IntegrateUpstreamError::BranchConflicts(id)) if *id == branch_id
+    ));
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches[0].head, head);
+    assert!(!branches[0].base_current);
+}
@@ -0,0 +1,118 @@
+use super::*;
+
+#[tokio::test]
+async fn clean_branch() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch1_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file one.txt"), "content").unwrap();
+    let commit1_oid = controller
+        .create_commit(
+            project_id,
+            &branch1_id,
+            "commit one",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let branch2_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file two.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch2_id,
+            "commit two",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    controller
+        .land_branch(project_id, &branch1_id)
+        .await
+        .unwrap();
+
+    let base = controller
+        .get_base_branch_data(project_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(base.base_sha, commit1_oid);
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].id, branch2_id);
+    assert_eq!(branches[0].commits.len(), 1);
+    assert_eq!(branches[0].commits[0].description, "commit two");
+    assert_eq!(branches[0].commits[0].parent_ids, vec![commit1_oid]);
+    assert!(branches[0].files.is_empty());
+
+    assert!(repository.path().join("file one.txt").exists());
+    assert!(repository.path().join("file two.txt").exists());
+}
+
+#[tokio::test]
+async fn with_uncommitted_changes() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    fs::write(repository.path().join("another file.txt"), "content").unwrap();
+    // picks up the new file
+    controller.list_virtual_branches(project_id).await.unwrap();
+
+    assert!(matches!(
+        controller
+            .land_branch(project_id, &branch_id)
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::LandBranchError::UncommittedChanges(id)) if *id == branch_id
+    ));
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].commits.len(), 1);
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+#[tokio::test]
+async fn matches_list_virtual_branches() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    for name in ["one", "two", "three"] {
+        controller
+            .create_virtual_branch(
+                project_id,
+                &branch::BranchCreateRequest {
+                    name: Some(name.to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+    }
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let unapplied = branches.iter().find(|branch| branch.name == "two").unwrap();
+    controller
+        .unapply_virtual_branch(project_id, &unapplied.id)
+        .await
+        .unwrap();
+
+    let names = controller
+        .list_applied_branch_names(project_id)
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let expected = branches
+        .into_iter()
+        .filter(|branch| branch.active)
+        .map(|branch| (branch.id.to_string(), branch.name))
+        .collect::<Vec<_>>();
+    assert_eq!(names, expected);
+    assert_eq!(
+        names
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["one", "three"]
+    );
+}
@@ -0,0 +1,70 @@
+use gitbutler_core::git::diff::ChangeType;
+
+use super::*;
+
+#[tokio::test]
+async fn lists_files_changed_by_commit() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    {
+        fs::write(repository.path().join("file.txt"), "one\ntwo\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    let commit_two_oid = {
+        fs::write(repository.path().join("file.txt"), "one\nthree\nfour\n").unwrap();
+        fs::write(repository.path().join("another file.txt"), "content\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    let mut files = controller
+        .list_commit_files(project_id, commit_two_oid)
+        .await
+        .unwrap();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    assert_eq!(files.len(), 2);
+
+    assert_eq!(files[0].path, path::PathBuf::from("another file.txt"));
+    assert_eq!(files[0].change_type, ChangeType::Added);
+    assert_eq!(files[0].insertions, 1);
+    assert_eq!(files[0].deletions, 0);
+
+    assert_eq!(files[1].path, path::PathBuf::from("file.txt"));
+    assert_eq!(files[1].change_type, ChangeType::Modified);
+    assert_eq!(files[1].insertions, 2);
+    assert_eq!(files[1].deletions, 1);
+}
@@ -0,0 +1,142 @@
+use gitbutler_core::reader::Content;
+
+use super::*;
+
+#[tokio::test]
+async fn no_conflicts() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(controller
+        .list_conflicts(project_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn reports_conflicted_path() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    // make sure we have an undiscovered commit in the remote branch
+    {
+        fs::write(repository.path().join("file.txt"), "first").unwrap();
+        let first_commit_oid = repository.commit_all("first");
+        fs::write(repository.path().join("file.txt"), "second").unwrap();
+        repository.commit_all("second");
+        repository.push();
+        repository.reset_hard(Some(first_commit_oid));
+    }
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = {
+        // make a conflicting branch, and stash it
+        fs::write(repository.path().join("file.txt"), "conflict").unwrap();
+
+        let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+        assert_eq!(branches.len(), 1);
+
+        controller
+            .unapply_virtual_branch(project_id, &branches[0].id)
+            .await
+            .unwrap();
+
+        branches[0].id
+    };
+
+    // update base branch, then apply the branch, causing a conflict
+    controller.update_base_branch(project_id).await.unwrap();
+    controller
+        .apply_virtual_branch(project_id, &branch_id)
+        .await
+        .unwrap();
+
+    let conflicts = controller.list_conflicts(project_id).await.unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].path, path::PathBuf::from("file.txt"));
+}
+
+#[tokio::test]
+async fn conflict_versions() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    // make sure we have an undiscovered commit in the remote branch
+    {
+        fs::write(repository.path().join("file.txt"), "first").unwrap();
+        let first_commit_oid = repository.commit_all("first");
+        fs::write(repository.path().join("file.txt"), "second").unwrap();
+        repository.commit_all("second");
+        repository.push();
+        repository.reset_hard(Some(first_commit_oid));
+    }
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = {
+        // make a conflicting branch, and stash it
+        fs::write(repository.path().join("file.txt"), "conflict").unwrap();
+
+        let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+        assert_eq!(branches.len(), 1);
+
+        controller
+            .unapply_virtual_branch(project_id, &branches[0].id)
+            .await
+            .unwrap();
+
+        branches[0].id
+    };
+
+    assert!(matches!(
+        controller
+            .conflict_versions(project_id, path::Path::new("file.txt"))
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::ConflictVersionsError::NotConflicted(_))
+    ));
+
+    // update base branch, then apply the branch, causing a conflict
+    controller.update_base_branch(project_id).await.unwrap();
+    controller
+        .apply_virtual_branch(project_id, &branch_id)
+        .await
+        .unwrap();
+
+    let versions = controller
+        .conflict_versions(project_id, path::Path::new("file.txt"))
+        .await
+        .unwrap();
+    assert_eq!(versions.base, Some(Content::UTF8("first".to_string())));
+    assert_eq!(versions.ours, Some(Content::UTF8("conflict".to_string())));
+    assert_eq!(versions.theirs, Some(Content::UTF8("second".to_string())));
+    let merged = versions.merged.as_str().unwrap();
+    assert!(merged.contains("<<<<<<<"));
+    assert!(merged.contains("conflict"));
+    assert!(merged.contains("second"));
+}
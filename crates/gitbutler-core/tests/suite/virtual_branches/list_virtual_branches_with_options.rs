@@ -0,0 +1,127 @@
+use gitbutler_core::virtual_branches::ListOptions;
+
+use super::*;
+
+#[tokio::test]
+async fn without_files_skips_the_working_directory() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+
+    let (branches, _, skipped_files) = controller
+        .list_virtual_branches_with_options(
+            project_id,
+            &ListOptions {
+                include_files: false,
+                ..ListOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].id, branch_id);
+    assert!(branches[0].files.is_empty());
+    assert!(skipped_files.is_empty());
+    // the change was not diffed, so it's not owned by the branch yet
+    assert!(branches[0].ownership.claims.is_empty());
+
+    let (branches, _, _) = controller
+        .list_virtual_branches_with_options(project_id, &ListOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].files.len(), 1);
+    assert_eq!(branches[0].ownership.claims.len(), 1);
+}
+
+#[tokio::test]
+async fn with_commit_files() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "first",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "changed content").unwrap();
+    fs::write(repository.path().join("another.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "second",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert!(branches[0]
+        .commits
+        .iter()
+        .all(|commit| commit.changed_files.is_none()));
+
+    let (branches, _, _) = controller
+        .list_virtual_branches_with_options(
+            project_id,
+            &ListOptions {
+                include_commit_files: true,
+                ..ListOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(branches[0].commits.len(), 2);
+    for commit in &branches[0].commits {
+        assert_eq!(
+            commit.changed_files.as_ref(),
+            Some(
+                &controller
+                    .list_commit_files(project_id, commit.id)
+                    .await
+                    .unwrap()
+            )
+        );
+    }
+    assert_eq!(
+        branches[0].commits[0].changed_files.as_ref().unwrap().len(),
+        2
+    );
+}
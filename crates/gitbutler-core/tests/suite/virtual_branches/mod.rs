@@ -4,7 +4,7 @@ use gitbutler_core::{
     git, keys,
     projects::{self, ProjectId},
     users,
-    virtual_branches::{branch, errors, Controller},
+    virtual_branches::{branch, errors, CommitOptions, Controller},
 };
 use tempfile::TempDir;
 
@@ -55,25 +55,67 @@ impl Default for Test {
     }
 }
 
+mod abort_integration;
 mod amend;
+mod apply_updates;
 mod apply_virtual_branch;
+mod archive_branch;
+mod base_branch_behind;
+mod base_branch_exists;
+mod branch_upstream;
 mod cherry_pick;
+mod commit_author;
+mod commit_graph;
+mod concurrent_reads;
 mod create_commit;
 mod create_virtual_branch_from_branch;
 mod delete_virtual_branch;
+mod diff_commits;
+mod discard_branch_changes;
+mod ensure_default_branch;
 mod fetch_from_target;
+mod file_history;
+mod file_status;
+mod fixup_commit;
+mod get_virtual_branch;
+mod hooks;
+mod ignored_changes;
 mod init;
+mod integrate_upstream;
+mod land_branch;
+mod list_applied_branch_names;
+mod list_commit_files;
+mod list_conflicts;
+mod list_virtual_branches_with_options;
 mod move_commit_to_vbranch;
+mod project_head;
+mod pull_requests;
+mod push_all;
+mod push_virtual_branch;
+mod reader_at_commit;
+mod reapply_branches;
+mod rebase_branch;
 mod references;
+mod reorder_virtual_branches;
 mod reset_virtual_branch;
 mod selected_for_changes;
 mod set_base_branch;
+mod set_push_remote;
+mod skipped_files;
+mod snapshot;
+mod split_to_new_branch;
 mod squash;
+mod stream_virtual_branches;
 mod unapply;
+mod unapply_all;
 mod unapply_ownership;
+mod uncommitted_stats;
+mod unset_base_branch;
 mod update_base_branch;
 mod update_commit_message;
 mod upstream;
+mod validate_branch_name;
+mod workspace_status;
 
 #[tokio::test]
 async fn resolve_conflict_flow() {
@@ -150,7 +192,13 @@ async fn resolve_conflict_flow() {
         // can't commit conflicts
         assert!(matches!(
             controller
-                .create_commit(project_id, &branch1_id, "commit conflicts", None, false)
+                .create_commit(
+                    project_id,
+                    &branch1_id,
+                    "commit conflicts",
+                    None,
+                    &CommitOptions::default()
+                )
                 .await
                 .unwrap_err()
                 .downcast_ref(),
@@ -162,7 +210,13 @@ async fn resolve_conflict_flow() {
         // fixing the conflict removes conflicted mark
         fs::write(repository.path().join("file.txt"), "resolved").unwrap();
         let commit_oid = controller
-            .create_commit(project_id, &branch1_id, "resolution", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "resolution",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
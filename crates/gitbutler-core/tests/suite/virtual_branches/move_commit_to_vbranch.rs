@@ -29,7 +29,13 @@ async fn no_diffs() {
     let source_branch_id = branches[0].id;
 
     let commit_oid = controller
-        .create_commit(project_id, &source_branch_id, "commit", None, false)
+        .create_commit(
+            project_id,
+            &source_branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
         .await
         .unwrap();
 
@@ -89,7 +95,13 @@ async fn diffs_on_source_branch() {
     let source_branch_id = branches[0].id;
 
     let commit_oid = controller
-        .create_commit(project_id, &source_branch_id, "commit", None, false)
+        .create_commit(
+            project_id,
+            &source_branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
         .await
         .unwrap();
 
@@ -155,7 +167,13 @@ async fn diffs_on_target_branch() {
     let source_branch_id = branches[0].id;
 
     let commit_oid = controller
-        .create_commit(project_id, &source_branch_id, "commit", None, false)
+        .create_commit(
+            project_id,
+            &source_branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
         .await
         .unwrap();
 
@@ -227,7 +245,13 @@ async fn locked_hunks_on_source_branch() {
     let source_branch_id = branches[0].id;
 
     let commit_oid = controller
-        .create_commit(project_id, &source_branch_id, "commit", None, false)
+        .create_commit(
+            project_id,
+            &source_branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
         .await
         .unwrap();
 
@@ -270,7 +294,13 @@ async fn no_commit() {
     let source_branch_id = branches[0].id;
 
     controller
-        .create_commit(project_id, &source_branch_id, "commit", None, false)
+        .create_commit(
+            project_id,
+            &source_branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
         .await
         .unwrap();
 
@@ -315,7 +345,13 @@ async fn no_branch() {
     let source_branch_id = branches[0].id;
 
     let commit_oid = controller
-        .create_commit(project_id, &source_branch_id, "commit", None, false)
+        .create_commit(
+            project_id,
+            &source_branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
         .await
         .unwrap();
 
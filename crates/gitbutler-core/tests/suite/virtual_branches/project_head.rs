@@ -0,0 +1,30 @@
+use super::*;
+
+#[tokio::test]
+async fn detects_external_head_change() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let expected_head = controller.project_head(project_id).await.unwrap();
+    assert_eq!(
+        controller.project_head(project_id).await.unwrap(),
+        expected_head
+    );
+
+    // someone commits from a terminal
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    let external_commit = repository.commit_all("external");
+
+    let head = controller.project_head(project_id).await.unwrap();
+    assert_ne!(head, expected_head);
+    assert_eq!(head, external_commit);
+}
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use gitbutler_core::{
+    projects::ProjectId,
+    virtual_branches::{PullRequest, RemoteProvider},
+};
+
+use super::*;
+
+struct FakeProvider(Vec<PullRequest>);
+
+#[async_trait]
+impl RemoteProvider for FakeProvider {
+    async fn list_pull_requests(&self, _project_id: ProjectId) -> anyhow::Result<Vec<PullRequest>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[tokio::test]
+async fn without_provider() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    assert!(controller
+        .pull_requests(project_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn delegates_to_provider() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    let pull_request = PullRequest {
+        number: 1,
+        title: "title".to_string(),
+        url: "https://example.com/pulls/1".to_string(),
+        source_branch: "name".to_string(),
+        draft: false,
+    };
+    controller.set_remote_provider(FakeProvider(vec![pull_request.clone()]));
+
+    assert_eq!(
+        controller.pull_requests(project_id).await.unwrap(),
+        vec![pull_request]
+    );
+}
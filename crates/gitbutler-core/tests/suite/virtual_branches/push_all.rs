@@ -0,0 +1,70 @@
+use super::*;
+
+#[tokio::test]
+async fn pushes_every_applied_branch() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let mut branch_ids = vec![];
+    for (name, file) in [("one", "one.txt"), ("two", "two.txt")] {
+        let branch_id = controller
+            .create_virtual_branch(
+                project_id,
+                &branch::BranchCreateRequest {
+                    name: Some(name.to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        fs::write(repository.path().join(file), name).unwrap();
+        controller
+            .update_virtual_branch(
+                project_id,
+                branch::BranchUpdateRequest {
+                    id: branch_id,
+                    ownership: Some(format!("{}:1-2", file).parse().unwrap()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                name,
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap();
+        branch_ids.push(branch_id);
+    }
+
+    let results = controller.push_all(project_id, false, None).await.unwrap();
+    assert_eq!(results.len(), 2);
+    for (branch_id, result) in &results {
+        assert!(branch_ids.contains(branch_id));
+        assert!(result.is_ok(), "{}: {:?}", branch_id, result);
+    }
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    for branch_id in branch_ids {
+        let branch = branches.iter().find(|b| b.id == branch_id).unwrap();
+        let upstream_name = branch.upstream_name.as_deref().unwrap();
+        assert_eq!(
+            repository.remote_branch_head("origin", upstream_name),
+            branch.head
+        );
+    }
+}
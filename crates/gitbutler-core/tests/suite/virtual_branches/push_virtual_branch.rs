@@ -0,0 +1,353 @@
+use gitbutler_core::{project_repository, virtual_branches::VirtualBranchesHandle};
+
+use super::*;
+
+fn remote_branch_names(repository: &TestProject) -> Vec<String> {
+    repository
+        .remote_references()
+        .into_iter()
+        .filter_map(|reference| reference.name())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn deletes_old_remote_branch_after_rename() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("old name".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+    assert!(remote_branch_names(repository).contains(&"refs/heads/old-name".to_string()));
+
+    controller
+        .update_virtual_branch(
+            project_id,
+            branch::BranchUpdateRequest {
+                id: branch_id,
+                name: Some("new name".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    let names = remote_branch_names(repository);
+    assert!(names.contains(&"refs/heads/new-name".to_string()));
+    assert!(!names.contains(&"refs/heads/old-name".to_string()));
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches[0].upstream_name.as_deref(), Some("new-name"));
+    assert!(!repository
+        .references()
+        .into_iter()
+        .filter_map(|reference| reference.name())
+        .any(|name| name.to_string() == "refs/remotes/origin/old-name"));
+}
+
+#[tokio::test]
+async fn keeps_upstream_not_derived_from_name() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("name".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    controller
+        .update_virtual_branch(
+            project_id,
+            branch::BranchUpdateRequest {
+                id: branch_id,
+                upstream: Some("custom".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    controller
+        .update_virtual_branch(
+            project_id,
+            branch::BranchUpdateRequest {
+                id: branch_id,
+                name: Some("renamed".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    let names = remote_branch_names(repository);
+    assert!(names.contains(&"refs/heads/custom".to_string()));
+    assert!(!names.contains(&"refs/heads/renamed".to_string()));
+}
+
+#[tokio::test]
+async fn rejects_non_fast_forward() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    let base_branch = controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("name".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit one",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    // replace the pushed commit, so that the remote branch is no longer an ancestor
+    controller
+        .reset_virtual_branch(project_id, &branch_id, base_branch.base_sha)
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "another content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit two",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .push_virtual_branch(project_id, &branch_id, false, None)
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::PushError::Remote(
+            project_repository::RemoteError::NonFastForward { branch }
+        )) if branch == "name"
+    ));
+}
+
+#[tokio::test]
+async fn pushes_to_explicit_remote_name() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("my branch".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .set_branch_remote_name(project_id, &branch_id, "feature/ABC 123")
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::SetBranchRemoteNameError::InvalidName(_))
+    ));
+
+    controller
+        .set_branch_remote_name(project_id, &branch_id, "feature/ABC-123")
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    let names = remote_branch_names(repository);
+    assert!(names.contains(&"refs/heads/feature/ABC-123".to_string()));
+    assert!(!names.contains(&"refs/heads/my-branch".to_string()));
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(
+        branches[0].upstream_name.as_deref(),
+        Some("feature/ABC-123")
+    );
+}
+
+#[tokio::test]
+async fn changing_remote_name_forgets_pushed_head() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        projects,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    let state = VirtualBranchesHandle::new(&projects.get(project_id).unwrap().gb_dir());
+    let upstream_head = || state.get_branch(&branch_id).unwrap().unwrap().upstream_head;
+    assert!(upstream_head().is_some());
+
+    // setting the same name again keeps it
+    let upstream_name = state
+        .get_branch(&branch_id)
+        .unwrap()
+        .unwrap()
+        .upstream
+        .unwrap()
+        .branch()
+        .to_string();
+    controller
+        .set_branch_remote_name(project_id, &branch_id, &upstream_name)
+        .await
+        .unwrap();
+    assert!(upstream_head().is_some());
+
+    controller
+        .set_branch_remote_name(project_id, &branch_id, "feature/ABC-123")
+        .await
+        .unwrap();
+    assert_eq!(upstream_head(), None);
+}
@@ -0,0 +1,72 @@
+use gitbutler_core::reader;
+
+use super::*;
+
+#[tokio::test]
+async fn lists_files_at_commit() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    let commit_oid = {
+        fs::write(repository.path().join("file.txt"), "content\n").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    // changes made after the commit are not visible through the reader
+    fs::write(repository.path().join("another file.txt"), "content\n").unwrap();
+
+    let owned_reader = controller
+        .reader_at_commit(project_id, commit_oid)
+        .await
+        .unwrap();
+    assert_eq!(owned_reader.commit_id(), commit_oid);
+
+    let reader = owned_reader.reader().unwrap();
+    let files = reader.list_files("").unwrap();
+    assert!(files.contains(&path::PathBuf::from("file.txt")));
+    assert!(!files.contains(&path::PathBuf::from("another file.txt")));
+    assert_eq!(
+        reader.read("file.txt").unwrap(),
+        reader::Content::UTF8("content\n".to_string())
+    );
+}
+
+#[tokio::test]
+async fn unknown_commit() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    assert!(controller
+        .reader_at_commit(
+            project_id,
+            "0123456789012345678901234567890123456789".parse().unwrap()
+        )
+        .await
+        .is_err());
+}
@@ -0,0 +1,114 @@
+use super::*;
+
+async fn two_unapplied_branches(
+    Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    }: &Test,
+) -> Vec<branch::BranchId> {
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file1.txt"), "one").unwrap();
+    controller.list_virtual_branches(project_id).await.unwrap();
+
+    controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                selected_for_changes: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file2.txt"), "two").unwrap();
+    controller.list_virtual_branches(project_id).await.unwrap();
+
+    let unapplied = controller.unapply_all(project_id).await.unwrap();
+    assert_eq!(unapplied.len(), 2);
+    unapplied
+}
+
+#[tokio::test]
+async fn round_trip_with_unapply_all() {
+    let test = Test::default();
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &test;
+
+    let unapplied = two_unapplied_branches(&test).await;
+    assert!(!repository.path().join("file1.txt").exists());
+    assert!(!repository.path().join("file2.txt").exists());
+
+    controller
+        .reapply_branches(project_id, &unapplied)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(repository.path().join("file1.txt")).unwrap(),
+        "one"
+    );
+    assert_eq!(
+        fs::read_to_string(repository.path().join("file2.txt")).unwrap(),
+        "two"
+    );
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 2);
+    assert!(branches.iter().all(|branch| branch.active));
+}
+
+#[tokio::test]
+async fn stops_at_first_conflicting_branch() {
+    let test = Test::default();
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &test;
+
+    let unapplied = two_unapplied_branches(&test).await;
+
+    // the second branch now conflicts with the working directory
+    fs::write(repository.path().join("file2.txt"), "conflict").unwrap();
+
+    assert!(controller
+        .reapply_branches(project_id, &unapplied)
+        .await
+        .is_err());
+
+    assert_eq!(
+        fs::read_to_string(repository.path().join("file1.txt")).unwrap(),
+        "one"
+    );
+    assert_eq!(
+        fs::read_to_string(repository.path().join("file2.txt")).unwrap(),
+        "conflict"
+    );
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let first = branches
+        .iter()
+        .find(|branch| branch.id == unapplied[0])
+        .unwrap();
+    let second = branches
+        .iter()
+        .find(|branch| branch.id == unapplied[1])
+        .unwrap();
+    assert!(first.active);
+    assert!(!second.active);
+}
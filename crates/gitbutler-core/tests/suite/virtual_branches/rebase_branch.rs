@@ -0,0 +1,134 @@
+use super::*;
+
+#[tokio::test]
+async fn onto_sibling() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch1_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file one.txt"), "content").unwrap();
+    let commit1_oid = controller
+        .create_commit(
+            project_id,
+            &branch1_id,
+            "commit one",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let branch2_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file two.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch2_id,
+            "commit two",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    controller
+        .rebase_branch(project_id, &branch2_id, commit1_oid)
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let branch2 = branches.iter().find(|b| b.id == branch2_id).unwrap();
+    assert_eq!(branch2.commits.len(), 2);
+    assert_eq!(branch2.commits[0].description, "commit two");
+    assert_eq!(branch2.commits[0].parent_ids, vec![commit1_oid]);
+    assert_eq!(branch2.commits[1].id, commit1_oid);
+
+    assert!(repository.path().join("file one.txt").exists());
+    assert!(repository.path().join("file two.txt").exists());
+}
+
+#[tokio::test]
+async fn conflicting() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch1_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "one").unwrap();
+    let commit1_oid = controller
+        .create_commit(
+            project_id,
+            &branch1_id,
+            "commit one",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+    controller
+        .unapply_virtual_branch(project_id, &branch1_id)
+        .await
+        .unwrap();
+
+    let branch2_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "two").unwrap();
+    let commit2_oid = controller
+        .create_commit(
+            project_id,
+            &branch2_id,
+            "commit two",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .rebase_branch(project_id, &branch2_id, commit1_oid)
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::RebaseBranchError::BranchConflicts(id)) if *id == branch2_id
+    ));
+
+    let branch2 = controller
+        .get_virtual_branch(project_id, &branch2_id)
+        .await
+        .unwrap();
+    assert_eq!(branch2.commits.len(), 1);
+    assert_eq!(branch2.commits[0].id, commit2_oid);
+    assert_eq!(
+        fs::read_to_string(repository.path().join("file.txt")).unwrap(),
+        "two"
+    );
+}
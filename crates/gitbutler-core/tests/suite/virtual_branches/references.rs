@@ -238,7 +238,13 @@ mod push_virtual_branch {
         fs::write(repository.path().join("file.txt"), "content").unwrap();
 
         controller
-            .create_commit(project_id, &branch1_id, "test", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "test",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
         controller
@@ -291,7 +297,13 @@ mod push_virtual_branch {
                 .unwrap();
             fs::write(repository.path().join("file.txt"), "content").unwrap();
             controller
-                .create_commit(project_id, &branch1_id, "test", None, false)
+                .create_commit(
+                    project_id,
+                    &branch1_id,
+                    "test",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
             controller
@@ -328,7 +340,13 @@ mod push_virtual_branch {
                 .unwrap();
             fs::write(repository.path().join("file.txt"), "updated content").unwrap();
             controller
-                .create_commit(project_id, &branch2_id, "test", None, false)
+                .create_commit(
+                    project_id,
+                    &branch2_id,
+                    "test",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
             controller
@@ -28,7 +28,13 @@ async fn to_head() {
 
         // commit changes
         let oid = controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
@@ -90,7 +96,13 @@ async fn to_target() {
 
         // commit changes
         let oid = controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
@@ -150,7 +162,13 @@ async fn to_commit() {
         fs::write(repository.path().join("file.txt"), "content").unwrap();
 
         let oid = controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
@@ -173,7 +191,13 @@ async fn to_commit() {
         fs::write(repository.path().join("file.txt"), "more content").unwrap();
 
         let second_commit_oid = controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
@@ -234,7 +258,13 @@ async fn to_non_existing() {
 
         // commit changes
         let oid = controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
@@ -37,6 +37,35 @@ mod error {
             Some(errors::SetBaseBranchError::BranchNotFound(_))
         ));
     }
+
+    #[tokio::test]
+    async fn remote_not_configured() {
+        let Test {
+            repository,
+            project_id,
+            controller,
+            ..
+        } = &Test::default();
+
+        // a tracking branch left behind by a remote that was removed
+        let repo = git2::Repository::open(repository.path()).unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/removed/master", head, false, "")
+            .unwrap();
+
+        assert!(matches!(
+            controller
+                .set_base_branch(
+                    project_id,
+                    &git::RemoteRefname::from_str("refs/remotes/removed/master").unwrap(),
+                )
+                .await
+                .unwrap_err()
+                .downcast_ref(),
+            Some(errors::SetBaseBranchError::InvalidTargetBranch(name))
+                if name.to_string() == "refs/remotes/removed/master"
+        ));
+    }
 }
 
 mod go_back_to_integration {
@@ -71,7 +100,13 @@ mod go_back_to_integration {
 
         std::fs::write(repository.path().join("another file.txt"), "content").unwrap();
         controller
-            .create_commit(project_id, &vbranch_id, "one", None, false)
+            .create_commit(
+                project_id,
+                &vbranch_id,
+                "one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap();
 
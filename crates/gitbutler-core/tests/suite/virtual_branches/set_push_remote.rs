@@ -0,0 +1,91 @@
+use super::*;
+
+#[tokio::test]
+async fn pushes_to_push_remote() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    let fork_tmp = tempfile::tempdir().unwrap();
+    let fork = git2::Repository::init_bare(fork_tmp.path()).unwrap();
+    git2::Repository::open(repository.path())
+        .unwrap()
+        .remote("fork", fork_tmp.path().to_str().unwrap())
+        .unwrap();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+    controller
+        .set_push_remote(project_id, "fork")
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("feature".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    let commit_id = controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fork.find_reference("refs/heads/feature")
+            .unwrap()
+            .target()
+            .unwrap()
+            .to_string(),
+        commit_id.to_string()
+    );
+    assert!(!repository
+        .remote_references()
+        .into_iter()
+        .filter_map(|reference| reference.name())
+        .any(|name| name.to_string() == "refs/heads/feature"));
+
+    // the base branch still comes from the target's remote
+    let base = controller.get_base_branch_data(project_id).await.unwrap();
+    assert_eq!(base.unwrap().remote_name, "origin");
+}
+
+#[tokio::test]
+async fn missing_remote() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(controller
+        .set_push_remote(project_id, "missing")
+        .await
+        .is_err());
+}
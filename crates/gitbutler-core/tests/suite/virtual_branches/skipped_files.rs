@@ -0,0 +1,151 @@
+use std::io::Write;
+
+use gitbutler_core::{
+    project_repository::{self, conflicts},
+    virtual_branches::{SkipReason, SkippedFile},
+};
+
+use super::*;
+
+#[tokio::test]
+async fn large_file_is_listed_as_large() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    std::fs::write(repository.path().join("file.txt"), "content").unwrap();
+    let mut large_file = std::fs::File::create(repository.path().join("large.bin")).unwrap();
+    large_file.write_all(&[0xff, 0x00]).unwrap();
+    large_file.set_len(50_000_001).unwrap();
+
+    let (branches, _, skipped_files) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert!(skipped_files.is_empty());
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].files.len(), 2);
+    let large = branches[0]
+        .files
+        .iter()
+        .find(|file| file.path == path::Path::new("large.bin"))
+        .unwrap();
+    assert!(large.large);
+    assert!(large.binary);
+    assert_eq!(large.size_delta_bytes, Some(50_000_001));
+}
+
+#[test]
+fn large_file_is_hashed_without_writing_its_blob() {
+    let repository = TestProject::default();
+
+    let mut large_file = std::fs::File::create(repository.path().join("large.bin")).unwrap();
+    large_file.write_all(&[0xff, 0x00]).unwrap();
+    large_file.set_len(50_000_001).unwrap();
+
+    let git_repository = git::Repository::open(repository.path()).unwrap();
+    let head = git_repository.head().unwrap().target().unwrap();
+    let diffs = git::diff::workdir(&git_repository, &head, 3).unwrap();
+    let hunks = diffs[path::Path::new("large.bin")].hunks.as_ref().unwrap();
+    assert_eq!(hunks.len(), 1);
+
+    let blob_id =
+        git2::Oid::hash_file(git2::ObjectType::Blob, repository.path().join("large.bin")).unwrap();
+    assert_eq!(hunks[0].diff, blob_id.to_string());
+    assert!(!git2::Repository::open(repository.path())
+        .unwrap()
+        .odb()
+        .unwrap()
+        .exists(blob_id));
+}
+
+#[tokio::test]
+async fn modified_large_file_is_listed_as_large() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    let content = "line\n".repeat(11_000_000);
+    fs::write(repository.path().join("large.txt"), &content).unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "add large file",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    fs::write(
+        repository.path().join("large.txt"),
+        format!("{content}{}", "more\n".repeat(10)),
+    )
+    .unwrap();
+
+    let (branches, _, skipped_files) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert!(skipped_files.is_empty());
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].files.len(), 1);
+    let file = &branches[0].files[0];
+    assert_eq!(file.path, path::Path::new("large.txt"));
+    assert!(file.large);
+    assert!(!file.binary);
+    assert_eq!(file.size_delta_bytes, Some(50));
+}
+
+#[tokio::test]
+async fn conflicted_large_file_is_skipped() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        projects,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    std::fs::write(repository.path().join("file.txt"), "content").unwrap();
+    let mut large_file = std::fs::File::create(repository.path().join("large.bin")).unwrap();
+    large_file.write_all(&[0xff, 0x00]).unwrap();
+    large_file.set_len(50_000_001).unwrap();
+
+    let project = projects.get(project_id).unwrap();
+    let project_repository = project_repository::Repository::open(&project).unwrap();
+    conflicts::mark(&project_repository, ["large.bin"], None).unwrap();
+
+    let (branches, _, skipped_files) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(
+        skipped_files,
+        vec![SkippedFile {
+            path: path::PathBuf::from("large.bin"),
+            reason: SkipReason::Conflicted,
+        }]
+    );
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].files.len(), 1);
+    assert_eq!(branches[0].files[0].path, path::Path::new("file.txt"));
+}
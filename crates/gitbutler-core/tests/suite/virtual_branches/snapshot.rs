@@ -0,0 +1,200 @@
+use super::*;
+
+use gitbutler_core::virtual_branches::{snapshot, SnapshotId, VirtualBranch};
+
+#[tokio::test]
+async fn restore_undoes_changes_since_snapshot() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("one".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let summary = |branches: Vec<VirtualBranch>| {
+        branches
+            .into_iter()
+            .map(|branch| {
+                (
+                    branch.id,
+                    branch.name,
+                    branch.active,
+                    branch
+                        .commits
+                        .into_iter()
+                        .map(|commit| commit.id)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    let before = summary(branches);
+
+    let snapshot_id = controller.create_snapshot(project_id).await.unwrap();
+
+    controller
+        .update_virtual_branch(
+            project_id,
+            branch::BranchUpdateRequest {
+                id: branch_id,
+                name: Some("renamed".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("two".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_ne!(summary(branches), before);
+
+    controller
+        .restore_snapshot(project_id, &snapshot_id)
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(summary(branches), before);
+}
+
+#[tokio::test]
+async fn restore_unknown_snapshot() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let error = controller
+        .restore_snapshot(project_id, &SnapshotId::generate())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(errors::RestoreSnapshotError::SnapshotNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn prune_deletes_oldest_snapshots() {
+    let Test {
+        project_id,
+        controller,
+        projects,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let mut snapshot_ids = Vec::new();
+    for _ in 0..4 {
+        snapshot_ids.push(controller.create_snapshot(project_id).await.unwrap());
+    }
+
+    assert_eq!(controller.prune_snapshots(project_id, 2).await.unwrap(), 2);
+
+    let snapshots_dir = projects.get(project_id).unwrap().gb_dir().join("snapshots");
+    for (i, snapshot_id) in snapshot_ids.iter().enumerate() {
+        assert_eq!(
+            snapshot_exists(&snapshots_dir, snapshot_id),
+            i >= 2,
+            "snapshot {i}"
+        );
+    }
+    assert!(matches!(
+        controller
+            .restore_snapshot(project_id, &snapshot_ids[0])
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::RestoreSnapshotError::SnapshotNotFound(_))
+    ));
+    controller
+        .restore_snapshot(project_id, &snapshot_ids[3])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn snapshots_are_capped() {
+    let Test {
+        project_id,
+        controller,
+        projects,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let first = controller.create_snapshot(project_id).await.unwrap();
+    for _ in 0..snapshot::MAX_SNAPSHOTS {
+        controller.create_snapshot(project_id).await.unwrap();
+    }
+
+    let snapshots_dir = projects.get(project_id).unwrap().gb_dir().join("snapshots");
+    assert!(!snapshot_exists(&snapshots_dir, &first));
+    assert_eq!(
+        fs::read_dir(&snapshots_dir).unwrap().count(),
+        snapshot::MAX_SNAPSHOTS
+    );
+}
+
+fn snapshot_exists(snapshots_dir: &path::Path, id: &SnapshotId) -> bool {
+    let suffix = format!("-{}.toml", id);
+    fs::read_dir(snapshots_dir).unwrap().any(|entry| {
+        entry
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .ends_with(&suffix)
+    })
+}
@@ -0,0 +1,78 @@
+use super::*;
+
+#[tokio::test]
+async fn moves_file_to_new_branch() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("a.txt"), "a\n").unwrap();
+    fs::write(repository.path().join("b.txt"), "b\n").unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].files.len(), 2);
+
+    let new_branch_id = controller
+        .split_to_new_branch(
+            project_id,
+            &branch_id,
+            &["b.txt:1-2".parse().unwrap()],
+            "split",
+        )
+        .await
+        .unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 2);
+
+    let branch = branches.iter().find(|b| b.id == branch_id).unwrap();
+    assert_eq!(branch.files.len(), 1);
+    assert_eq!(branch.files[0].path.display().to_string(), "a.txt");
+
+    let new_branch = branches.iter().find(|b| b.id == new_branch_id).unwrap();
+    assert_eq!(new_branch.name, "split");
+    assert_eq!(new_branch.files.len(), 1);
+    assert_eq!(new_branch.files[0].path.display().to_string(), "b.txt");
+}
+
+#[tokio::test]
+async fn from_missing_branch() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .split_to_new_branch(
+                project_id,
+                &branch::BranchId::generate(),
+                &["b.txt:1-2".parse().unwrap()],
+                "split",
+            )
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::SplitBranchError::BranchNotFound(_))
+    ));
+}
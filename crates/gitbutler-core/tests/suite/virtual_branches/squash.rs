@@ -22,7 +22,13 @@ async fn head() {
     {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -30,7 +36,13 @@ async fn head() {
     {
         fs::write(repository.path().join("file two.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit two", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -38,7 +50,13 @@ async fn head() {
     {
         fs::write(repository.path().join("file three.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit three", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -46,7 +64,13 @@ async fn head() {
     let commit_four_oid = {
         fs::write(repository.path().join("file four.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit four", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit four",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -98,7 +122,13 @@ async fn middle() {
     {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -106,7 +136,13 @@ async fn middle() {
     let commit_two_oid = {
         fs::write(repository.path().join("file two.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit two", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -114,7 +150,13 @@ async fn middle() {
     {
         fs::write(repository.path().join("file three.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit three", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -122,7 +164,13 @@ async fn middle() {
     {
         fs::write(repository.path().join("file four.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit four", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit four",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -184,7 +232,13 @@ async fn forcepush_allowed() {
     {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -197,7 +251,13 @@ async fn forcepush_allowed() {
     let commit_two_oid = {
         fs::write(repository.path().join("file two.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit two", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -205,7 +265,13 @@ async fn forcepush_allowed() {
     {
         fs::write(repository.path().join("file three.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit three", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -213,7 +279,13 @@ async fn forcepush_allowed() {
     {
         fs::write(repository.path().join("file four.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit four", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit four",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -267,7 +339,13 @@ async fn forcepush_forbidden() {
     {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -289,7 +367,13 @@ async fn forcepush_forbidden() {
     let commit_two_oid = {
         fs::write(repository.path().join("file two.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit two", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -297,7 +381,13 @@ async fn forcepush_forbidden() {
     {
         fs::write(repository.path().join("file three.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit three", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -305,7 +395,13 @@ async fn forcepush_forbidden() {
     {
         fs::write(repository.path().join("file four.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit four", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit four",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -342,7 +438,13 @@ async fn root() {
     let commit_one_oid = {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -0,0 +1,58 @@
+use tokio::sync::mpsc;
+
+use super::*;
+
+#[tokio::test]
+async fn streams_same_branches_as_list() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch1_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file one.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch1_id,
+            "commit one",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file two.txt"), "content").unwrap();
+
+    let (listed, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(listed.len(), 2);
+
+    let (tx, mut rx) = mpsc::channel(1);
+    let collect = async {
+        let mut branches = vec![];
+        while let Some(branch) = rx.recv().await {
+            branches.push(branch);
+        }
+        branches
+    };
+    let (result, mut streamed) =
+        tokio::join!(controller.stream_virtual_branches(project_id, tx), collect);
+    result.unwrap();
+
+    streamed.sort_by_key(|branch| branch.order);
+    assert_eq!(streamed, listed);
+}
@@ -0,0 +1,65 @@
+use super::*;
+
+#[tokio::test]
+async fn unapplies_every_applied_branch() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch1_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file1.txt"), "one").unwrap();
+    controller.list_virtual_branches(project_id).await.unwrap();
+
+    let branch2_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                selected_for_changes: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file2.txt"), "two").unwrap();
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 2);
+    assert!(branches.iter().all(|branch| branch.active));
+
+    let unapplied = controller.unapply_all(project_id).await.unwrap();
+    assert_eq!(unapplied, vec![branch1_id, branch2_id]);
+
+    assert!(!repository.path().join("file1.txt").exists());
+    assert!(!repository.path().join("file2.txt").exists());
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches.len(), 2);
+    assert!(branches.iter().all(|branch| !branch.active));
+}
+
+#[tokio::test]
+async fn nothing_applied() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(controller.unapply_all(project_id).await.unwrap().is_empty());
+}
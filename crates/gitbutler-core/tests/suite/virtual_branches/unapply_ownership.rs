@@ -29,7 +29,13 @@ async fn should_unapply_with_commits() {
     )
     .unwrap();
     controller
-        .create_commit(project_id, &branch_id, "test", None, false)
+        .create_commit(
+            project_id,
+            &branch_id,
+            "test",
+            None,
+            &CommitOptions::default(),
+        )
         .await
         .unwrap();
 
@@ -0,0 +1,52 @@
+use gitbutler_core::virtual_branches::DiffStats;
+
+use super::*;
+
+#[tokio::test]
+async fn sums_up_all_uncommitted_changes() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "one\ntwo\nthree\n").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        controller.uncommitted_stats(project_id).await.unwrap(),
+        DiffStats::default()
+    );
+
+    fs::write(repository.path().join("file.txt"), "one\nfour\n").unwrap();
+    fs::write(repository.path().join("another file.txt"), "a\nb\n").unwrap();
+
+    assert_eq!(
+        controller.uncommitted_stats(project_id).await.unwrap(),
+        DiffStats {
+            files_changed: 2,
+            insertions: 3,
+            deletions: 2,
+        }
+    );
+}
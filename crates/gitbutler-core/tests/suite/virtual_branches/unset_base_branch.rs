@@ -0,0 +1,137 @@
+use super::*;
+
+#[tokio::test]
+async fn unsets_target() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    let base_branch = controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+
+    controller.unset_base_branch(project_id).await.unwrap();
+
+    assert_eq!(
+        controller.get_base_branch_data(project_id).await.unwrap(),
+        None
+    );
+    // the uncommitted changes are kept with the unapplied branch
+    assert!(!repository.path().join("file.txt").exists());
+    // the branch that was checked out before is checked out again
+    let repo = git2::Repository::open(repository.path()).unwrap();
+    assert!(!repo.head_detached().unwrap());
+    assert_eq!(repo.head().unwrap().name(), Some("refs/heads/master"));
+    assert_eq!(
+        repo.head().unwrap().target().unwrap(),
+        git2::Oid::from_str(&base_branch.base_sha.to_string()).unwrap()
+    );
+
+    // the project can be connected again
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+    controller
+        .apply_virtual_branch(project_id, &branch_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(repository.path().join("file.txt")).unwrap(),
+        "content"
+    );
+}
+
+#[tokio::test]
+async fn detaches_head_without_previous_branch() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    let base_branch = controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+    let repo = git2::Repository::open(repository.path()).unwrap();
+    repo.find_reference("refs/heads/master")
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    controller.unset_base_branch(project_id).await.unwrap();
+
+    assert!(repo.head_detached().unwrap());
+    assert_eq!(
+        repo.head().unwrap().target().unwrap(),
+        git2::Oid::from_str(&base_branch.base_sha.to_string()).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn refuses_with_unpushed_commits() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch_id,
+            "commit one",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .unset_base_branch(project_id)
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::UnsetBaseBranchError::UnpushedCommits(_))
+    ));
+    assert!(controller
+        .get_base_branch_data(project_id)
+        .await
+        .unwrap()
+        .is_some());
+
+    controller
+        .push_virtual_branch(project_id, &branch_id, false, None)
+        .await
+        .unwrap();
+
+    controller.unset_base_branch(project_id).await.unwrap();
+    assert_eq!(
+        controller.get_base_branch_data(project_id).await.unwrap(),
+        None
+    );
+}
@@ -1,3 +1,5 @@
+use gitbutler_core::virtual_branches::RebaseOutcome;
+
 use super::*;
 
 mod unapplied_branch {
@@ -118,7 +120,13 @@ mod unapplied_branch {
 
             fs::write(repository.path().join("file.txt"), "conflict").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "conflicting commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "conflicting commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -204,7 +212,13 @@ mod unapplied_branch {
 
             fs::write(repository.path().join("file.txt"), "conflict").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "conflicting commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "conflicting commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -295,7 +309,13 @@ mod unapplied_branch {
 
             fs::write(repository.path().join("file.txt"), "conflict").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "conflicting commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "conflicting commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -383,7 +403,13 @@ mod unapplied_branch {
 
             fs::write(repository.path().join("file.txt"), "conflict").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "conflicting commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "conflicting commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -476,7 +502,7 @@ mod unapplied_branch {
                     &branch_id,
                     "non conflicting commit",
                     None,
-                    false,
+                    &CommitOptions::default(),
                 )
                 .await
                 .unwrap();
@@ -561,7 +587,13 @@ mod unapplied_branch {
 
             fs::write(repository.path().join("file.txt"), "second").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "second", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "second",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -674,7 +706,13 @@ mod unapplied_branch {
             fs::write(repository.path().join("file.txt"), "second").unwrap();
 
             controller
-                .create_commit(project_id, &branch_id, "second", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "second",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -728,7 +766,13 @@ mod unapplied_branch {
             // open pr
             fs::write(repository.path().join("file2.txt"), "new file").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "second", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "second",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
             controller
@@ -879,7 +923,13 @@ mod applied_branch {
 
             fs::write(repository.path().join("file.txt"), "conflict").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "conflicting commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "conflicting commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -960,7 +1010,13 @@ mod applied_branch {
 
             fs::write(repository.path().join("file.txt"), "conflict").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "conflicting commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "conflicting commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -1046,7 +1102,13 @@ mod applied_branch {
 
             fs::write(repository.path().join("file.txt"), "conflict").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "conflicting commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "conflicting commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -1129,7 +1191,13 @@ mod applied_branch {
 
             fs::write(repository.path().join("file.txt"), "conflict").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "conflicting commit", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "conflicting commit",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -1224,7 +1292,13 @@ mod applied_branch {
                 fs::write(repository.path().join("file2.txt"), "no conflict").unwrap();
 
                 controller
-                    .create_commit(project_id, &branch_id, "no conflicts", None, false)
+                    .create_commit(
+                        project_id,
+                        &branch_id,
+                        "no conflicts",
+                        None,
+                        &CommitOptions::default(),
+                    )
                     .await
                     .unwrap();
                 controller
@@ -1295,7 +1369,13 @@ mod applied_branch {
                 fs::write(repository.path().join("file2.txt"), "no conflict").unwrap();
 
                 controller
-                    .create_commit(project_id, &branch_id, "no conflicts", None, false)
+                    .create_commit(
+                        project_id,
+                        &branch_id,
+                        "no conflicts",
+                        None,
+                        &CommitOptions::default(),
+                    )
                     .await
                     .unwrap();
                 controller
@@ -1376,7 +1456,13 @@ mod applied_branch {
             fs::write(repository.path().join("file2.txt"), "no conflict").unwrap();
 
             controller
-                .create_commit(project_id, &branch_id, "no conflicts", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "no conflicts",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -1458,7 +1544,13 @@ mod applied_branch {
             fs::write(repository.path().join("file.txt"), "second").unwrap();
 
             controller
-                .create_commit(project_id, &branch_id, "second", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "second",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
             controller
@@ -1576,7 +1668,13 @@ mod applied_branch {
             .unwrap();
 
             controller
-                .create_commit(project_id, &branch_id, "first", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "first",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -1673,7 +1771,13 @@ mod applied_branch {
             fs::write(repository.path().join("file.txt"), "first").unwrap();
 
             controller
-                .create_commit(project_id, &branch_id, "first", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "first",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -1755,7 +1859,13 @@ mod applied_branch {
             fs::write(repository.path().join("file.txt"), "first").unwrap();
 
             controller
-                .create_commit(project_id, &branch_id, "first", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "first",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
 
@@ -1847,7 +1957,13 @@ mod applied_branch {
             fs::write(repository.path().join("file.txt"), "second").unwrap();
 
             controller
-                .create_commit(project_id, &branch_id, "second", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "second",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
         };
@@ -1896,7 +2012,13 @@ mod applied_branch {
             // open pr
             fs::write(repository.path().join("file2.txt"), "new file").unwrap();
             controller
-                .create_commit(project_id, &branch_id, "second", None, false)
+                .create_commit(
+                    project_id,
+                    &branch_id,
+                    "second",
+                    None,
+                    &CommitOptions::default(),
+                )
                 .await
                 .unwrap();
             controller
@@ -1927,3 +2049,76 @@ mod applied_branch {
         }
     }
 }
+
+#[tokio::test]
+async fn reports_outcome_of_each_branch() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    // make sure we have an undiscovered commit in the remote branch
+    {
+        fs::write(repository.path().join("file.txt"), "first").unwrap();
+        let first_commit_oid = repository.commit_all("first");
+        fs::write(repository.path().join("file.txt"), "second").unwrap();
+        repository.commit_all("second");
+        repository.push();
+        repository.reset_hard(Some(first_commit_oid));
+    }
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    // two branches that rebase cleanly, and one that conflicts with the remote branch
+    for (name, file, content) in [
+        ("one", "file2.txt", "no conflict"),
+        ("two", "file3.txt", "no conflict either"),
+        ("three", "file.txt", "conflict"),
+    ] {
+        let branch_id = controller
+            .create_virtual_branch(
+                project_id,
+                &branch::BranchCreateRequest {
+                    name: Some(name.to_string()),
+                    selected_for_changes: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        fs::write(repository.path().join(file), content).unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                name,
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap();
+    }
+
+    let mut outcomes = vec![];
+    controller
+        .update_base_branch_with_progress(project_id, |name, outcome| {
+            outcomes.push((name.to_string(), outcome));
+        })
+        .await
+        .unwrap();
+    outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert_eq!(
+        outcomes,
+        vec![
+            ("one".to_string(), RebaseOutcome::Rebased),
+            ("three".to_string(), RebaseOutcome::Conflicted),
+            ("two".to_string(), RebaseOutcome::Rebased),
+        ]
+    );
+}
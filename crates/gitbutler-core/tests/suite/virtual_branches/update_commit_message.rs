@@ -22,7 +22,13 @@ async fn head() {
     {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -30,7 +36,13 @@ async fn head() {
     {
         fs::write(repository.path().join("file two.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit two", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -38,7 +50,13 @@ async fn head() {
     let commit_three_oid = {
         fs::write(repository.path().join("file three.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit three", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -96,7 +114,13 @@ async fn middle() {
     {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -104,7 +128,13 @@ async fn middle() {
     let commit_two_oid = {
         fs::write(repository.path().join("file two.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit two", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -112,7 +142,13 @@ async fn middle() {
     {
         fs::write(repository.path().join("file three.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit three", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -142,6 +178,95 @@ async fn middle() {
     );
 }
 
+#[tokio::test]
+async fn returns_rewritten_commit() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    let commit_one_oid = {
+        fs::write(repository.path().join("file one.txt"), "").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    let commit_two_oid = {
+        fs::write(repository.path().join("file two.txt"), "").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    {
+        fs::write(repository.path().join("file three.txt"), "").unwrap();
+        controller
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
+            .await
+            .unwrap()
+    };
+
+    let new_commit_two_oid = controller
+        .update_commit_message(project_id, &branch_id, commit_two_oid, "commit two updated")
+        .await
+        .unwrap();
+    assert_ne!(new_commit_two_oid, commit_two_oid);
+
+    let branch = controller
+        .list_virtual_branches(project_id)
+        .await
+        .unwrap()
+        .0
+        .into_iter()
+        .find(|b| b.id == branch_id)
+        .unwrap();
+
+    assert_eq!(branch.commits.len(), 3);
+    assert_eq!(branch.commits[0].description, "commit three");
+    assert_eq!(branch.commits[0].parent_ids, vec![new_commit_two_oid]);
+    assert_eq!(branch.commits[0].files.len(), 1);
+    assert_eq!(
+        branch.commits[0].files[0].path.display().to_string(),
+        "file three.txt"
+    );
+    assert_eq!(branch.commits[1].id, new_commit_two_oid);
+    assert_eq!(branch.commits[1].description, "commit two updated");
+    assert_eq!(branch.commits[2].id, commit_one_oid);
+}
+
 #[tokio::test]
 async fn forcepush_allowed() {
     let Test {
@@ -174,7 +299,13 @@ async fn forcepush_allowed() {
     let commit_one_oid = {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -239,7 +370,13 @@ async fn forcepush_forbidden() {
     let commit_one_oid = {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -281,7 +418,13 @@ async fn root() {
     let commit_one_oid = {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -289,7 +432,13 @@ async fn root() {
     {
         fs::write(repository.path().join("file two.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit two", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit two",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -297,7 +446,13 @@ async fn root() {
     {
         fs::write(repository.path().join("file three.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit three", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit three",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -349,7 +504,13 @@ async fn empty() {
     let commit_one_oid = {
         fs::write(repository.path().join("file one.txt"), "").unwrap();
         controller
-            .create_commit(project_id, &branch_id, "commit one", None, false)
+            .create_commit(
+                project_id,
+                &branch_id,
+                "commit one",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
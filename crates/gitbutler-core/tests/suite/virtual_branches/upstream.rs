@@ -23,7 +23,13 @@ async fn detect_upstream_commits() {
         // create first commit
         fs::write(repository.path().join("file.txt"), "content").unwrap();
         controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -32,7 +38,13 @@ async fn detect_upstream_commits() {
         // create second commit
         fs::write(repository.path().join("file.txt"), "content2").unwrap();
         controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -47,7 +59,13 @@ async fn detect_upstream_commits() {
         // create third commit
         fs::write(repository.path().join("file.txt"), "content3").unwrap();
         controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -90,7 +108,13 @@ async fn detect_integrated_commits() {
         // create first commit
         fs::write(repository.path().join("file.txt"), "content").unwrap();
         controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -99,7 +123,13 @@ async fn detect_integrated_commits() {
         // create second commit
         fs::write(repository.path().join("file.txt"), "content2").unwrap();
         controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -128,7 +158,13 @@ async fn detect_integrated_commits() {
         // create third commit
         fs::write(repository.path().join("file.txt"), "content3").unwrap();
         controller
-            .create_commit(project_id, &branch1_id, "commit", None, false)
+            .create_commit(
+                project_id,
+                &branch1_id,
+                "commit",
+                None,
+                &CommitOptions::default(),
+            )
             .await
             .unwrap()
     };
@@ -147,3 +183,100 @@ async fn detect_integrated_commits() {
         assert!(branches[0].commits[2].is_integrated);
     }
 }
+
+#[tokio::test]
+async fn ahead_behind_of_unpushed_branch() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch1_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch1_id,
+            "commit",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        controller
+            .ahead_behind(project_id, &branch1_id)
+            .await
+            .unwrap(),
+        (0, 0)
+    );
+}
+
+#[tokio::test]
+async fn ahead_behind_after_push() {
+    let Test {
+        repository,
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch1_id = controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch1_id,
+            "commit one",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    controller
+        .push_virtual_branch(project_id, &branch1_id, false, None)
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content2").unwrap();
+    controller
+        .create_commit(
+            project_id,
+            &branch1_id,
+            "commit two",
+            None,
+            &CommitOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        controller
+            .ahead_behind(project_id, &branch1_id)
+            .await
+            .unwrap(),
+        (1, 0)
+    );
+}
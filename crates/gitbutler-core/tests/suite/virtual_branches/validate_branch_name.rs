@@ -0,0 +1,125 @@
+use super::*;
+
+#[test]
+fn invalid_names() {
+    let Test { controller, .. } = &Test::default();
+
+    assert_eq!(
+        controller.validate_branch_name(""),
+        Err(errors::BranchNameError::Empty)
+    );
+    for name in ["..", "feature..fix", "a/../b"] {
+        assert_eq!(
+            controller.validate_branch_name(name),
+            Err(errors::BranchNameError::ConsecutiveDots(name.to_string()))
+        );
+    }
+    for name in ["/feature", "feature/", "feature//fix"] {
+        assert_eq!(
+            controller.validate_branch_name(name),
+            Err(errors::BranchNameError::InvalidSlash(name.to_string()))
+        );
+    }
+    for name in ["feature.lock", "feature.lock/fix"] {
+        assert_eq!(
+            controller.validate_branch_name(name),
+            Err(errors::BranchNameError::LockSuffix(name.to_string()))
+        );
+    }
+    for name in [".feature", "feature.", "feature/.fix"] {
+        assert_eq!(
+            controller.validate_branch_name(name),
+            Err(errors::BranchNameError::InvalidDot(name.to_string()))
+        );
+    }
+}
+
+#[test]
+fn valid_names() {
+    let Test { controller, .. } = &Test::default();
+
+    // characters like spaces are replaced when pushing, so they are fine
+    for name in ["feature", "feature/fix-1.2", "Virtual branch", "foo!branch"] {
+        assert_eq!(controller.validate_branch_name(name), Ok(()));
+    }
+}
+
+#[tokio::test]
+async fn create_refuses_invalid_name() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .create_virtual_branch(
+                project_id,
+                &branch::BranchCreateRequest {
+                    name: Some("feature..fix".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::CreateVirtualBranchError::InvalidName(
+            errors::BranchNameError::ConsecutiveDots(_)
+        ))
+    ));
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert!(branches.is_empty());
+}
+
+#[tokio::test]
+async fn rename_refuses_invalid_name() {
+    let Test {
+        project_id,
+        controller,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    let branch_id = controller
+        .create_virtual_branch(
+            project_id,
+            &branch::BranchCreateRequest {
+                name: Some("feature".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        controller
+            .update_virtual_branch(
+                project_id,
+                branch::BranchUpdateRequest {
+                    id: branch_id,
+                    name: Some("feature.lock".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err()
+            .downcast_ref(),
+        Some(errors::UpdateBranchError::InvalidName(
+            errors::BranchNameError::LockSuffix(_)
+        ))
+    ));
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(branches[0].name, "feature");
+}
@@ -0,0 +1,47 @@
+use super::*;
+
+#[tokio::test]
+async fn matches_individual_getters() {
+    let Test {
+        project_id,
+        controller,
+        repository,
+        ..
+    } = &Test::default();
+
+    controller
+        .set_base_branch(project_id, &"refs/remotes/origin/master".parse().unwrap())
+        .await
+        .unwrap();
+
+    controller
+        .create_virtual_branch(project_id, &branch::BranchCreateRequest::default())
+        .await
+        .unwrap();
+
+    fs::write(repository.path().join("file.txt"), "content").unwrap();
+
+    let status = controller.workspace_status(project_id).await.unwrap();
+
+    assert_eq!(
+        status.base_branch,
+        controller.get_base_branch_data(project_id).await.unwrap()
+    );
+    assert!(status.base_branch.is_some());
+
+    let (branches, _, _) = controller.list_virtual_branches(project_id).await.unwrap();
+    assert_eq!(status.branches, branches);
+    assert_eq!(status.branches.len(), 1);
+    assert_eq!(status.branches[0].files.len(), 1);
+
+    assert_eq!(
+        status.conflicts,
+        controller.list_conflicts(project_id).await.unwrap()
+    );
+    assert!(status.conflicts.is_empty());
+
+    assert_eq!(
+        status.head,
+        controller.project_head(project_id).await.unwrap()
+    );
+}
@@ -0,0 +1,59 @@
+use gitbutler_core::{
+    secret,
+    tokens::{Controller, Token},
+};
+use gitbutler_testsupport::{secrets, temp_dir};
+
+fn token() -> Token {
+    Token {
+        username: "user".to_string(),
+        token: "glpat-secret".to_string(),
+    }
+}
+
+#[test]
+fn kept_in_the_keychain() {
+    secrets::setup_in_memory();
+    let data_dir = temp_dir();
+    let controller = Controller::from_path(&data_dir);
+
+    controller.set_token("gitlab.com", &token()).unwrap();
+
+    assert_eq!(controller.get_token("gitlab.com").unwrap(), Some(token()));
+    assert_eq!(
+        secret::retrieve("tokens/gitlab.com").unwrap().as_deref(),
+        Some("glpat-secret")
+    );
+    let stored = std::fs::read_to_string(data_dir.path().join("tokens.json")).unwrap();
+    assert!(stored.contains("user"));
+    assert!(!stored.contains("glpat-secret"));
+}
+
+#[test]
+fn deleted() {
+    secrets::setup_in_memory();
+    let data_dir = temp_dir();
+    let controller = Controller::from_path(&data_dir);
+    controller.set_token("gitlab.com", &token()).unwrap();
+
+    controller.delete_token("gitlab.com").unwrap();
+
+    assert_eq!(controller.get_token("gitlab.com").unwrap(), None);
+    assert_eq!(secret::retrieve("tokens/gitlab.com").unwrap(), None);
+}
+
+#[test]
+fn plain_text_tokens_are_moved_to_the_keychain() {
+    secrets::setup_in_memory();
+    let data_dir = temp_dir();
+    std::fs::write(
+        data_dir.path().join("tokens.json"),
+        r#"{"gitlab.com":{"username":"user","token":"glpat-secret"}}"#,
+    )
+    .unwrap();
+    let controller = Controller::from_path(&data_dir);
+
+    assert_eq!(controller.get_token("gitlab.com").unwrap(), Some(token()));
+    let stored = std::fs::read_to_string(data_dir.path().join("tokens.json")).unwrap();
+    assert!(!stored.contains("glpat-secret"));
+}
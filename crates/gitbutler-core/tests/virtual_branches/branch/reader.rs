@@ -38,6 +38,7 @@ fn test_branch() -> Branch {
         ),
         created_timestamp_ms: TEST_INDEX.load(Ordering::Relaxed) as u128,
         updated_timestamp_ms: (TEST_INDEX.load(Ordering::Relaxed) + 100) as u128,
+        last_activity_ms: (TEST_INDEX.load(Ordering::Relaxed) + 50) as u128,
         head: format!(
             "0123456789abcdef0123456789abcdef0123456{}",
             TEST_INDEX.load(Ordering::Relaxed)
@@ -56,6 +57,8 @@ fn test_branch() -> Branch {
                 .unwrap()],
         },
         selected_for_changes: Some(1),
+        archived: false,
+        pushed_name: None,
     }
 }
 
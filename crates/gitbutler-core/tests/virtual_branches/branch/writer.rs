@@ -32,6 +32,7 @@ fn new_test_branch() -> Branch {
         upstream_head: None,
         created_timestamp_ms: TEST_INDEX.load(Ordering::Relaxed) as u128,
         updated_timestamp_ms: (TEST_INDEX.load(Ordering::Relaxed) + 100) as u128,
+        last_activity_ms: (TEST_INDEX.load(Ordering::Relaxed) + 50) as u128,
         head: format!(
             "0123456789abcdef0123456789abcdef0123456{}",
             TEST_INDEX.load(Ordering::Relaxed)
@@ -52,6 +53,8 @@ fn new_test_branch() -> Branch {
         },
         order: TEST_INDEX.load(Ordering::Relaxed),
         selected_for_changes: Some(1),
+        archived: false,
+        pushed_name: None,
     }
 }
 
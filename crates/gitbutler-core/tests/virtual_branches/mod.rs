@@ -1,11 +1,13 @@
 mod branch;
 mod iterator;
+mod snapshot;
 mod target;
 
 use std::{
     collections::HashMap,
     io::Write,
     path::{Path, PathBuf},
+    time::Duration,
 };
 #[cfg(target_family = "unix")]
 use std::{
@@ -15,15 +17,18 @@ use std::{
 
 use anyhow::{Context, Result};
 use gitbutler_core::{
-    git, reader, sessions,
+    git,
+    project_repository::RemoteError,
+    reader, sessions,
     virtual_branches::{
         self, apply_branch,
         branch::{BranchCreateRequest, BranchOwnershipClaims},
         commit, create_virtual_branch,
-        errors::CommitError,
+        errors::{CommitError, PushError},
         integration::verify_branch,
         is_remote_branch_mergeable, is_virtual_branch_mergeable, list_remote_branches,
-        merge_virtual_branch_upstream, unapply_ownership, update_branch, VirtualBranchesHandle,
+        merge_virtual_branch_upstream, unapply_ownership, update_branch, BranchSort, CommitOptions,
+        ListOptions, VirtualBranchesHandle,
     },
 };
 use pretty_assertions::assert_eq;
@@ -59,7 +64,7 @@ fn commit_on_branch_then_change_file_then_get_status() -> Result<()> {
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches[0];
     assert_eq!(branch.files.len(), 1);
     assert_eq!(branch.commits.len(), 0);
@@ -73,12 +78,12 @@ fn commit_on_branch_then_change_file_then_get_status() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     // status (no files)
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches[0];
     assert_eq!(branch.files.len(), 0);
     assert_eq!(branch.commits.len(), 1);
@@ -90,7 +95,7 @@ fn commit_on_branch_then_change_file_then_get_status() -> Result<()> {
 
     // should have just the last change now, the other line is committed
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches[0];
     assert_eq!(branch.files.len(), 1);
     assert_eq!(branch.commits.len(), 1);
@@ -141,11 +146,11 @@ fn signed_commit() -> Result<()> {
         None,
         Some(suite.keys.get_or_create()?).as_ref(),
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository).unwrap();
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false).unwrap();
     let commit_id = &branches[0].commits[0].id;
     let commit_obj = project_repository.git_repository.find_commit(*commit_id)?;
     // check the raw_header contains the string "SSH SIGNATURE"
@@ -212,7 +217,7 @@ fn track_binary_files() -> Result<()> {
     file.write_all(&image_data)?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches[0];
     assert_eq!(branch.files.len(), 2);
     let img_file = &branch
@@ -235,12 +240,12 @@ fn track_binary_files() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     // status (no files)
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository).unwrap();
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false).unwrap();
     let commit_id = &branches[0].commits[0].id;
     let commit_obj = project_repository.git_repository.find_commit(*commit_id)?;
     let tree = commit_obj.tree()?;
@@ -266,11 +271,11 @@ fn track_binary_files() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository).unwrap();
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false).unwrap();
     let commit_id = &branches[0].commits[0].id;
     // get tree from commit_id
     let commit_obj = project_repository.git_repository.find_commit(*commit_id)?;
@@ -945,7 +950,7 @@ fn merge_vbranch_upstream_clean_rebase() -> Result<()> {
 
     // create the branch
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch1 = &branches[0];
     assert_eq!(branch1.files.len(), 1);
     assert_eq!(branch1.commits.len(), 1);
@@ -960,7 +965,7 @@ fn merge_vbranch_upstream_clean_rebase() -> Result<()> {
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch1 = &branches[0];
 
     let contents = std::fs::read(Path::new(&project.path).join(file_path))?;
@@ -1078,7 +1083,7 @@ fn merge_vbranch_upstream_conflict() -> Result<()> {
 
     // create the branch
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch1 = &branches[0];
 
     assert_eq!(branch1.files.len(), 1);
@@ -1088,7 +1093,7 @@ fn merge_vbranch_upstream_conflict() -> Result<()> {
     merge_virtual_branch_upstream(gb_repository, project_repository, &branch1.id, None, None)?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch1 = &branches[0];
     let contents = std::fs::read(Path::new(&project.path).join(file_path))?;
 
@@ -1109,7 +1114,7 @@ fn merge_vbranch_upstream_conflict() -> Result<()> {
 
     // make gb see the conflict resolution
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     assert!(branches[0].conflicted);
 
     // commit the merge resolution
@@ -1121,11 +1126,11 @@ fn merge_vbranch_upstream_conflict() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch1 = &branches[0];
     assert!(!branch1.conflicted);
     assert_eq!(branch1.files.len(), 0);
@@ -1167,7 +1172,7 @@ fn unapply_ownership_partial() -> Result<()> {
     .expect("failed to create virtual branch");
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     assert_eq!(branches.len(), 1);
     assert_eq!(branches[0].files.len(), 1);
     assert_eq!(branches[0].ownership.claims.len(), 1);
@@ -1186,7 +1191,7 @@ fn unapply_ownership_partial() -> Result<()> {
     .unwrap();
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     assert_eq!(branches.len(), 1);
     assert_eq!(branches[0].files.len(), 0);
     assert_eq!(branches[0].ownership.claims.len(), 0);
@@ -1259,7 +1264,7 @@ fn unapply_branch() -> Result<()> {
     assert_eq!("line5\nline6\n", String::from_utf8(contents)?);
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
     assert_eq!(branch.files.len(), 1);
     assert!(branch.active);
@@ -1272,7 +1277,7 @@ fn unapply_branch() -> Result<()> {
     assert_eq!("line5\nline6\n", String::from_utf8(contents)?);
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
     assert_eq!(branch.files.len(), 1);
     assert!(!branch.active);
@@ -1287,7 +1292,7 @@ fn unapply_branch() -> Result<()> {
     assert_eq!("line5\nline6\n", String::from_utf8(contents)?);
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
     assert_eq!(branch.files.len(), 1);
     assert!(branch.active);
@@ -1534,7 +1539,7 @@ fn detect_mergeable_branch() -> Result<()> {
     branch_writer.write(&mut branch4)?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     assert_eq!(branches.len(), 4);
 
     let branch1 = &branches.iter().find(|b| b.id == branch1_id).unwrap();
@@ -1706,7 +1711,7 @@ fn upstream_integrated_vbranch() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
     commit(
         gb_repository,
@@ -1716,11 +1721,11 @@ fn upstream_integrated_vbranch() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
 
     let branch1 = &branches.iter().find(|b| b.id == branch1_id).unwrap();
     assert!(branch1.commits.iter().any(|c| c.is_integrated));
@@ -1769,7 +1774,7 @@ fn commit_same_hunk_twice() -> Result<()> {
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(branch.files.len(), 1);
@@ -1785,11 +1790,11 @@ fn commit_same_hunk_twice() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(branch.files.len(), 0, "no files expected");
@@ -1810,7 +1815,7 @@ fn commit_same_hunk_twice() -> Result<()> {
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(branch.files.len(), 1, "one file should be changed");
@@ -1824,11 +1829,11 @@ fn commit_same_hunk_twice() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(
@@ -1875,7 +1880,7 @@ fn commit_same_file_twice() -> Result<()> {
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(branch.files.len(), 1);
@@ -1891,11 +1896,11 @@ fn commit_same_file_twice() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(branch.files.len(), 0, "no files expected");
@@ -1916,7 +1921,7 @@ fn commit_same_file_twice() -> Result<()> {
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(branch.files.len(), 1, "one file should be changed");
@@ -1930,11 +1935,11 @@ fn commit_same_file_twice() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(
@@ -1981,7 +1986,7 @@ fn commit_partial_by_hunk() -> Result<()> {
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(branch.files.len(), 1);
@@ -1997,11 +2002,11 @@ fn commit_partial_by_hunk() -> Result<()> {
         Some(&"test.txt:1-6".parse::<BranchOwnershipClaims>().unwrap()),
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(branch.files.len(), 1);
@@ -2018,11 +2023,11 @@ fn commit_partial_by_hunk() -> Result<()> {
         Some(&"test.txt:16-22".parse::<BranchOwnershipClaims>().unwrap()),
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     assert_eq!(branch.files.len(), 0);
@@ -2084,11 +2089,11 @@ fn commit_partial_by_file() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch1 = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     // branch one test.txt has just the 1st and 3rd hunks applied
@@ -2159,11 +2164,11 @@ fn commit_add_and_delete_files() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch1 = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     // branch one test.txt has just the 1st and 3rd hunks applied
@@ -2232,11 +2237,11 @@ fn commit_executable_and_symlinks() -> Result<()> {
         None,
         None,
         None,
-        false,
+        &CommitOptions::default(),
     )?;
 
     let (branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     let branch1 = &branches.iter().find(|b| b.id == branch1_id).unwrap();
 
     let commit = &branch1.commits[0].id;
@@ -2337,7 +2342,7 @@ fn verify_branch_commits_to_integration() -> Result<()> {
 
     // one virtual branch with two commits was created
     let (virtual_branches, _, _) =
-        virtual_branches::list_virtual_branches(gb_repository, project_repository)?;
+        virtual_branches::list_virtual_branches(gb_repository, project_repository, false)?;
     assert_eq!(virtual_branches.len(), 1);
 
     let branch = &virtual_branches.first().unwrap();
@@ -2492,7 +2497,10 @@ fn post_commit_hook() -> Result<()> {
         None,
         Some(suite.keys.get_or_create()?).as_ref(),
         None,
-        true,
+        &CommitOptions {
+            run_hooks: true,
+            ..Default::default()
+        },
     )?;
 
     assert!(hook_ran_proof.exists());
@@ -2562,3 +2570,136 @@ fn commit_msg_hook_rejection() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn list_sorted_by_recent_activity() -> Result<()> {
+    let suite = Suite::default();
+    let Case {
+        project,
+        project_repository,
+        gb_repository,
+        ..
+    } = &suite.new_case();
+
+    set_test_target(gb_repository, project_repository)?;
+
+    let branch1_id = create_virtual_branch(
+        gb_repository,
+        project_repository,
+        &BranchCreateRequest::default(),
+    )
+    .expect("failed to create virtual branch")
+    .id;
+    let branch2_id = create_virtual_branch(
+        gb_repository,
+        project_repository,
+        &BranchCreateRequest::default(),
+    )
+    .expect("failed to create virtual branch")
+    .id;
+
+    let current_session = gb_repository.get_or_create_current_session()?;
+    let current_session_reader = sessions::Reader::open(gb_repository, &current_session)?;
+    let branch_reader = virtual_branches::branch::Reader::new(
+        &current_session_reader,
+        VirtualBranchesHandle::new(&project_repository.project().gb_dir()),
+        project_repository.project().use_toml_vbranches_state(),
+    );
+    let branch_writer = virtual_branches::branch::Writer::new(
+        gb_repository,
+        VirtualBranchesHandle::new(&project.gb_dir()),
+    )?;
+    let mut branch1 = branch_reader.read(&branch1_id)?;
+    branch1.last_activity_ms = 2;
+    branch_writer.write(&mut branch1)?;
+    let mut branch2 = branch_reader.read(&branch2_id)?;
+    branch2.last_activity_ms = 1;
+    branch_writer.write(&mut branch2)?;
+
+    let options = ListOptions {
+        sort: BranchSort::RecentActivity,
+        ..ListOptions::default()
+    };
+
+    let (branches, _, _) = virtual_branches::list_virtual_branches_with_options(
+        gb_repository,
+        project_repository,
+        false,
+        &options,
+    )?;
+    assert_eq!(branches[0].id, branch1_id);
+    assert_eq!(branches[1].id, branch2_id);
+
+    // listing rewrites the branches, but that isn't activity
+    let (branches, _, _) = virtual_branches::list_virtual_branches_with_options(
+        gb_repository,
+        project_repository,
+        false,
+        &options,
+    )?;
+    assert_eq!(branches[0].id, branch1_id);
+    assert_eq!(branches[0].last_activity_at, 2);
+    assert_eq!(branches[1].id, branch2_id);
+    assert_eq!(branches[1].last_activity_at, 1);
+
+    // renaming is
+    update_branch(
+        gb_repository,
+        project_repository,
+        virtual_branches::branch::BranchUpdateRequest {
+            id: branch2_id,
+            name: Some("renamed".to_string()),
+            ..Default::default()
+        },
+    )?;
+
+    let (branches, _, _) = virtual_branches::list_virtual_branches_with_options(
+        gb_repository,
+        project_repository,
+        false,
+        &options,
+    )?;
+    assert_eq!(branches[0].id, branch2_id);
+    assert_eq!(branches[1].id, branch1_id);
+
+    Ok(())
+}
+
+#[test]
+fn push_with_retries_retries_network_errors() {
+    let mut attempts = 0;
+    let result = virtual_branches::push_with_retries(3, Duration::from_millis(1), || {
+        attempts += 1;
+        if attempts <= 2 {
+            Err(PushError::Remote(RemoteError::Network))
+        } else {
+            Ok(attempts)
+        }
+    });
+    assert_eq!(result.unwrap(), 3);
+}
+
+#[test]
+fn push_with_retries_gives_up() {
+    let mut attempts = 0;
+    let result = virtual_branches::push_with_retries(2, Duration::from_millis(1), || {
+        attempts += 1;
+        Err::<(), _>(PushError::Remote(RemoteError::Network))
+    });
+    assert!(matches!(
+        result,
+        Err(PushError::Remote(RemoteError::Network))
+    ));
+    assert_eq!(attempts, 3);
+}
+
+#[test]
+fn push_with_retries_does_not_retry_auth_errors() {
+    let mut attempts = 0;
+    let result = virtual_branches::push_with_retries(3, Duration::from_millis(1), || {
+        attempts += 1;
+        Err::<(), _>(PushError::Remote(RemoteError::Auth))
+    });
+    assert!(matches!(result, Err(PushError::Remote(RemoteError::Auth))));
+    assert_eq!(attempts, 1);
+}
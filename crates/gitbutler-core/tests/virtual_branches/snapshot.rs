@@ -0,0 +1,81 @@
+use anyhow::Result;
+use gitbutler_core::virtual_branches::{
+    branch::BranchCreateRequest, create_virtual_branch, snapshot, target, VirtualBranchesHandle,
+};
+
+use gitbutler_testsupport::{virtual_branches::set_test_target, Case, Suite};
+
+#[test]
+fn restore_deletes_target_the_snapshot_had_none() -> Result<()> {
+    let suite = Suite::default();
+    let Case {
+        project,
+        project_repository,
+        gb_repository,
+        ..
+    } = &suite.new_case();
+
+    let snapshot_id = snapshot::create(gb_repository, project_repository)?;
+
+    set_test_target(gb_repository, project_repository)?;
+    create_virtual_branch(
+        gb_repository,
+        project_repository,
+        &BranchCreateRequest::default(),
+    )?;
+
+    snapshot::restore(gb_repository, project_repository, &snapshot_id)?;
+
+    let state = VirtualBranchesHandle::new(&project.gb_dir());
+    assert_eq!(state.get_default_target()?, None);
+    assert!(state.list_branches()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn restore_deletes_branch_targets_set_since() -> Result<()> {
+    let suite = Suite::default();
+    let Case {
+        project,
+        project_repository,
+        gb_repository,
+        ..
+    } = &suite.new_case();
+
+    set_test_target(gb_repository, project_repository)?;
+    let state = VirtualBranchesHandle::new(&project.gb_dir());
+    let default_target = state.get_default_target()?.unwrap();
+    let other_target = target::Target {
+        branch: "refs/remotes/origin/other".parse().unwrap(),
+        ..default_target.clone()
+    };
+
+    let kept = create_virtual_branch(
+        gb_repository,
+        project_repository,
+        &BranchCreateRequest::default(),
+    )?;
+
+    let snapshot_id = snapshot::create(gb_repository, project_repository)?;
+
+    let created = create_virtual_branch(
+        gb_repository,
+        project_repository,
+        &BranchCreateRequest::default(),
+    )?;
+    let target_writer =
+        target::Writer::new(gb_repository, VirtualBranchesHandle::new(&project.gb_dir()))?;
+    target_writer.write(&kept.id, &other_target)?;
+    target_writer.write(&created.id, &other_target)?;
+
+    snapshot::restore(gb_repository, project_repository, &snapshot_id)?;
+
+    assert_eq!(state.get_default_target()?, Some(default_target));
+    assert_eq!(state.get_branch_target(&kept.id)?, None);
+    assert!(state.get_branch(&kept.id)?.is_some());
+    assert_eq!(state.get_branch_target(&created.id)?, None);
+    assert_eq!(state.get_branch(&created.id)?, None);
+
+    Ok(())
+}
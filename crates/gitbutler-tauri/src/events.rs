@@ -73,6 +73,26 @@ impl Event {
         }
     }
 
+    pub fn base_branch_updated(project_id: &ProjectId, behind: usize) -> Self {
+        Event {
+            name: format!("project://{}/base-branch", project_id),
+            payload: serde_json::json!({ "behind": behind }),
+            project_id: *project_id,
+        }
+    }
+
+    pub fn base_branch_rebased(
+        project_id: &ProjectId,
+        branch_name: &str,
+        outcome: virtual_branches::RebaseOutcome,
+    ) -> Self {
+        Event {
+            name: format!("project://{}/base-branch/rebase", project_id),
+            payload: serde_json::json!({ "branchName": branch_name, "outcome": outcome }),
+            project_id: *project_id,
+        }
+    }
+
     pub fn git_head(project_id: &ProjectId, head: &str) -> Self {
         Event {
             name: format!("project://{}/git/head", project_id),
@@ -129,6 +149,17 @@ impl Event {
         }
     }
 
+    pub fn virtual_branch(
+        project_id: &ProjectId,
+        virtual_branch: &virtual_branches::VirtualBranch,
+    ) -> Self {
+        Event {
+            name: format!("project://{}/virtual-branches/branch", project_id),
+            payload: serde_json::json!(virtual_branch),
+            project_id: *project_id,
+        }
+    }
+
     pub fn virtual_branches(
         project_id: &ProjectId,
         virtual_branches: &virtual_branches::VirtualBranches,
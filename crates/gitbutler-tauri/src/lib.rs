@@ -29,6 +29,7 @@ pub mod keys;
 pub mod projects;
 pub mod sentry;
 pub mod sessions;
+pub mod tokens;
 pub mod users;
 pub mod virtual_branches;
 pub mod zip;
@@ -19,7 +19,7 @@ use anyhow::Context;
 use gitbutler_core::{assets, database, git, storage};
 use gitbutler_tauri::{
     analytics, app, askpass, commands, deltas, github, keys, logs, menu, projects, sentry,
-    sessions, users, virtual_branches, watcher, zip,
+    sessions, tokens, users, virtual_branches, watcher, zip,
 };
 use tauri::{generate_context, Manager, Wry};
 use tauri_plugin_log::LogTarget;
@@ -134,9 +134,16 @@ fn main() {
                     let keys_controller = gitbutler_core::keys::Controller::new(keys_storage_controller.clone());
                     app_handle.manage(keys_controller.clone());
 
+                    let tokens_storage_controller = gitbutler_core::tokens::storage::Storage::new(storage_controller.clone());
+                    app_handle.manage(tokens_storage_controller.clone());
+
+                    let tokens_controller = gitbutler_core::tokens::Controller::new(tokens_storage_controller.clone());
+                    app_handle.manage(tokens_controller.clone());
+
                     let git_credentials_controller = git::credentials::Helper::new(
                         keys_controller.clone(),
                         users_controller.clone(),
+                        tokens_controller.clone(),
                         std::env::var_os("HOME").map(PathBuf::from)
                     );
                     app_handle.manage(git_credentials_controller.clone());
@@ -231,6 +238,7 @@ fn main() {
                     projects::commands::add_project,
                     projects::commands::get_project,
                     projects::commands::update_project,
+                    projects::commands::set_project_key_passphrase,
                     projects::commands::delete_project,
                     projects::commands::list_projects,
                     projects::commands::git_get_local_config,
@@ -241,17 +249,31 @@ fn main() {
                     virtual_branches::commands::create_virtual_branch,
                     virtual_branches::commands::commit_virtual_branch,
                     virtual_branches::commands::get_base_branch_data,
+                    virtual_branches::commands::get_base_branch_behind,
+                    virtual_branches::commands::get_virtual_branch,
+                    virtual_branches::commands::stream_virtual_branches,
+                    virtual_branches::commands::get_workspace_status,
                     virtual_branches::commands::set_base_branch,
+                    virtual_branches::commands::unset_base_branch,
                     virtual_branches::commands::update_base_branch,
                     virtual_branches::commands::merge_virtual_branch_upstream,
+                    virtual_branches::commands::abort_integration,
                     virtual_branches::commands::update_virtual_branch,
+                    virtual_branches::commands::update_virtual_branches,
+                    virtual_branches::commands::set_branch_remote_name,
                     virtual_branches::commands::delete_virtual_branch,
+                    virtual_branches::commands::archive_branch,
+                    virtual_branches::commands::unarchive_branch,
+                    virtual_branches::commands::reorder_virtual_branches,
                     virtual_branches::commands::apply_branch,
                     virtual_branches::commands::unapply_branch,
+                    virtual_branches::commands::unapply_all,
+                    virtual_branches::commands::reapply_branches,
                     virtual_branches::commands::unapply_ownership,
                     virtual_branches::commands::reset_files,
                     virtual_branches::commands::push_virtual_branch,
                     virtual_branches::commands::create_virtual_branch_from_branch,
+                    virtual_branches::commands::split_to_new_branch,
                     virtual_branches::commands::can_apply_virtual_branch,
                     virtual_branches::commands::can_apply_remote_branch,
                     virtual_branches::commands::list_remote_commit_files,
@@ -260,12 +282,29 @@ fn main() {
                     virtual_branches::commands::amend_virtual_branch,
                     virtual_branches::commands::list_remote_branches,
                     virtual_branches::commands::get_remote_branch_data,
+                    virtual_branches::commands::ahead_behind,
+                    virtual_branches::commands::branch_upstream,
+                    virtual_branches::commands::file_history,
+                    virtual_branches::commands::commit_graph,
+                    virtual_branches::commands::pull_requests,
+                    virtual_branches::commands::list_commit_files,
+                    virtual_branches::commands::diff_commits,
+                    virtual_branches::commands::get_uncommitted_stats,
+                    virtual_branches::commands::get_ignored_changes,
+                    virtual_branches::commands::get_commit_author,
+                    virtual_branches::commands::list_conflicts,
+                    virtual_branches::commands::conflict_versions,
                     virtual_branches::commands::squash_branch_commit,
+                    virtual_branches::commands::fixup_branch_commit,
                     virtual_branches::commands::fetch_from_target,
                     virtual_branches::commands::move_commit,
+                    virtual_branches::commands::rebase_branch,
+                    virtual_branches::commands::land_branch,
                     virtual_branches::commands::save_vbranches_state,
                     menu::menu_item_set_enabled,
                     keys::commands::get_public_key,
+                    tokens::commands::set_token,
+                    tokens::commands::delete_token,
                     github::commands::init_device_oauth,
                     github::commands::check_auth_status,
                     askpass::commands::submit_prompt_response,
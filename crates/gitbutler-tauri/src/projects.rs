@@ -23,6 +23,23 @@ pub mod commands {
             .map_err(Error::from_error_with_context)
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle, passphrase), err(Debug))]
+    pub async fn set_project_key_passphrase(
+        handle: tauri::AppHandle,
+        id: &str,
+        passphrase: Option<String>,
+    ) -> Result<projects::Project, Error> {
+        let id = id.parse().context(error::Context::new_static(
+            Code::Validation,
+            "Malformed project id",
+        ))?;
+        handle
+            .state::<Controller>()
+            .set_key_passphrase(&id, passphrase.as_deref())
+            .map_err(Error::from_error_with_context)
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn add_project(
@@ -0,0 +1,36 @@
+pub mod commands {
+    use gitbutler_core::tokens::{Controller, Token};
+    use tauri::Manager;
+    use tracing::instrument;
+
+    use crate::error::Error;
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle, token), err(Debug))]
+    pub async fn set_token(
+        handle: tauri::AppHandle,
+        host: &str,
+        username: &str,
+        token: &str,
+    ) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .set_token(
+                host,
+                &Token {
+                    username: username.to_string(),
+                    token: token.to_string(),
+                },
+            )
+            .map_err(Into::into)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn delete_token(handle: tauri::AppHandle, host: &str) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .delete_token(host)
+            .map_err(Into::into)
+    }
+}
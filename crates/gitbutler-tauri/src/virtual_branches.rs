@@ -5,18 +5,23 @@ pub mod commands {
         askpass::AskpassBroker,
         assets,
         error::Code,
-        git, projects,
+        git,
+        project_repository::conflicts::ConflictedFile,
+        projects,
         projects::ProjectId,
         virtual_branches::{
             branch::{self, BranchId, BranchOwnershipClaims},
             controller::Controller,
-            BaseBranch, RemoteBranch, RemoteBranchData, RemoteBranchFile, VirtualBranches,
+            Author, BaseBranch, CommitFile, CommitGraph, CommitGraphNode, CommitOptions,
+            ConflictVersions, DiffStats, FileRevision, PullRequest, RemoteBranch, RemoteBranchData,
+            RemoteBranchFile, UpstreamInfo, VirtualBranch, VirtualBranches, WorkspaceStatus,
         },
     };
+    use std::path::PathBuf;
     use tauri::{AppHandle, Manager};
     use tracing::instrument;
 
-    use crate::watcher;
+    use crate::{events, watcher};
 
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
@@ -26,11 +31,11 @@ pub mod commands {
         branch: BranchId,
         message: &str,
         ownership: Option<BranchOwnershipClaims>,
-        run_hooks: bool,
+        options: CommitOptions,
     ) -> Result<git::Oid, Error> {
         let oid = handle
             .state::<Controller>()
-            .create_commit(&project_id, &branch, message, ownership.as_ref(), run_hooks)
+            .create_commit(&project_id, &branch, message, ownership.as_ref(), &options)
             .await?;
         emit_vbranches(&handle, &project_id).await;
         Ok(oid)
@@ -56,11 +61,16 @@ pub mod commands {
     pub async fn list_virtual_branches(
         handle: AppHandle,
         project_id: ProjectId,
+        include_archived: Option<bool>,
     ) -> Result<VirtualBranches, Error> {
-        let (branches, uses_diff_context, skipped_files) = handle
-            .state::<Controller>()
-            .list_virtual_branches(&project_id)
-            .await?;
+        let controller = handle.state::<Controller>();
+        let (branches, uses_diff_context, skipped_files) = if include_archived.unwrap_or(false) {
+            controller
+                .list_virtual_branches_including_archived(&project_id)
+                .await?
+        } else {
+            controller.list_virtual_branches(&project_id).await?
+        };
 
         // Migration: If use_diff_context is not already set and if there are no vbranches, set use_diff_context to true
         let has_active_branches = branches.iter().any(|branch| branch.active);
@@ -83,6 +93,75 @@ pub mod commands {
         })
     }
 
+    /// Emits every virtual branch as soon as it is listed, with its authors proxied, and returns
+    /// once all of them are emitted.
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn stream_virtual_branches(
+        handle: AppHandle,
+        project_id: ProjectId,
+    ) -> Result<(), Error> {
+        let sender = events::Sender::try_from(&handle)?;
+        let proxy = handle.state::<assets::Proxy>();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let emit = async {
+            while let Some(branch) = rx.recv().await {
+                let branch = proxy.proxy_virtual_branch(branch).await;
+                if let Err(error) =
+                    sender.send(&events::Event::virtual_branch(&project_id, &branch))
+                {
+                    tracing::error!(?error, "failed to emit virtual branch");
+                }
+            }
+        };
+        let (result, ()) = tokio::join!(
+            handle
+                .state::<Controller>()
+                .stream_virtual_branches(&project_id, tx),
+            emit
+        );
+        result?;
+        Ok(())
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn get_virtual_branch(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+    ) -> Result<VirtualBranch, Error> {
+        let branch = handle
+            .state::<Controller>()
+            .get_virtual_branch(&project_id, &branch_id)
+            .await?;
+        let proxy = handle.state::<assets::Proxy>();
+        Ok(proxy.proxy_virtual_branch(branch).await)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn get_workspace_status(
+        handle: AppHandle,
+        project_id: ProjectId,
+    ) -> Result<WorkspaceStatus, Error> {
+        let status = handle
+            .state::<Controller>()
+            .workspace_status(&project_id)
+            .await?;
+        let proxy = handle.state::<assets::Proxy>();
+        let base_branch = match status.base_branch {
+            Some(base_branch) => Some(proxy.proxy_base_branch(base_branch).await),
+            None => None,
+        };
+        let branches = proxy.proxy_virtual_branches(status.branches).await;
+        Ok(WorkspaceStatus {
+            base_branch,
+            branches,
+            ..status
+        })
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn create_virtual_branch(
@@ -98,16 +177,34 @@ pub mod commands {
         Ok(branch_id)
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn split_to_new_branch(
+        handle: AppHandle,
+        project_id: ProjectId,
+        from_branch: BranchId,
+        ownership: BranchOwnershipClaims,
+        name: &str,
+    ) -> Result<BranchId, Error> {
+        let branch_id = handle
+            .state::<Controller>()
+            .split_to_new_branch(&project_id, &from_branch, &ownership.claims, name)
+            .await?;
+        emit_vbranches(&handle, &project_id).await;
+        Ok(branch_id)
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn create_virtual_branch_from_branch(
         handle: AppHandle,
         project_id: ProjectId,
         branch: git::Refname,
+        apply: Option<bool>,
     ) -> Result<BranchId, Error> {
         let branch_id = handle
             .state::<Controller>()
-            .create_virtual_branch_from_branch(&project_id, &branch)
+            .create_virtual_branch_from_branch(&project_id, &branch, apply.unwrap_or(true))
             .await?;
         emit_vbranches(&handle, &project_id).await;
         Ok(branch_id)
@@ -128,6 +225,17 @@ pub mod commands {
         Ok(())
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn abort_integration(handle: AppHandle, project_id: ProjectId) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .abort_integration(&project_id)
+            .await?;
+        emit_vbranches(&handle, &project_id).await;
+        Ok(())
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn get_base_branch_data(
@@ -147,6 +255,19 @@ pub mod commands {
         }
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn get_base_branch_behind(
+        handle: AppHandle,
+        project_id: ProjectId,
+    ) -> Result<usize, Error> {
+        handle
+            .state::<Controller>()
+            .base_branch_behind(&project_id)
+            .await
+            .map_err(Into::into)
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn set_base_branch(
@@ -169,12 +290,28 @@ pub mod commands {
         Ok(base_branch)
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn unset_base_branch(handle: AppHandle, project_id: ProjectId) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .unset_base_branch(&project_id)
+            .await?;
+        Ok(())
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn update_base_branch(handle: AppHandle, project_id: ProjectId) -> Result<(), Error> {
+        let sender = events::Sender::try_from(&handle)?;
         handle
             .state::<Controller>()
-            .update_base_branch(&project_id)
+            .update_base_branch_with_progress(&project_id, |name, outcome| {
+                let event = events::Event::base_branch_rebased(&project_id, name, outcome);
+                if let Err(error) = sender.send(&event) {
+                    tracing::error!(?error, "failed to send rebase progress");
+                }
+            })
             .await?;
         emit_vbranches(&handle, &project_id).await;
         Ok(())
@@ -196,6 +333,39 @@ pub mod commands {
         Ok(())
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn set_branch_remote_name(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+        upstream: &str,
+    ) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .set_branch_remote_name(&project_id, &branch_id, upstream)
+            .await?;
+
+        emit_vbranches(&handle, &project_id).await;
+        Ok(())
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn update_virtual_branches(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branches: Vec<branch::BranchUpdateRequest>,
+    ) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .apply_updates(&project_id, branches)
+            .await?;
+
+        emit_vbranches(&handle, &project_id).await;
+        Ok(())
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn delete_virtual_branch(
@@ -211,6 +381,51 @@ pub mod commands {
         Ok(())
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn archive_branch(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+    ) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .archive_branch(&project_id, &branch_id)
+            .await?;
+        emit_vbranches(&handle, &project_id).await;
+        Ok(())
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn unarchive_branch(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+    ) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .unarchive_branch(&project_id, &branch_id)
+            .await?;
+        emit_vbranches(&handle, &project_id).await;
+        Ok(())
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn reorder_virtual_branches(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branch_ids: Vec<BranchId>,
+    ) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .reorder_virtual_branches(&project_id, &branch_ids)
+            .await?;
+        emit_vbranches(&handle, &project_id).await;
+        Ok(())
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn apply_branch(
@@ -241,6 +456,36 @@ pub mod commands {
         Ok(())
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn unapply_all(
+        handle: AppHandle,
+        project_id: ProjectId,
+    ) -> Result<Vec<BranchId>, Error> {
+        let unapplied = handle
+            .state::<Controller>()
+            .unapply_all(&project_id)
+            .await?;
+        emit_vbranches(&handle, &project_id).await;
+        Ok(unapplied)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn reapply_branches(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branch_ids: Vec<BranchId>,
+    ) -> Result<(), Error> {
+        let result = handle
+            .state::<Controller>()
+            .reapply_branches(&project_id, &branch_ids)
+            .await;
+        // branches applied before a conflicting one stay applied
+        emit_vbranches(&handle, &project_id).await;
+        result.map_err(Into::into)
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn unapply_ownership(
@@ -419,6 +664,188 @@ pub mod commands {
         Ok(branch_data)
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn branch_upstream(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+    ) -> Result<Option<UpstreamInfo>, Error> {
+        let upstream = handle
+            .state::<Controller>()
+            .branch_upstream(&project_id, &branch_id)
+            .await?;
+        Ok(upstream)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn pull_requests(
+        handle: AppHandle,
+        project_id: ProjectId,
+    ) -> Result<Vec<PullRequest>, Error> {
+        let pull_requests = handle
+            .state::<Controller>()
+            .pull_requests(&project_id)
+            .await?;
+        Ok(pull_requests)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn commit_graph(
+        handle: AppHandle,
+        project_id: ProjectId,
+    ) -> Result<CommitGraph, Error> {
+        let graph = handle
+            .state::<Controller>()
+            .commit_graph(&project_id)
+            .await?;
+        let proxy = handle.state::<assets::Proxy>();
+        let mut nodes = Vec::with_capacity(graph.nodes.len());
+        for node in graph.nodes {
+            nodes.push(CommitGraphNode {
+                author: proxy.proxy_author(node.author).await,
+                ..node
+            });
+        }
+        Ok(CommitGraph { nodes, ..graph })
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn file_history(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+        path: PathBuf,
+    ) -> Result<Vec<FileRevision>, Error> {
+        let revisions = handle
+            .state::<Controller>()
+            .file_history(&project_id, &branch_id, &path)
+            .await?;
+        let proxy = handle.state::<assets::Proxy>();
+        let mut proxied = Vec::with_capacity(revisions.len());
+        for revision in revisions {
+            proxied.push(FileRevision {
+                author: proxy.proxy_author(revision.author).await,
+                ..revision
+            });
+        }
+        Ok(proxied)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn ahead_behind(
+        handle: AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+    ) -> Result<(usize, usize), Error> {
+        let ahead_behind = handle
+            .state::<Controller>()
+            .ahead_behind(&project_id, &branch_id)
+            .await?;
+        Ok(ahead_behind)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn list_commit_files(
+        handle: AppHandle,
+        project_id: ProjectId,
+        commit_oid: git::Oid,
+    ) -> Result<Vec<CommitFile>, Error> {
+        let files = handle
+            .state::<Controller>()
+            .list_commit_files(&project_id, commit_oid)
+            .await?;
+        Ok(files)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn get_uncommitted_stats(
+        handle: AppHandle,
+        project_id: ProjectId,
+    ) -> Result<DiffStats, Error> {
+        handle
+            .state::<Controller>()
+            .uncommitted_stats(&project_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn get_ignored_changes(
+        handle: AppHandle,
+        project_id: ProjectId,
+    ) -> Result<Vec<PathBuf>, Error> {
+        handle
+            .state::<Controller>()
+            .ignored_changes(&project_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn diff_commits(
+        handle: AppHandle,
+        project_id: ProjectId,
+        from: git::Oid,
+        to: git::Oid,
+    ) -> Result<Vec<CommitFile>, Error> {
+        let files = handle
+            .state::<Controller>()
+            .diff_commits(&project_id, from, to)
+            .await?;
+        Ok(files)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn get_commit_author(
+        handle: AppHandle,
+        project_id: ProjectId,
+        commit_oid: git::Oid,
+    ) -> Result<Author, Error> {
+        let author = handle
+            .state::<Controller>()
+            .commit_author(&project_id, commit_oid)
+            .await?;
+        let proxy = handle.state::<assets::Proxy>();
+        Ok(proxy.proxy_author(author).await)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn list_conflicts(
+        handle: AppHandle,
+        project_id: ProjectId,
+    ) -> Result<Vec<ConflictedFile>, Error> {
+        let conflicts = handle
+            .state::<Controller>()
+            .list_conflicts(&project_id)
+            .await?;
+        Ok(conflicts)
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn conflict_versions(
+        handle: AppHandle,
+        project_id: ProjectId,
+        path: PathBuf,
+    ) -> Result<ConflictVersions, Error> {
+        handle
+            .state::<Controller>()
+            .conflict_versions(&project_id, &path)
+            .await
+            .map_err(Into::into)
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn squash_branch_commit(
@@ -435,6 +862,22 @@ pub mod commands {
         Ok(())
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn fixup_branch_commit(
+        handle: tauri::AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+        target_commit_oid: git::Oid,
+    ) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .fixup_commit(&project_id, &branch_id, target_commit_oid)
+            .await?;
+        emit_vbranches(&handle, &project_id).await;
+        Ok(())
+    }
+
     #[tauri::command(async)]
     #[instrument(skip(handle), err(Debug))]
     pub async fn fetch_from_target(
@@ -473,6 +916,37 @@ pub mod commands {
         Ok(())
     }
 
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn rebase_branch(
+        handle: tauri::AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+        onto: git::Oid,
+    ) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .rebase_branch(&project_id, &branch_id, onto)
+            .await?;
+        emit_vbranches(&handle, &project_id).await;
+        Ok(())
+    }
+
+    #[tauri::command(async)]
+    #[instrument(skip(handle), err(Debug))]
+    pub async fn land_branch(
+        handle: tauri::AppHandle,
+        project_id: ProjectId,
+        branch_id: BranchId,
+    ) -> Result<(), Error> {
+        handle
+            .state::<Controller>()
+            .land_branch(&project_id, &branch_id)
+            .await?;
+        emit_vbranches(&handle, &project_id).await;
+        Ok(())
+    }
+
     pub async fn update_commit_message(
         handle: tauri::AppHandle,
         project_id: ProjectId,
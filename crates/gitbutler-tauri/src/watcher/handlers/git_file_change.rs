@@ -4,7 +4,7 @@ use anyhow::{Context, Result};
 use gitbutler_core::{
     gb_repository, git, project_repository,
     projects::{self, ProjectId},
-    users,
+    users, virtual_branches,
 };
 use tauri::{AppHandle, Manager};
 
@@ -63,10 +63,30 @@ impl Handler {
             .context("failed to open project repository for project")?;
 
         match path.as_ref().to_str().unwrap() {
-            "FETCH_HEAD" => Ok(vec![
-                events::Event::Emit(app_events::Event::git_fetch(&project.id)),
-                events::Event::CalculateVirtualBranches(*project_id),
-            ]),
+            "FETCH_HEAD" => {
+                let mut events = vec![
+                    events::Event::Emit(app_events::Event::git_fetch(&project.id)),
+                    events::Event::CalculateVirtualBranches(*project_id),
+                ];
+
+                // let the ui know right away if the fetch moved the upstream past our base. this
+                // reads the branches state without waiting for operations on it, so failing to is
+                // no reason to hold back the other events.
+                match self.base_branch_behind(&project_repository) {
+                    Ok(behind) if behind > 0 => {
+                        events.push(events::Event::Emit(app_events::Event::base_branch_updated(
+                            &project.id,
+                            behind,
+                        )));
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        tracing::warn!(%project_id, ?error, "failed to get base branch data");
+                    }
+                }
+
+                Ok(events)
+            }
             "logs/HEAD" => Ok(vec![events::Event::Emit(app_events::Event::git_activity(
                 &project.id,
             ))]),
@@ -131,4 +151,21 @@ impl Handler {
             _ => Ok(vec![]),
         }
     }
+
+    // how many upstream commits the base branch is behind, 0 without a base branch
+    fn base_branch_behind(
+        &self,
+        project_repository: &project_repository::Repository,
+    ) -> Result<usize> {
+        let user = self.users.get_user()?;
+        let gb_repo = gb_repository::Repository::open(
+            &self.local_data_dir,
+            project_repository,
+            user.as_ref(),
+        )
+        .context("failed to open repository")?;
+        let base_branch = virtual_branches::get_base_branch_data(&gb_repo, project_repository)
+            .context("failed to get base branch data")?;
+        Ok(base_branch.map_or(0, |base_branch| base_branch.behind))
+    }
 }
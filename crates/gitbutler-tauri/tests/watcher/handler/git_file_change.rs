@@ -2,10 +2,13 @@ use std::fs;
 
 use anyhow::Result;
 use gitbutler_core::projects;
-use gitbutler_tauri::watcher::{handlers, handlers::git_file_change::Handler, Event};
+use gitbutler_tauri::{
+    events as app_events,
+    watcher::{handlers, handlers::git_file_change::Handler, Event},
+};
 use pretty_assertions::assert_eq;
 
-use gitbutler_testsupport::{Case, Suite};
+use gitbutler_testsupport::{virtual_branches::set_test_target, Case, Suite};
 
 #[test]
 fn flush_session() -> Result<()> {
@@ -97,3 +100,88 @@ fn flush_deletes_flush_file_without_session_to_flush() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn fetch_emits_base_branch_updated_when_upstream_advances() -> Result<()> {
+    let suite = Suite::default();
+    let Case {
+        project,
+        project_repository,
+        gb_repository,
+        ..
+    } = &suite.new_case();
+
+    set_test_target(gb_repository, project_repository)?;
+
+    let listener = Handler::new(
+        suite.local_app_data().into(),
+        suite.projects.clone(),
+        suite.users.clone(),
+    );
+
+    let result = listener.handle("FETCH_HEAD", &project.id)?;
+    assert_eq!(result.len(), 2, "base is up to date");
+
+    // move the remote tracking branch two commits ahead of the base
+    let repository = git2::Repository::open(&project.path)?;
+    let signature = git2::Signature::now("test", "test@email.com")?;
+    let mut head = repository
+        .find_reference("refs/remotes/origin/master")?
+        .peel_to_commit()?;
+    for message in ["first upstream commit", "second upstream commit"] {
+        let oid = repository.commit(
+            None,
+            &signature,
+            &signature,
+            message,
+            &head.tree()?,
+            &[&head],
+        )?;
+        head = repository.find_commit(oid)?;
+    }
+    repository.reference("refs/remotes/origin/master", head.id(), true, "fetch")?;
+
+    let result = listener.handle("FETCH_HEAD", &project.id)?;
+    assert_eq!(result.len(), 3);
+    assert_eq!(
+        result[2],
+        Event::Emit(app_events::Event::base_branch_updated(&project.id, 2))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fetch_emits_events_when_base_branch_lookup_fails() -> Result<()> {
+    let suite = Suite::default();
+    let Case {
+        project,
+        project_repository,
+        gb_repository,
+        ..
+    } = &suite.new_case();
+
+    set_test_target(gb_repository, project_repository)?;
+
+    // the base branch can't be looked up without the remote tracking branch of the target
+    git2::Repository::open(&project.path)?
+        .find_reference("refs/remotes/origin/master")?
+        .delete()?;
+
+    let listener = Handler::new(
+        suite.local_app_data().into(),
+        suite.projects.clone(),
+        suite.users.clone(),
+    );
+
+    let result = listener.handle("FETCH_HEAD", &project.id)?;
+    assert_eq!(
+        result,
+        vec![
+            Event::Emit(app_events::Event::git_fetch(&project.id)),
+            Event::CalculateVirtualBranches(project.id),
+        ]
+    );
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 pub const VAR_NO_CLEANUP: &str = "GITBUTLER_TESTS_NO_CLEANUP";
 
 mod test_project;
-pub use test_project::TestProject;
+pub use test_project::{TestProject, TestProjectBuilder};
 
 mod suite;
 pub use suite::*;
@@ -16,6 +16,77 @@ pub mod paths {
     }
 }
 
+pub mod secrets {
+    use std::{any::Any, cell::RefCell, collections::HashMap, sync::Once};
+
+    use keyring::credential::{Credential, CredentialApi, CredentialBuilderApi};
+
+    thread_local! {
+        static SECRETS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    }
+
+    struct Entry {
+        handle: String,
+    }
+
+    impl CredentialApi for Entry {
+        fn set_password(&self, password: &str) -> keyring::Result<()> {
+            SECRETS.with(|secrets| {
+                secrets
+                    .borrow_mut()
+                    .insert(self.handle.clone(), password.to_string())
+            });
+            Ok(())
+        }
+
+        fn get_password(&self) -> keyring::Result<String> {
+            SECRETS
+                .with(|secrets| secrets.borrow().get(&self.handle).cloned())
+                .ok_or(keyring::Error::NoEntry)
+        }
+
+        fn delete_password(&self) -> keyring::Result<()> {
+            SECRETS
+                .with(|secrets| secrets.borrow_mut().remove(&self.handle))
+                .map(|_| ())
+                .ok_or(keyring::Error::NoEntry)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    struct Builder;
+
+    impl CredentialBuilderApi for Builder {
+        fn build(
+            &self,
+            _target: Option<&str>,
+            service: &str,
+            user: &str,
+        ) -> keyring::Result<Box<Credential>> {
+            Ok(Box::new(Entry {
+                handle: format!("{service}/{user}"),
+            }))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// Keeps the secrets of [`gitbutler_core::secret`] in memory for the rest of the process,
+    /// so tests don't touch the keychain of the machine they run on. Secrets are kept per
+    /// thread, which keeps tests that run in parallel from seeing each other's secrets.
+    pub fn setup_in_memory() {
+        static SETUP: Once = Once::new();
+        SETUP.call_once(|| {
+            keyring::set_default_credential_builder(Box::new(Builder));
+        });
+    }
+}
+
 pub mod virtual_branches {
     use gitbutler_core::{
         gb_repository, project_repository,
@@ -27,10 +27,40 @@ impl Drop for TestProject {
 
 impl Default for TestProject {
     fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Configures the tree of the initial commit of a [`TestProject`].
+#[derive(Default)]
+pub struct TestProjectBuilder {
+    files: Vec<(path::PathBuf, String)>,
+}
+
+impl TestProjectBuilder {
+    /// Adds a file which is written to the working directory and included in the initial commit.
+    pub fn file<P: AsRef<path::Path>>(mut self, path: P, contents: &str) -> Self {
+        self.files
+            .push((path.as_ref().to_path_buf(), contents.to_string()));
+        self
+    }
+
+    pub fn build(self) -> TestProject {
         let local_tmp = temp_dir();
         let local_repository = git::Repository::init_opts(local_tmp.path(), &init_opts())
             .expect("failed to init repository");
+        for (path, contents) in &self.files {
+            let path = local_tmp.path().join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("failed to create dir");
+            }
+            std::fs::write(path, contents).expect("failed to write file");
+        }
         let mut index = local_repository.index().expect("failed to get index");
+        index
+            .add_all(["."], git2::IndexAddOption::DEFAULT, None)
+            .expect("failed to add all");
+        index.write().expect("failed to write index");
         let oid = index.write_tree().expect("failed to write tree");
         let signature = git::Signature::now("test", "test@email.com").unwrap();
         local_repository
@@ -72,7 +102,7 @@ impl Default for TestProject {
                 .expect("failed to push");
         }
 
-        Self {
+        TestProject {
             local_repository,
             local_tmp: Some(local_tmp),
             remote_repository,
@@ -82,6 +112,11 @@ impl Default for TestProject {
 }
 
 impl TestProject {
+    /// Starts a project whose initial commit contains the files added to the builder.
+    pub fn builder() -> TestProjectBuilder {
+        TestProjectBuilder::default()
+    }
+
     pub fn path(&self) -> &std::path::Path {
         self.local_repository.workdir().unwrap()
     }
@@ -98,6 +133,48 @@ impl TestProject {
             .unwrap();
     }
 
+    /// pushes master together with all local tags
+    pub fn push_with_tags(&self) {
+        let refspecs = self
+            .tags()
+            .into_iter()
+            .map(|tag| format!("refs/tags/{tag}:refs/tags/{tag}"))
+            .chain(std::iter::once(
+                "refs/heads/master:refs/heads/master".to_string(),
+            ))
+            .collect::<Vec<_>>();
+        let mut origin = self.local_repository.find_remote("origin").unwrap();
+        origin
+            .push(
+                &refspecs.iter().map(String::as_str).collect::<Vec<_>>(),
+                None,
+            )
+            .unwrap();
+    }
+
+    /// deletes the branch in the remote repository, as if someone else deleted it
+    pub fn delete_remote_branch(&self, name: &str) {
+        self.remote_repository
+            .find_reference(&format!("refs/heads/{name}").parse().unwrap())
+            .expect("failed to find remote branch")
+            .delete()
+            .expect("failed to delete remote branch");
+    }
+
+    /// creates an annotated tag if `message` is given, a lightweight one otherwise
+    pub fn tag(&self, name: &str, oid: git::Oid, message: Option<&str>) {
+        let signature = git::Signature::now("test", "test@email.com").unwrap();
+        self.local_repository
+            .tag(name, oid, &signature, message, false)
+            .expect("failed to create tag");
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        self.local_repository
+            .tag_names()
+            .expect("failed to list tags")
+    }
+
     /// git add -A
     /// git reset --hard <oid>
     pub fn reset_hard(&self, oid: Option<git::Oid>) {
@@ -245,6 +322,44 @@ impl TestProject {
         self.local_repository.find_commit(oid)
     }
 
+    /// the commit `refs/heads/<name>` points to
+    pub fn branch_head(&self, name: &str) -> git::Oid {
+        let refname: git::Refname = format!("refs/heads/{name}").parse().unwrap();
+        self.local_repository
+            .find_branch(&refname)
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+    }
+
+    /// the contents of `path` at `refs/heads/<name>` in the remote repository
+    pub fn remote_file(&self, name: &str, path: &str) -> String {
+        let refname: git::Refname = format!("refs/heads/{name}").parse().unwrap();
+        let tree = self
+            .remote_repository
+            .find_branch(&refname)
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .tree()
+            .unwrap();
+        let entry = tree.get_path(path).unwrap();
+        let blob = self.remote_repository.find_blob(entry.id()).unwrap();
+        String::from_utf8(blob.content().to_vec()).unwrap()
+    }
+
+    /// the commit `refs/remotes/<remote>/<name>` points to
+    pub fn remote_branch_head(&self, remote: &str, name: &str) -> git::Oid {
+        let refname: git::Refname = format!("refs/remotes/{remote}/{name}").parse().unwrap();
+        self.local_repository
+            .find_branch(&refname)
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+    }
+
     pub fn checkout_commit(&self, commit_oid: git::Oid) {
         let commit = self.local_repository.find_commit(commit_oid).unwrap();
         let commit_tree = commit.tree().unwrap();
@@ -324,6 +439,15 @@ impl TestProject {
             .expect("failed to read references")
     }
 
+    /// the references of the remote repository
+    pub fn remote_references(&self) -> Vec<git::Reference> {
+        self.remote_repository
+            .references()
+            .expect("failed to get references")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("failed to read references")
+    }
+
     pub fn add_submodule(&self, url: &git::Url, path: &path::Path) {
         let mut submodule = self.local_repository.add_submodule(url, path).unwrap();
         let repo = submodule.open().unwrap();
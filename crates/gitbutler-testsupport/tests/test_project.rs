@@ -0,0 +1,38 @@
+use gitbutler_testsupport::TestProject;
+
+mod tag {
+    use super::*;
+
+    #[test]
+    fn annotated() {
+        let test_project = TestProject::default();
+        let oid = test_project.commit_all("release");
+
+        test_project.tag("v1.0.0", oid, Some("first release"));
+
+        assert_eq!(test_project.tags(), vec!["v1.0.0".to_string()]);
+    }
+
+    #[test]
+    fn lightweight() {
+        let test_project = TestProject::default();
+        let oid = test_project.commit_all("release");
+
+        test_project.tag("v1.0.0", oid, None);
+        test_project.push_with_tags();
+
+        assert_eq!(test_project.tags(), vec!["v1.0.0".to_string()]);
+    }
+}
+
+#[test]
+fn branch_heads_match_after_push() {
+    let test_project = TestProject::default();
+    std::fs::write(test_project.path().join("file.txt"), "content").unwrap();
+    let oid = test_project.commit_all("commit");
+
+    test_project.push();
+
+    assert_eq!(test_project.branch_head("master"), oid);
+    assert_eq!(test_project.remote_branch_head("origin", "master"), oid);
+}